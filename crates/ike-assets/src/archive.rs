@@ -0,0 +1,551 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::SystemTime,
+};
+
+use crate::{not_found, AssetIo};
+
+/// Archives start with the magic followed by the version, the number of entries and the
+/// index, every entry being the length of its path, the path, the offset and size of its
+/// stored data, its uncompressed size, its [`Compression`] and the [`content_hash`] of its
+/// uncompressed data, all little endian.
+pub const ARCHIVE_MAGIC: [u8; 4] = *b"IKEA";
+pub const ARCHIVE_VERSION: u32 = 2;
+
+// the size of an index entry with an empty path
+const INDEX_ENTRY_SIZE: u64 = 4 + 8 + 8 + 8 + 1 + 4;
+
+/// Hash of the content of an archive entry, a crc32.
+#[inline]
+pub fn content_hash(bytes: &[u8]) -> u32 {
+    crc32fast::hash(bytes)
+}
+
+#[inline]
+fn read_u8(reader: &mut impl Read) -> io::Result<u8> {
+    let mut bytes = [0; 1];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes[0])
+}
+
+#[inline]
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+#[inline]
+fn read_u64(reader: &mut impl Read) -> io::Result<u64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+#[inline]
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+// paths are stored with `/` separators on every platform
+#[inline]
+fn archive_path(path: &Path) -> String {
+    let components: Vec<_> = path
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect();
+
+    components.join("/")
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Compression {
+    None,
+    Deflate,
+}
+
+impl Default for Compression {
+    #[inline]
+    fn default() -> Self {
+        Self::Deflate
+    }
+}
+
+impl Compression {
+    #[inline]
+    fn to_u8(self) -> u8 {
+        match self {
+            Self::None => 0,
+            Self::Deflate => 1,
+        }
+    }
+
+    #[inline]
+    fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::None),
+            1 => Some(Self::Deflate),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn compress(self, bytes: &[u8]) -> Option<Vec<u8>> {
+        match self {
+            Self::None => None,
+            Self::Deflate => Some(miniz_oxide::deflate::compress_to_vec(bytes, 6)),
+        }
+    }
+
+    // the size and hash are checked by the caller, inflating stops past `size` so corrupt
+    // data can't use more memory than the entry claims
+    #[inline]
+    fn decompress(self, bytes: Vec<u8>, size: u64) -> io::Result<Vec<u8>> {
+        match self {
+            Self::None => Ok(bytes),
+            Self::Deflate => {
+                // miniz_oxide doubles its buffer starting from twice the input size, and fails
+                // with `HasMoreOutput` once doubling passes the limit even if the data would
+                // fit, so the limit is the first of those sizes larger than `size`
+                let size = usize::try_from(size).unwrap_or(usize::MAX);
+                let mut limit = bytes.len().saturating_mul(2).max(1);
+
+                while limit <= size && limit < usize::MAX {
+                    limit = limit.saturating_mul(2);
+                }
+
+                miniz_oxide::inflate::decompress_to_vec_with_limit(&bytes, limit)
+                    .map_err(|status| invalid_data(format!("inflate failed: {:?}", status)))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ArchiveEntry {
+    offset: u64,
+    size: u64,
+    uncompressed_size: u64,
+    compression: Compression,
+    hash: u32,
+}
+
+impl ArchiveEntry {
+    /// Size of the stored, possibly compressed, data.
+    #[inline]
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    #[inline]
+    pub fn uncompressed_size(&self) -> u64 {
+        self.uncompressed_size
+    }
+
+    #[inline]
+    pub fn compression(&self) -> Compression {
+        self.compression
+    }
+
+    /// The [`content_hash`] of the uncompressed data.
+    #[inline]
+    pub fn hash(&self) -> u32 {
+        self.hash
+    }
+}
+
+trait ReadSeek: Read + Seek + Send {}
+
+impl<T: Read + Seek + Send> ReadSeek for T {}
+
+/// Reads assets from a single archive file built by [`ArchiveBuilder`], checking their
+/// hashes as they're read.
+pub struct ArchiveIo {
+    reader: Mutex<Box<dyn ReadSeek>>,
+    entries: HashMap<PathBuf, ArchiveEntry>,
+}
+
+impl ArchiveIo {
+    #[inline]
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::from_reader(BufReader::new(File::open(path)?))
+    }
+
+    /// Reads the index of the archive, failing if it doesn't fit in the stream.
+    #[inline]
+    pub fn from_reader(mut reader: impl Read + Seek + Send + 'static) -> io::Result<Self> {
+        let len = reader.seek(SeekFrom::End(0))?;
+        reader.seek(SeekFrom::Start(0))?;
+
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+
+        if magic != ARCHIVE_MAGIC {
+            return Err(invalid_data("not an archive"));
+        }
+
+        let version = read_u32(&mut reader)?;
+
+        if version != ARCHIVE_VERSION {
+            return Err(invalid_data(format!(
+                "unsupported archive version {}",
+                version
+            )));
+        }
+
+        let count = read_u32(&mut reader)?;
+
+        if count as u64 * INDEX_ENTRY_SIZE > len {
+            return Err(invalid_data("archive index is larger than the archive"));
+        }
+
+        let mut entries = HashMap::with_capacity(count as usize);
+
+        for _ in 0..count {
+            let path_len = read_u32(&mut reader)?;
+
+            if path_len as u64 > len {
+                return Err(invalid_data("archive path is larger than the archive"));
+            }
+
+            let mut path = vec![0; path_len as usize];
+            reader.read_exact(&mut path)?;
+
+            let path = String::from_utf8(path).map_err(|_| invalid_data("path isn't utf-8"))?;
+
+            let offset = read_u64(&mut reader)?;
+            let size = read_u64(&mut reader)?;
+            let uncompressed_size = read_u64(&mut reader)?;
+
+            if offset.checked_add(size).is_none_or(|end| end > len) {
+                return Err(invalid_data(format!("'{}' is outside the archive", path)));
+            }
+
+            let compression = read_u8(&mut reader)?;
+            let compression = Compression::from_u8(compression)
+                .ok_or_else(|| invalid_data(format!("unknown compression {}", compression)))?;
+
+            let entry = ArchiveEntry {
+                offset,
+                size,
+                uncompressed_size,
+                compression,
+                hash: read_u32(&mut reader)?,
+            };
+
+            entries.insert(PathBuf::from(path), entry);
+        }
+
+        Ok(Self {
+            reader: Mutex::new(Box::new(reader)),
+            entries,
+        })
+    }
+
+    #[inline]
+    pub fn entry(&self, path: impl AsRef<Path>) -> Option<&ArchiveEntry> {
+        self.entries.get(path.as_ref())
+    }
+
+    #[inline]
+    pub fn entries(&self) -> impl Iterator<Item = (&Path, &ArchiveEntry)> {
+        self.entries
+            .iter()
+            .map(|(path, entry)| (path.as_path(), entry))
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Reads every entry, returning those that can't be read or whose hash doesn't match.
+    #[inline]
+    pub fn verify(&self) -> Vec<(PathBuf, io::Error)> {
+        let mut paths: Vec<_> = self.entries.keys().collect();
+        paths.sort();
+
+        paths
+            .into_iter()
+            .filter_map(|path| match self.read(path) {
+                Ok(_) => None,
+                Err(error) => Some((path.clone(), error)),
+            })
+            .collect()
+    }
+}
+
+impl AssetIo for ArchiveIo {
+    #[inline]
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        let entry = match self.entries.get(path) {
+            Some(entry) => *entry,
+            None => return Err(not_found(path)),
+        };
+
+        let mut reader = self.reader.lock().unwrap();
+        reader.seek(SeekFrom::Start(entry.offset))?;
+
+        let mut bytes = vec![0; entry.size as usize];
+        reader.read_exact(&mut bytes)?;
+        drop(reader);
+
+        let bytes = entry
+            .compression
+            .decompress(bytes, entry.uncompressed_size)?;
+
+        if bytes.len() as u64 != entry.uncompressed_size || content_hash(&bytes) != entry.hash {
+            return Err(invalid_data(format!(
+                "'{}' doesn't match its hash",
+                path.display()
+            )));
+        }
+
+        Ok(bytes)
+    }
+
+    #[inline]
+    fn exists(&self, path: &Path) -> bool {
+        self.entries.contains_key(path)
+    }
+
+    /// Archives are read-only.
+    #[inline]
+    fn modified(&self, _path: &Path) -> Option<SystemTime> {
+        None
+    }
+}
+
+struct BuilderEntry {
+    path: String,
+    data: Vec<u8>,
+    uncompressed_size: u64,
+    compression: Compression,
+    hash: u32,
+}
+
+/// Builds an archive readable by [`ArchiveIo`], entries are compressed as they're added.
+#[derive(Default)]
+pub struct ArchiveBuilder {
+    compression: Compression,
+    entries: Vec<BuilderEntry>,
+}
+
+impl ArchiveBuilder {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the compression of entries added after this, defaults to [`Compression::Deflate`].
+    #[inline]
+    pub fn set_compression(&mut self, compression: Compression) -> &mut Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Adds `bytes` under `path`, replacing an entry with the same path.
+    ///
+    /// Entries compression doesn't make smaller are stored uncompressed.
+    #[inline]
+    pub fn add(&mut self, path: impl AsRef<Path>, bytes: Vec<u8>) -> &mut Self {
+        let path = archive_path(path.as_ref());
+        self.entries.retain(|entry| entry.path != path);
+
+        let hash = content_hash(&bytes);
+        let uncompressed_size = bytes.len() as u64;
+
+        let compressed = self
+            .compression
+            .compress(&bytes)
+            .filter(|data| data.len() < bytes.len());
+
+        let (compression, data) = match compressed {
+            Some(data) => (self.compression, data),
+            None => (Compression::None, bytes),
+        };
+
+        self.entries.push(BuilderEntry {
+            path,
+            data,
+            uncompressed_size,
+            compression,
+            hash,
+        });
+
+        self
+    }
+
+    /// Adds every file under `dir`, with paths relative to `dir`.
+    #[inline]
+    pub fn add_dir(&mut self, dir: impl AsRef<Path>) -> io::Result<&mut Self> {
+        let dir = dir.as_ref();
+        let mut stack = vec![dir.to_path_buf()];
+
+        while let Some(path) = stack.pop() {
+            for entry in std::fs::read_dir(path)? {
+                let path = entry?.path();
+
+                if path.is_dir() {
+                    stack.push(path);
+                } else {
+                    let bytes = std::fs::read(&path)?;
+                    self.add(path.strip_prefix(dir).unwrap(), bytes);
+                }
+            }
+        }
+
+        Ok(self)
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    #[inline]
+    pub fn write(&self, writer: &mut impl Write) -> io::Result<()> {
+        let index_size: usize = self
+            .entries
+            .iter()
+            .map(|entry| 4 + entry.path.len() + 8 + 8 + 8 + 1 + 4)
+            .sum();
+
+        let mut offset = (4 + 4 + 4 + index_size) as u64;
+
+        writer.write_all(&ARCHIVE_MAGIC)?;
+        writer.write_all(&ARCHIVE_VERSION.to_le_bytes())?;
+        writer.write_all(&(self.entries.len() as u32).to_le_bytes())?;
+
+        for entry in &self.entries {
+            writer.write_all(&(entry.path.len() as u32).to_le_bytes())?;
+            writer.write_all(entry.path.as_bytes())?;
+            writer.write_all(&offset.to_le_bytes())?;
+            writer.write_all(&(entry.data.len() as u64).to_le_bytes())?;
+            writer.write_all(&entry.uncompressed_size.to_le_bytes())?;
+            writer.write_all(&[entry.compression.to_u8()])?;
+            writer.write_all(&entry.hash.to_le_bytes())?;
+
+            offset += entry.data.len() as u64;
+        }
+
+        for entry in &self.entries {
+            writer.write_all(&entry.data)?;
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer)?;
+        writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    fn archive() -> Vec<u8> {
+        let mut builder = ArchiveBuilder::new();
+        builder.add("a.txt", b"aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa".to_vec());
+        builder.set_compression(Compression::None);
+        builder.add(Path::new("dir").join("b.txt"), b"b".to_vec());
+
+        let mut bytes = Vec::new();
+        builder.write(&mut bytes).unwrap();
+        bytes
+    }
+
+    fn open(bytes: Vec<u8>) -> io::Result<ArchiveIo> {
+        ArchiveIo::from_reader(Cursor::new(bytes))
+    }
+
+    #[test]
+    fn round_trip() {
+        let archive = open(archive()).unwrap();
+        assert_eq!(archive.len(), 2);
+
+        let a = archive.entry("a.txt").unwrap();
+        assert_eq!(a.compression(), Compression::Deflate);
+        assert_eq!(a.uncompressed_size(), 32);
+        assert!(a.size() < 32);
+
+        let b = archive.entry("dir/b.txt").unwrap();
+        assert_eq!(b.compression(), Compression::None);
+        assert_eq!(b.hash(), content_hash(b"b"));
+
+        let read = |path: &str| archive.read(Path::new(path)).unwrap();
+        assert_eq!(read("a.txt"), [b'a'; 32]);
+        assert_eq!(read("dir/b.txt"), b"b");
+
+        assert!(archive.exists(Path::new("dir/b.txt")));
+        assert!(!archive.exists(Path::new("c.txt")));
+        assert!(archive.verify().is_empty());
+    }
+
+    #[test]
+    fn corruption() {
+        // flipping a byte of the stored data fails its hash
+        let mut bytes = archive();
+        *bytes.last_mut().unwrap() ^= 1;
+
+        let archive = open(bytes).unwrap();
+        let error = archive.read(Path::new("dir/b.txt")).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+        let failed = archive.verify();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].0, Path::new("dir/b.txt"));
+
+        // truncated data is outside the archive
+        let mut bytes = self::archive();
+        bytes.pop();
+        assert!(open(bytes).is_err());
+
+        // as are entries claiming more than the archive holds
+        let mut bytes = self::archive();
+        bytes[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(open(bytes).is_err());
+
+        assert!(open(b"IKEA".to_vec()).is_err());
+        assert!(open(b"not an archive".to_vec()).is_err());
+    }
+
+    #[test]
+    fn inflate_limit() {
+        let mut builder = ArchiveBuilder::new();
+        builder.add("a.txt", vec![0; 4096]);
+
+        let mut bytes = Vec::new();
+        builder.write(&mut bytes).unwrap();
+
+        // the uncompressed size is the 8 bytes before the compression and hash
+        let index_end = 12 + INDEX_ENTRY_SIZE as usize + "a.txt".len();
+        let size = index_end - 8 - 1 - 4;
+        bytes[size..size + 8].copy_from_slice(&16u64.to_le_bytes());
+
+        // inflating stops at the claimed size instead of checking the size after
+        let archive = open(bytes).unwrap();
+        let error = archive.read(Path::new("a.txt")).unwrap_err();
+        assert!(error.to_string().contains("inflate failed"));
+    }
+}
@@ -1,10 +1,119 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    marker::PhantomData,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, Weak,
+    },
+};
 
-use crate::Handle;
+use crossbeam::queue::SegQueue;
 
+use crate::{AllocatedId, DropQueue, Handle, HandleRef, HandleUntyped};
+
+/// The memory held by an asset, used to measure [`Assets`] and enforce their budget.
+pub trait AssetSize {
+    /// Bytes of cpu memory held by the asset, including what it points to.
+    fn cpu_size(&self) -> usize;
+
+    /// Bytes of gpu memory held by the asset.
+    #[inline]
+    fn gpu_size(&self) -> usize {
+        0
+    }
+}
+
+/// Events hold weak handles so they don't keep assets alive.
+pub enum AssetEvent<T: 'static> {
+    Created(Handle<T>),
+    /// The asset was accessed mutably or replaced in place, like when it's reloaded.
+    Modified(Handle<T>),
+    Removed(Handle<T>),
+}
+
+impl<T> AssetEvent<T> {
+    #[inline]
+    pub fn handle(&self) -> &Handle<T> {
+        match self {
+            Self::Created(handle) | Self::Modified(handle) | Self::Removed(handle) => handle,
+        }
+    }
+}
+
+impl<T> Clone for AssetEvent<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        match self {
+            Self::Created(handle) => Self::Created(handle.clone()),
+            Self::Modified(handle) => Self::Modified(handle.clone()),
+            Self::Removed(handle) => Self::Removed(handle.clone()),
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for AssetEvent<T> {
+    #[inline]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Created(_) => "Created",
+            Self::Modified(_) => "Modified",
+            Self::Removed(_) => "Removed",
+        };
+
+        f.debug_tuple(name).field(self.handle()).finish()
+    }
+}
+
+/// Tracks which [`AssetEvent`]s of an [`Assets<T>`] have been read.
+pub struct AssetEventReader<T> {
+    next: usize,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for AssetEventReader<T> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            next: 0,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T> AssetEventReader<T> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+struct AssetEntry<T> {
+    asset: T,
+    // the strong handles of the asset, dangling if it was inserted with a weak handle
+    handle_ref: Weak<HandleRef>,
+    // the frame the asset was last accessed
+    last_used: AtomicU64,
+}
+
+/// Stores assets of type `T`, an asset is removed once every strong [`Handle`] to it is
+/// dropped, assets inserted with a weak handle are kept until they're removed.
+///
+/// With a [`budget`](Self::set_budget), the least recently used assets that can be loaded
+/// again from their path are evicted.
 pub struct Assets<T: 'static> {
-    inner: HashMap<Handle<T>, T>,
+    inner: HashMap<HandleUntyped, AssetEntry<T>>,
     next_id: u64,
+    drops: DropQueue,
+    frame: u64,
+    budget: Option<usize>,
+    // evicted assets still alive, and those accessed since which should be loaded again
+    evicted: Mutex<HashMap<HandleUntyped, Weak<HandleRef>>>,
+    reloads: SegQueue<HandleUntyped>,
+    // events sent this frame and the frame before, each buffer is preceded by `*_start` events
+    events: Vec<AssetEvent<T>>,
+    last_events: Vec<AssetEvent<T>>,
+    events_start: usize,
+    last_events_start: usize,
 }
 
 impl<T> Default for Assets<T> {
@@ -20,14 +129,24 @@ impl<T> Assets<T> {
         Self {
             inner: HashMap::new(),
             next_id: 0,
+            drops: DropQueue::default(),
+            frame: 0,
+            budget: None,
+            evicted: Mutex::new(HashMap::new()),
+            reloads: SegQueue::new(),
+            events: Vec::new(),
+            last_events: Vec::new(),
+            events_start: 0,
+            last_events_start: 0,
         }
     }
 
     #[inline]
     pub fn add(&mut self, asset: T) -> Handle<T> {
-        let handle = Handle::new(self.next_id);
+        let id = AllocatedId(self.next_id);
         self.next_id += 1;
 
+        let handle = Handle::strong(HandleUntyped::Allocated(id));
         self.insert(handle.clone(), asset);
 
         handle
@@ -35,21 +154,474 @@ impl<T> Assets<T> {
 
     #[inline]
     pub fn insert(&mut self, handle: Handle<T>, asset: T) {
-        self.inner.insert(handle, asset);
+        self.replace(&handle, asset);
+    }
+
+    /// Replaces the asset of `handle` in place keeping every handle to it valid, and returns
+    /// the old asset, inserts `asset` if there was none.
+    #[inline]
+    pub fn replace(&mut self, handle: &Handle<T>, asset: T) -> Option<T> {
+        let handle_ref = match handle.handle_ref() {
+            Some(handle_ref) => {
+                handle_ref.set_drop_queue(&self.drops);
+                Arc::downgrade(handle_ref)
+            }
+            None => match self.inner.get(handle.untyped()) {
+                Some(entry) => entry.handle_ref.clone(),
+                None => Weak::new(),
+            },
+        };
+
+        let entry = AssetEntry {
+            asset,
+            handle_ref,
+            last_used: AtomicU64::new(self.frame),
+        };
+
+        self.evicted.get_mut().unwrap().remove(handle.untyped());
+        let old = self.inner.insert(handle.untyped().clone(), entry);
+
+        if old.is_some() {
+            self.send_event(AssetEvent::Modified(handle.clone_weak()));
+        } else {
+            self.send_event(AssetEvent::Created(handle.clone_weak()));
+        }
+
+        old.map(|entry| entry.asset)
+    }
+
+    #[inline]
+    pub fn remove(&mut self, handle: &Handle<T>) -> Option<T> {
+        let entry = self.inner.remove(handle.untyped())?;
+        self.send_event(AssetEvent::Removed(handle.clone_weak()));
+
+        Some(entry.asset)
     }
 
+    #[inline]
+    pub fn contains(&self, handle: &Handle<T>) -> bool {
+        self.inner.contains_key(handle.untyped())
+    }
+
+    /// Accessing an evicted asset loads it again.
     #[inline]
     pub fn get(&self, handle: &Handle<T>) -> Option<&T> {
-        self.inner.get(handle)
+        match self.inner.get(handle.untyped()) {
+            Some(entry) => {
+                entry.last_used.store(self.frame, Ordering::Relaxed);
+                Some(&entry.asset)
+            }
+            None => {
+                self.request_reload(handle.untyped());
+                None
+            }
+        }
     }
 
+    /// Sends [`AssetEvent::Modified`] if the asset exists.
     #[inline]
     pub fn get_mut(&mut self, handle: &Handle<T>) -> Option<&mut T> {
-        self.inner.get_mut(handle)
+        if self.contains(handle) {
+            self.events.push(AssetEvent::Modified(handle.clone_weak()));
+        }
+
+        self.get_mut_untracked(handle)
+    }
+
+    /// Doesn't send [`AssetEvent::Modified`], for updating state cached inside the asset.
+    #[inline]
+    pub fn get_mut_untracked(&mut self, handle: &Handle<T>) -> Option<&mut T> {
+        if !self.contains(handle) {
+            self.request_reload(handle.untyped());
+            return None;
+        }
+
+        let entry = self.inner.get_mut(handle.untyped()).unwrap();
+        *entry.last_used.get_mut() = self.frame;
+
+        Some(&mut entry.asset)
+    }
+
+    #[inline]
+    fn request_reload(&self, id: &HandleUntyped) {
+        let mut evicted = self.evicted.lock().unwrap();
+
+        if let Some(handle_ref) = evicted.remove(id) {
+            if handle_ref.strong_count() > 0 {
+                self.reloads.push(id.clone());
+            }
+        }
+    }
+
+    /// Returns the evicted assets accessed since the last call, which should be loaded again.
+    #[inline]
+    pub fn take_reloads(&self) -> Vec<Handle<T>> {
+        let mut reloads = Vec::new();
+
+        while let Some(id) = self.reloads.pop() {
+            reloads.push(Handle::weak(id));
+        }
+
+        reloads
+    }
+
+    #[inline]
+    pub fn is_evicted(&self, handle: &Handle<T>) -> bool {
+        let evicted = self.evicted.lock().unwrap();
+        evicted.contains_key(handle.untyped())
+    }
+
+    #[inline]
+    pub fn budget(&self) -> Option<usize> {
+        self.budget
+    }
+
+    /// Sets the bytes of cpu and gpu memory the assets should fit in, `None` by default.
+    #[inline]
+    pub fn set_budget(&mut self, budget: Option<usize>) {
+        self.budget = budget;
+    }
+
+    #[inline]
+    pub fn cpu_size(&self) -> usize
+    where
+        T: AssetSize,
+    {
+        self.inner
+            .values()
+            .map(|entry| entry.asset.cpu_size())
+            .sum()
+    }
+
+    #[inline]
+    pub fn gpu_size(&self) -> usize
+    where
+        T: AssetSize,
+    {
+        self.inner
+            .values()
+            .map(|entry| entry.asset.gpu_size())
+            .sum()
+    }
+
+    /// Removes the least recently used assets that can be loaded again from their path until
+    /// the assets fit in the budget, assets used this frame or the last are kept.
+    ///
+    /// Evicted assets send [`AssetEvent::Removed`] and are loaded again when accessed,
+    /// returns the evicted handles.
+    #[inline]
+    pub fn evict(&mut self) -> Vec<Handle<T>>
+    where
+        T: AssetSize,
+    {
+        let budget = match self.budget {
+            Some(budget) => budget,
+            None => return Vec::new(),
+        };
+
+        let mut size = self.cpu_size() + self.gpu_size();
+
+        if size <= budget {
+            return Vec::new();
+        }
+
+        let mut candidates: Vec<_> = self
+            .inner
+            .iter()
+            .filter_map(|(id, entry)| {
+                let last_used = entry.last_used.load(Ordering::Relaxed);
+
+                let reloadable = matches!(id, HandleUntyped::Path(_));
+                let alive = entry.handle_ref.strong_count() > 0;
+
+                if reloadable && alive && last_used + 1 < self.frame {
+                    let size = entry.asset.cpu_size() + entry.asset.gpu_size();
+                    Some((last_used, id.clone(), size))
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        candidates.sort_by_key(|(last_used, _, _)| *last_used);
+
+        let mut evicted = Vec::new();
+
+        for (_, id, asset_size) in candidates {
+            if size <= budget {
+                break;
+            }
+
+            let entry = self.inner.remove(&id).unwrap();
+            let handle = Handle::weak(id.clone());
+
+            self.evicted.get_mut().unwrap().insert(id, entry.handle_ref);
+            self.send_event(AssetEvent::Removed(handle.clone()));
+
+            size -= asset_size;
+            evicted.push(handle);
+        }
+
+        evicted
+    }
+
+    /// Returns a strong handle to the asset of `handle`, keeping it alive.
+    ///
+    /// Dropping every strong handle to an asset inserted with a weak handle removes it.
+    #[inline]
+    pub fn get_handle(&mut self, handle: &Handle<T>) -> Option<Handle<T>> {
+        let entry = self.inner.get_mut(handle.untyped())?;
+
+        if let Some(handle_ref) = entry.handle_ref.upgrade() {
+            return Some(Handle::from_ref(handle_ref));
+        }
+
+        let strong = Handle::strong(handle.untyped().clone());
+        let handle_ref = strong.handle_ref().unwrap();
+        handle_ref.set_drop_queue(&self.drops);
+        entry.handle_ref = Arc::downgrade(handle_ref);
+
+        Some(strong)
     }
 
+    /// Iterates over the assets with weak handles, without counting them as used.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (Handle<T>, &T)> {
+        self.inner
+            .iter()
+            .map(|(id, entry)| (Handle::weak(id.clone()), &entry.asset))
+    }
+
+    /// Like [`iter`](Self::iter) with mutable assets, doesn't send [`AssetEvent::Modified`].
+    #[inline]
+    pub fn iter_mut_untracked(&mut self) -> impl Iterator<Item = (Handle<T>, &mut T)> {
+        self.inner
+            .iter_mut()
+            .map(|(id, entry)| (Handle::weak(id.clone()), &mut entry.asset))
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Removes the assets whose last strong handle was dropped since the last call.
     #[inline]
     pub fn clean(&mut self) {
-        self.inner.retain(|handle, _| handle.shared());
+        while let Some(id) = self.drops.pop() {
+            // a new strong handle may have been created since
+            let dropped = match self.inner.get(&id) {
+                Some(entry) => entry.handle_ref.strong_count() == 0,
+                None => false,
+            };
+
+            if dropped {
+                self.remove(&Handle::weak(id));
+            }
+        }
+
+        let evicted = self.evicted.get_mut().unwrap();
+        evicted.retain(|_, handle_ref| handle_ref.strong_count() > 0);
+    }
+
+    #[inline]
+    fn send_event(&mut self, event: AssetEvent<T>) {
+        self.events.push(event);
+    }
+
+    /// Returns the events `reader` hasn't read yet, events are kept for two frames so every
+    /// system running once a frame sees them.
+    #[inline]
+    pub fn read_events<'a>(
+        &'a self,
+        reader: &mut AssetEventReader<T>,
+    ) -> impl Iterator<Item = &'a AssetEvent<T>> {
+        let last = reader.next.saturating_sub(self.last_events_start);
+        let current = reader.next.saturating_sub(self.events_start);
+
+        reader.next = self.events_start + self.events.len();
+
+        let last = self.last_events.get(last..).unwrap_or(&[]);
+        let current = self.events.get(current..).unwrap_or(&[]);
+
+        last.iter().chain(current)
+    }
+
+    /// Drops the events of the previous frame and starts a new frame for tracking which
+    /// assets are used, called once a frame by [`asset_system`](crate::asset_system).
+    #[inline]
+    pub fn update_events(&mut self) {
+        self.frame += 1;
+
+        self.last_events_start = self.events_start;
+        self.events_start += self.events.len();
+        self.last_events = std::mem::take(&mut self.events);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read<T>(assets: &Assets<T>, reader: &mut AssetEventReader<T>) -> Vec<&'static str> {
+        let events = assets.read_events(reader).map(|event| match event {
+            AssetEvent::Created(_) => "created",
+            AssetEvent::Modified(_) => "modified",
+            AssetEvent::Removed(_) => "removed",
+        });
+
+        events.collect()
+    }
+
+    #[test]
+    fn events() {
+        let mut assets = Assets::new();
+        let mut reader = AssetEventReader::new();
+        let mut late = AssetEventReader::new();
+
+        let a = assets.add(1);
+        assets.get_mut(&a).unwrap();
+        assert_eq!(read(&assets, &mut reader), ["created", "modified"]);
+        assert!(read(&assets, &mut reader).is_empty());
+
+        // events are kept for the next frame
+        assets.update_events();
+        *assets.get_mut_untracked(&a).unwrap() = 2;
+        assets.remove(&a);
+        assert_eq!(read(&assets, &mut reader), ["removed"]);
+
+        // and dropped the frame after
+        assets.update_events();
+        assert_eq!(read(&assets, &mut late), ["removed"]);
+
+        assets.update_events();
+        assert!(read(&assets, &mut late).is_empty());
+        assert!(read(&assets, &mut reader).is_empty());
+    }
+
+    #[test]
+    fn handles() {
+        let mut assets = Assets::new();
+
+        let a = assets.add(1);
+        let weak = a.clone_weak();
+        assert!(a.is_strong());
+        assert!(weak.is_weak());
+
+        // ids chosen by the user never collide with allocated ones
+        let b = Handle::weak(a.untyped().clone());
+        let id = Handle::weak(0u64);
+        assets.insert(id.clone(), 2);
+        assert_eq!(assets.get(&b), Some(&1));
+        assert_eq!(assets.get(&id), Some(&2));
+
+        // weak handles don't keep assets alive
+        drop(a);
+        assets.clean();
+        assert!(!assets.contains(&weak));
+
+        // assets inserted with a weak handle live until removed, or until the strong
+        // handles created for them are dropped
+        assets.clean();
+        assert!(assets.contains(&id));
+
+        let strong = assets.get_handle(&id).unwrap();
+        assert!(strong.is_strong());
+        drop(strong);
+
+        // a strong handle created before `clean` keeps the asset alive
+        let strong = assets.get_handle(&id).unwrap();
+        assets.clean();
+        assert!(assets.contains(&id));
+
+        drop(strong);
+        assets.clean();
+        assert!(!assets.contains(&id));
+    }
+
+    struct Blob(usize);
+
+    impl AssetSize for Blob {
+        fn cpu_size(&self) -> usize {
+            self.0
+        }
+    }
+
+    // a strong handle to `path`, like the ones of loaded assets
+    fn path(path: &str) -> Handle<Blob> {
+        Handle::strong(Handle::<Blob>::weak(path).untyped().clone())
+    }
+
+    #[test]
+    fn evict() {
+        let mut assets = Assets::new();
+        let mut reader = AssetEventReader::new();
+
+        let a = path("a");
+        let b = path("b");
+        let c = path("c");
+        assets.insert(a.clone(), Blob(10));
+        assets.insert(b.clone(), Blob(10));
+        assets.insert(c.clone(), Blob(10));
+
+        // assets that can't be loaded again are never evicted
+        let added = assets.add(Blob(10));
+        assets.insert(Handle::weak("unused"), Blob(10));
+
+        assert!(assets.evict().is_empty());
+
+        // assets used this frame or the last are kept
+        assets.set_budget(Some(45));
+        assert!(assets.evict().is_empty());
+
+        assets.update_events();
+        assets.get(&c);
+        assets.update_events();
+        assets.get(&b);
+        assets.update_events();
+        read(&assets, &mut reader);
+
+        // the least recently used go first, until the assets fit
+        let evicted = assets.evict();
+        assert_eq!(evicted.len(), 1);
+        assert_eq!(evicted[0].untyped(), a.untyped());
+        assert!(assets.is_evicted(&a));
+        assert!(!assets.contains(&a));
+        assert_eq!(read(&assets, &mut reader), ["removed"]);
+
+        assets.set_budget(Some(25));
+        let evicted = assets.evict();
+        assert_eq!(evicted.len(), 1);
+        assert_eq!(evicted[0].untyped(), c.untyped());
+
+        // the rest can't be evicted, so the assets stay over budget
+        assert!(assets.evict().is_empty());
+        assert_eq!(assets.cpu_size(), 30);
+        assert!(assets.contains(&added));
+
+        // accessing an evicted asset requests it once
+        assert!(assets.get(&a).is_none());
+        assert!(assets.get(&a).is_none());
+        let reloads = assets.take_reloads();
+        assert_eq!(reloads.len(), 1);
+        assert_eq!(reloads[0].untyped(), a.untyped());
+        assert!(assets.take_reloads().is_empty());
+
+        // evicted assets without strong handles aren't loaded again
+        drop(c);
+        assets.clean();
+        assert!(assets.get(&path("c")).is_none());
+        assert!(assets.take_reloads().is_empty());
+
+        // and loading one again keeps it from being evicted this frame
+        assets.insert(a.clone(), Blob(10));
+        assert!(!assets.is_evicted(&a));
+        assets.set_budget(Some(0));
+        assert_eq!(assets.evict().len(), 0);
     }
 }
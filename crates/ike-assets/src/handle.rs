@@ -1,15 +1,47 @@
-use std::{hash::Hash, marker::PhantomData, path::PathBuf, sync::Arc};
+use std::{
+    hash::Hash,
+    marker::PhantomData,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, OnceLock,
+    },
+};
+
+use crossbeam::queue::SegQueue;
+
+use crate::AssetPath;
+
+/// Id of an asset allocated by [`Assets::add`](crate::Assets::add), these can't be created
+/// any other way so they never collide with user chosen ids.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AllocatedId(pub(crate) u64);
+
+impl AllocatedId {
+    #[inline]
+    pub fn get(self) -> u64 {
+        self.0
+    }
+}
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum HandleUntyped {
-    Path(PathBuf),
+    Path(AssetPath),
     Id(u64),
+    Allocated(AllocatedId),
 }
 
 pub trait IntoHandleUntyped {
     fn into_handle_untyped(self) -> HandleUntyped;
 }
 
+impl IntoHandleUntyped for HandleUntyped {
+    #[inline]
+    fn into_handle_untyped(self) -> HandleUntyped {
+        self
+    }
+}
+
 impl IntoHandleUntyped for u64 {
     #[inline]
     fn into_handle_untyped(self) -> HandleUntyped {
@@ -20,80 +52,150 @@ impl IntoHandleUntyped for u64 {
 impl IntoHandleUntyped for &str {
     #[inline]
     fn into_handle_untyped(self) -> HandleUntyped {
-        HandleUntyped::Path(self.into())
+        HandleUntyped::Path(AssetPath::parse(self))
     }
 }
 
 impl IntoHandleUntyped for PathBuf {
+    #[inline]
+    fn into_handle_untyped(self) -> HandleUntyped {
+        HandleUntyped::Path(AssetPath::new(self))
+    }
+}
+
+impl IntoHandleUntyped for AssetPath {
     #[inline]
     fn into_handle_untyped(self) -> HandleUntyped {
         HandleUntyped::Path(self)
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
-enum Inner {
-    Tracked(Arc<HandleUntyped>),
-    Untracked(HandleUntyped),
+pub(crate) type DropQueue = Arc<SegQueue<HandleUntyped>>;
+
+// shared by every strong handle to an asset, pushes the id to the drop queue of the
+// `Assets` it's inserted in when the last one is dropped
+pub(crate) struct HandleRef {
+    id: HandleUntyped,
+    drops: OnceLock<DropQueue>,
+    // set when returned by `AssetServer::load`
+    requested: AtomicBool,
+    // the sub-assets of the same file no one requested, kept alive by requested handles
+    keep_alive: Mutex<Option<Arc<Vec<Arc<HandleRef>>>>>,
 }
 
-impl Inner {
+impl HandleRef {
     #[inline]
-    fn untyped(&self) -> &HandleUntyped {
-        match self {
-            Self::Tracked(handle) => handle,
-            Self::Untracked(handle) => handle,
+    pub(crate) fn new(id: HandleUntyped) -> Self {
+        Self {
+            id,
+            drops: OnceLock::new(),
+            requested: AtomicBool::new(false),
+            keep_alive: Mutex::new(None),
         }
     }
+
+    #[inline]
+    pub(crate) fn id(&self) -> &HandleUntyped {
+        &self.id
+    }
+
+    #[inline]
+    pub(crate) fn set_drop_queue(&self, drops: &DropQueue) {
+        let _ = self.drops.set(drops.clone());
+    }
+
+    #[inline]
+    pub(crate) fn set_requested(&self) {
+        self.requested.store(true, Ordering::Release);
+    }
+
+    #[inline]
+    pub(crate) fn is_requested(&self) -> bool {
+        self.requested.load(Ordering::Acquire)
+    }
+
+    #[inline]
+    pub(crate) fn set_keep_alive(&self, keep_alive: Arc<Vec<Arc<HandleRef>>>) {
+        *self.keep_alive.lock().unwrap() = Some(keep_alive);
+    }
 }
+
+impl Drop for HandleRef {
+    #[inline]
+    fn drop(&mut self) {
+        if let Some(drops) = self.drops.get() {
+            drops.push(self.id.clone());
+        }
+    }
+}
+
+/// Refers to an asset in [`Assets<T>`](crate::Assets).
+///
+/// Strong handles keep the asset alive, it's removed once the last strong handle is dropped.
+/// Weak handles only refer to it.
 pub struct Handle<T: 'static> {
-    inner: Inner,
-    marker: PhantomData<&'static T>,
+    id: HandleUntyped,
+    strong: Option<Arc<HandleRef>>,
+    marker: PhantomData<fn() -> T>,
 }
 
 impl<T> Handle<T> {
     #[inline]
-    pub fn new<U: IntoHandleUntyped>(untyped: U) -> Self {
+    pub fn weak<U: IntoHandleUntyped>(id: U) -> Self {
         Self {
-            inner: Inner::Tracked(Arc::new(untyped.into_handle_untyped())),
+            id: id.into_handle_untyped(),
+            strong: None,
             marker: PhantomData,
         }
     }
 
     #[inline]
-    pub fn new_untracked<U: IntoHandleUntyped>(untyped: U) -> Self {
+    pub const fn weak_from_u64(id: u64) -> Self {
         Self {
-            inner: Inner::Untracked(untyped.into_handle_untyped()),
+            id: HandleUntyped::Id(id),
+            strong: None,
             marker: PhantomData,
         }
     }
 
     #[inline]
-    pub const fn untracked_from_u64(id: u64) -> Self {
+    pub(crate) fn from_ref(handle_ref: Arc<HandleRef>) -> Self {
         Self {
-            inner: Inner::Untracked(HandleUntyped::Id(id)),
+            id: handle_ref.id.clone(),
+            strong: Some(handle_ref),
             marker: PhantomData,
         }
     }
 
+    #[inline]
+    pub(crate) fn strong(id: HandleUntyped) -> Self {
+        Self::from_ref(Arc::new(HandleRef::new(id)))
+    }
+
+    #[inline]
+    pub(crate) fn handle_ref(&self) -> Option<&Arc<HandleRef>> {
+        self.strong.as_ref()
+    }
+
     #[inline]
     pub fn untyped(&self) -> &HandleUntyped {
-        self.inner.untyped()
+        &self.id
     }
 
     #[inline]
-    pub fn tracked(&self) -> Option<&Arc<HandleUntyped>> {
-        match self.inner {
-            Inner::Tracked(ref tracked) => Some(tracked),
-            _ => None,
-        }
+    pub fn is_strong(&self) -> bool {
+        self.strong.is_some()
     }
 
     #[inline]
-    pub fn shared(&self) -> bool {
-        self.tracked()
-            .map(|tracked| Arc::strong_count(tracked) > 1)
-            .unwrap_or(true)
+    pub fn is_weak(&self) -> bool {
+        self.strong.is_none()
+    }
+
+    /// Returns a weak handle to the same asset.
+    #[inline]
+    pub fn clone_weak(&self) -> Self {
+        Self::weak(self.id.clone())
     }
 }
 
@@ -117,7 +219,8 @@ impl<T> Clone for Handle<T> {
     #[inline]
     fn clone(&self) -> Self {
         Self {
-            inner: self.inner.clone(),
+            id: self.id.clone(),
+            strong: self.strong.clone(),
             marker: PhantomData,
         }
     }
@@ -126,6 +229,8 @@ impl<T> Clone for Handle<T> {
 impl<T> std::fmt::Debug for Handle<T> {
     #[inline]
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.inner.fmt(f)
+        let name = if self.is_strong() { "Strong" } else { "Weak" };
+
+        f.debug_tuple(name).field(&self.id).finish()
     }
 }
@@ -0,0 +1,235 @@
+use std::{
+    ffi::OsString,
+    io,
+    path::{Path, PathBuf},
+};
+
+use serde::de::DeserializeOwned;
+
+use crate::content_hash;
+
+/// Converts source files with one of `extensions` into processed files, which are loaded in
+/// their place by the [`AssetLoader`](crate::AssetLoader) for [`processed_extension`](Self::processed_extension).
+///
+/// Settings are read from a toml `.meta` file next to the source, `"brick.png.meta"` for
+/// `"brick.png"`, and are the default without one.
+pub trait AssetImporter: Send + Sync + 'static {
+    type Settings: DeserializeOwned + Default + Send + Sync + 'static;
+
+    fn extensions(&self) -> &[&str];
+
+    fn processed_extension(&self) -> &str;
+
+    /// Changing the version re-imports every file imported by the importer.
+    #[inline]
+    fn version(&self) -> u32 {
+        0
+    }
+
+    fn import(&self, bytes: &[u8], settings: &Self::Settings) -> anyhow::Result<Vec<u8>>;
+}
+
+pub const META_EXTENSION: &str = "meta";
+
+/// The path of the `.meta` file holding the import settings of `path`.
+#[inline]
+pub fn meta_path(path: impl AsRef<Path>) -> PathBuf {
+    let mut meta = OsString::from(path.as_ref());
+    meta.push(".");
+    meta.push(META_EXTENSION);
+    PathBuf::from(meta)
+}
+
+pub(crate) trait ErasedAssetImporter: Send + Sync + 'static {
+    fn processed_extension(&self) -> &str;
+
+    fn version(&self) -> u32;
+
+    fn import(&self, bytes: &[u8], meta: Option<&[u8]>) -> anyhow::Result<Vec<u8>>;
+}
+
+impl<T: AssetImporter> ErasedAssetImporter for T {
+    #[inline]
+    fn processed_extension(&self) -> &str {
+        AssetImporter::processed_extension(self)
+    }
+
+    #[inline]
+    fn version(&self) -> u32 {
+        AssetImporter::version(self)
+    }
+
+    #[inline]
+    fn import(&self, bytes: &[u8], meta: Option<&[u8]>) -> anyhow::Result<Vec<u8>> {
+        let settings = match meta {
+            Some(meta) => toml::from_slice(meta)
+                .map_err(|error| anyhow::anyhow!("invalid import settings: {}", error))?,
+            None => T::Settings::default(),
+        };
+
+        AssetImporter::import(self, bytes, &settings)
+    }
+}
+
+// the name of the processed file, changes with the source, the settings and the importer,
+// the path of the source is hashed too so two files only collide if both hashes do
+#[inline]
+fn cache_key(
+    importer: &dyn ErasedAssetImporter,
+    path: &Path,
+    bytes: &[u8],
+    meta: Option<&[u8]>,
+) -> String {
+    let mut settings = crc32fast::Hasher::new();
+    settings.update(&importer.version().to_le_bytes());
+    settings.update(importer.processed_extension().as_bytes());
+    settings.update(meta.unwrap_or_default());
+
+    let path = path.to_string_lossy().replace('\\', "/");
+
+    format!(
+        "{:08x}{:08x}{:08x}.{}",
+        content_hash(path.as_bytes()),
+        content_hash(bytes),
+        settings.finalize(),
+        importer.processed_extension()
+    )
+}
+
+/// Imports `bytes` read from `path`, reusing the processed file in `cache` when the source
+/// and settings haven't changed.
+#[inline]
+pub(crate) fn import_cached(
+    importer: &dyn ErasedAssetImporter,
+    cache: Option<&Path>,
+    path: &Path,
+    bytes: &[u8],
+    meta: Option<&[u8]>,
+) -> anyhow::Result<Vec<u8>> {
+    let cache = match cache {
+        Some(cache) => cache.join(cache_key(importer, path, bytes, meta)),
+        None => return importer.import(bytes, meta),
+    };
+
+    if let Ok(processed) = std::fs::read(&cache) {
+        return Ok(processed);
+    }
+
+    let processed = importer.import(bytes, meta)?;
+
+    // failing to write the cache only means importing again next time
+    let _ = write_cache(&cache, &processed);
+
+    Ok(processed)
+}
+
+// written next to the cached file and renamed, so a cached file is never partial
+#[inline]
+fn write_cache(cache: &Path, processed: &[u8]) -> io::Result<()> {
+    let temp = cache.with_extension("tmp");
+
+    if let Some(parent) = cache.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    std::fs::write(&temp, processed)?;
+    std::fs::rename(&temp, cache)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Default, Deserialize)]
+    struct Settings {
+        #[serde(default)]
+        suffix: String,
+    }
+
+    #[derive(Default)]
+    struct Importer {
+        version: u32,
+        imports: AtomicUsize,
+    }
+
+    impl AssetImporter for Importer {
+        type Settings = Settings;
+
+        fn extensions(&self) -> &[&str] {
+            &["txt"]
+        }
+
+        fn processed_extension(&self) -> &str {
+            "ptxt"
+        }
+
+        fn version(&self) -> u32 {
+            self.version
+        }
+
+        fn import(&self, bytes: &[u8], settings: &Settings) -> anyhow::Result<Vec<u8>> {
+            self.imports.fetch_add(1, Ordering::Relaxed);
+
+            let mut processed = bytes.to_vec();
+            processed.extend_from_slice(settings.suffix.as_bytes());
+            Ok(processed)
+        }
+    }
+
+    #[test]
+    fn cache_keys() {
+        let importer = Importer::default();
+        let key = |path: &str, bytes: &[u8], meta: Option<&[u8]>| {
+            cache_key(&importer, Path::new(path), bytes, meta)
+        };
+
+        let a = key("a.txt", b"a", None);
+        assert!(a.ends_with(".ptxt"));
+        assert_eq!(a, key("a.txt", b"a", None));
+
+        assert_ne!(a, key("b.txt", b"a", None));
+        assert_ne!(a, key("a.txt", b"b", None));
+        assert_ne!(a, key("a.txt", b"a", Some(b"suffix = '!'")));
+
+        let newer = Importer {
+            version: 1,
+            ..Default::default()
+        };
+        assert_ne!(a, cache_key(&newer, Path::new("a.txt"), b"a", None));
+    }
+
+    #[test]
+    fn import_cache() {
+        let cache = std::env::temp_dir().join("ike_import_cache_test");
+        let _ = std::fs::remove_dir_all(&cache);
+
+        let importer = Importer::default();
+        let import = |bytes: &[u8], meta: Option<&[u8]>| {
+            import_cached(&importer, Some(&cache), Path::new("a.txt"), bytes, meta).unwrap()
+        };
+
+        assert_eq!(import(b"a", None), b"a");
+        assert_eq!(import(b"a", None), b"a");
+        assert_eq!(importer.imports.load(Ordering::Relaxed), 1);
+
+        // changing the source or its settings imports it again
+        assert_eq!(import(b"b", None), b"b");
+        assert_eq!(import(b"b", Some(b"suffix = '!'")), b"b!");
+        assert_eq!(importer.imports.load(Ordering::Relaxed), 3);
+
+        assert!(import_cached(
+            &importer,
+            Some(&cache),
+            Path::new("a.txt"),
+            b"b",
+            Some(b"[")
+        )
+        .is_err());
+
+        std::fs::remove_dir_all(&cache).unwrap();
+    }
+}
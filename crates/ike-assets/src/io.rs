@@ -0,0 +1,280 @@
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    io,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+    time::SystemTime,
+};
+
+/// Where asset bytes are read from, paths are relative to the root of the io.
+pub trait AssetIo: Send + Sync + 'static {
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>>;
+
+    fn exists(&self, path: &Path) -> bool;
+
+    /// Used to reload changed assets, `None` if the io can't tell.
+    fn modified(&self, path: &Path) -> Option<SystemTime>;
+}
+
+impl<T: AssetIo> AssetIo for Arc<T> {
+    #[inline]
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        T::read(self, path)
+    }
+
+    #[inline]
+    fn exists(&self, path: &Path) -> bool {
+        T::exists(self, path)
+    }
+
+    #[inline]
+    fn modified(&self, path: &Path) -> Option<SystemTime> {
+        T::modified(self, path)
+    }
+}
+
+#[inline]
+pub(crate) fn not_found(path: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("'{}' not found", path.display()),
+    )
+}
+
+/// Reads files from a directory.
+pub struct DirectoryIo {
+    root: PathBuf,
+}
+
+impl DirectoryIo {
+    #[inline]
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    #[inline]
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+}
+
+impl AssetIo for DirectoryIo {
+    #[inline]
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        std::fs::read(self.root.join(path))
+    }
+
+    #[inline]
+    fn exists(&self, path: &Path) -> bool {
+        self.root.join(path).is_file()
+    }
+
+    #[inline]
+    fn modified(&self, path: &Path) -> Option<SystemTime> {
+        std::fs::metadata(self.root.join(path))
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+}
+
+struct MemoryFile {
+    bytes: Cow<'static, [u8]>,
+    modified: SystemTime,
+}
+
+/// Files kept in memory, inserting a file again counts as modifying it.
+#[derive(Default)]
+pub struct MemoryIo {
+    files: RwLock<HashMap<PathBuf, MemoryFile>>,
+}
+
+impl MemoryIo {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn insert(&self, path: impl Into<PathBuf>, bytes: impl Into<Cow<'static, [u8]>>) {
+        let file = MemoryFile {
+            bytes: bytes.into(),
+            modified: SystemTime::now(),
+        };
+
+        self.files.write().unwrap().insert(path.into(), file);
+    }
+
+    #[inline]
+    pub fn remove(&self, path: &Path) -> bool {
+        self.files.write().unwrap().remove(path).is_some()
+    }
+}
+
+impl AssetIo for MemoryIo {
+    #[inline]
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        let files = self.files.read().unwrap();
+
+        match files.get(path) {
+            Some(file) => Ok(file.bytes.to_vec()),
+            None => Err(not_found(path)),
+        }
+    }
+
+    #[inline]
+    fn exists(&self, path: &Path) -> bool {
+        self.files.read().unwrap().contains_key(path)
+    }
+
+    #[inline]
+    fn modified(&self, path: &Path) -> Option<SystemTime> {
+        let files = self.files.read().unwrap();
+        files.get(path).map(|file| file.modified)
+    }
+}
+
+struct Mount {
+    point: PathBuf,
+    io: Box<dyn AssetIo>,
+}
+
+/// Combines [`AssetIo`]s mounted at paths, a path is read from the last mount it's
+/// found in whose mount point it starts with.
+#[derive(Default)]
+pub struct Vfs {
+    mounts: Vec<Mount>,
+}
+
+impl Vfs {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mounts `io` at `point`, an empty `point` mounts it at the root.
+    #[inline]
+    pub fn mount(&mut self, point: impl Into<PathBuf>, io: impl AssetIo) {
+        self.mounts.push(Mount {
+            point: point.into(),
+            io: Box::new(io),
+        });
+    }
+
+    /// Removes every mount at `point`.
+    #[inline]
+    pub fn unmount(&mut self, point: impl AsRef<Path>) {
+        self.mounts.retain(|mount| mount.point != point.as_ref());
+    }
+
+    // the io `path` is found in and the path relative to its mount point
+    #[inline]
+    fn find<'a>(&self, path: &'a Path) -> Option<(&dyn AssetIo, &'a Path)> {
+        self.mounts.iter().rev().find_map(|mount| {
+            let path = path.strip_prefix(&mount.point).ok()?;
+
+            if mount.io.exists(path) {
+                Some((mount.io.as_ref(), path))
+            } else {
+                None
+            }
+        })
+    }
+}
+
+impl AssetIo for Vfs {
+    #[inline]
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        match self.find(path) {
+            Some((io, path)) => io.read(path),
+            None => Err(not_found(path)),
+        }
+    }
+
+    #[inline]
+    fn exists(&self, path: &Path) -> bool {
+        self.find(path).is_some()
+    }
+
+    #[inline]
+    fn modified(&self, path: &Path) -> Option<SystemTime> {
+        let (io, path) = self.find(path)?;
+        io.modified(path)
+    }
+}
+
+/// Embeds the file at `path`, relative to the calling file, in the binary and adds it to
+/// an [`AssetServer`](crate::AssetServer) under `embedded/<virtual_path>`, `virtual_path`
+/// defaults to `path`.
+///
+/// ```ignore
+/// embedded_asset!(asset_server, "fallback.png", "../assets/fallback.png");
+/// let texture = asset_server.load::<Texture>("embedded/fallback.png");
+/// ```
+#[macro_export]
+macro_rules! embedded_asset {
+    ($asset_server:expr, $path:literal) => {
+        $asset_server.embed($path, include_bytes!($path) as &'static [u8])
+    };
+    ($asset_server:expr, $virtual_path:expr, $path:literal) => {
+        $asset_server.embed($virtual_path, include_bytes!($path) as &'static [u8])
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memory(files: &[(&str, &'static str)]) -> MemoryIo {
+        let io = MemoryIo::new();
+
+        for (path, text) in files {
+            io.insert(*path, text.as_bytes());
+        }
+
+        io
+    }
+
+    fn read(io: &impl AssetIo, path: &str) -> Option<String> {
+        let bytes = io.read(Path::new(path)).ok()?;
+        Some(String::from_utf8(bytes).unwrap())
+    }
+
+    #[test]
+    fn vfs() {
+        let mut vfs = Vfs::new();
+        vfs.mount("", memory(&[("a.txt", "root"), ("b.txt", "root")]));
+        vfs.mount("", memory(&[("a.txt", "patch")]));
+        vfs.mount("data", memory(&[("a.txt", "data")]));
+
+        // later mounts shadow earlier ones, falling back to them
+        assert_eq!(read(&vfs, "a.txt").as_deref(), Some("patch"));
+        assert_eq!(read(&vfs, "b.txt").as_deref(), Some("root"));
+
+        // the mount point is stripped from paths under it
+        assert_eq!(read(&vfs, "data/a.txt").as_deref(), Some("data"));
+        assert!(vfs.exists(Path::new("data/a.txt")));
+        assert!(!vfs.exists(Path::new("data/b.txt")));
+        assert!(read(&vfs, "missing.txt").is_none());
+
+        vfs.unmount("");
+        assert!(read(&vfs, "a.txt").is_none());
+        assert_eq!(read(&vfs, "data/a.txt").as_deref(), Some("data"));
+    }
+
+    #[test]
+    fn memory_io() {
+        let io = memory(&[("a.txt", "first")]);
+        let modified = io.modified(Path::new("a.txt")).unwrap();
+
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        io.insert("a.txt", &b"second"[..]);
+
+        assert_eq!(read(&io, "a.txt").as_deref(), Some("second"));
+        assert!(io.modified(Path::new("a.txt")).unwrap() > modified);
+
+        assert!(io.remove(Path::new("a.txt")));
+        assert!(!io.exists(Path::new("a.txt")));
+        assert_eq!(io.modified(Path::new("a.txt")), None);
+    }
+}
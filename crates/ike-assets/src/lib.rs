@@ -1,22 +1,148 @@
+mod archive;
 mod assets;
 mod handle;
+mod import;
+mod io;
+mod loader;
+mod path;
+mod server;
 mod system;
 
+use std::{path::PathBuf, time::Duration};
+
+pub use archive::*;
 pub use assets::*;
 pub use handle::*;
+pub use import::*;
+pub use io::*;
+pub use loader::*;
+pub use path::*;
+pub use server::*;
 pub use system::*;
 
-use ike_core::FnSystem;
+use ike_core::{
+    resource_exists, stage, AppBuilder, Diagnostics, FnSystem, Plugin, ResMut, RunIfExt,
+};
 
 pub trait AssetAppBuilderExt {
     fn add_asset<T: Send + Sync + 'static>(&mut self) -> &mut Self;
+
+    /// Adds an asset whose [`AssetSize`] is measured, and which can be given a budget with
+    /// [`Assets::set_budget`].
+    fn add_sized_asset<T: AssetSize + Send + Sync + 'static>(&mut self) -> &mut Self;
+
+    fn add_asset_loader<T: AssetLoader>(&mut self, loader: T) -> &mut Self;
+
+    fn add_asset_importer<T: AssetImporter>(&mut self, importer: T) -> &mut Self;
 }
 
-impl AssetAppBuilderExt for ike_core::AppBuilder {
+impl AssetAppBuilderExt for AppBuilder {
     #[inline]
     fn add_asset<T: Send + Sync + 'static>(&mut self) -> &mut Self {
         self.world_mut().insert_resource(Assets::<T>::new());
+        self.add_system(
+            asset_server_system::<T>
+                .system()
+                .run_if(resource_exists::<AssetServer>()),
+        );
         self.add_system(asset_system::<T>.system());
+        asset_diagnostics(self).add::<T>();
         self
     }
+
+    #[inline]
+    fn add_sized_asset<T: AssetSize + Send + Sync + 'static>(&mut self) -> &mut Self {
+        self.world_mut().insert_resource(Assets::<T>::new());
+        self.add_system(
+            asset_server_system::<T>
+                .system()
+                .run_if(resource_exists::<AssetServer>()),
+        );
+        self.add_system(
+            asset_budget_system::<T>
+                .system()
+                .run_if(resource_exists::<AssetServer>()),
+        );
+        self.add_system(asset_system::<T>.system());
+        asset_diagnostics(self).add_sized::<T>();
+        self
+    }
+
+    #[inline]
+    fn add_asset_loader<T: AssetLoader>(&mut self, loader: T) -> &mut Self {
+        self.init_resource::<AssetServer>();
+
+        let mut asset_server = self.world_mut().write_resource::<AssetServer>().unwrap();
+        asset_server.add_loader(loader);
+        drop(asset_server);
+
+        self
+    }
+
+    #[inline]
+    fn add_asset_importer<T: AssetImporter>(&mut self, importer: T) -> &mut Self {
+        self.init_resource::<AssetServer>();
+
+        let mut asset_server = self.world_mut().write_resource::<AssetServer>().unwrap();
+        asset_server.add_importer(importer);
+        drop(asset_server);
+
+        self
+    }
+}
+
+// the asset diagnostics, adding the system measuring them the first time
+#[inline]
+fn asset_diagnostics(app: &mut AppBuilder) -> ResMut<'_, AssetDiagnostics> {
+    if !app.world().has_resource::<AssetDiagnostics>() {
+        app.init_resource::<AssetDiagnostics>();
+        app.add_system(
+            asset_diagnostics_system
+                .system()
+                .run_if(resource_exists::<Diagnostics>()),
+        );
+    }
+
+    app.world().write_resource::<AssetDiagnostics>().unwrap()
+}
+
+/// Sets the root directory and import cache of the [`AssetServer`], and optionally reloads
+/// assets when their files change.
+pub struct AssetPlugin {
+    pub root: PathBuf,
+    pub import_cache: Option<PathBuf>,
+    pub watch_for_changes: bool,
+}
+
+impl AssetPlugin {
+    pub const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+}
+
+impl Default for AssetPlugin {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            root: PathBuf::from("assets"),
+            import_cache: Some(PathBuf::from(AssetServer::IMPORT_CACHE)),
+            watch_for_changes: false,
+        }
+    }
+}
+
+impl Plugin for AssetPlugin {
+    #[inline]
+    fn build(self, app: &mut AppBuilder) {
+        app.init_resource::<AssetServer>();
+
+        let mut asset_server = app.world_mut().write_resource::<AssetServer>().unwrap();
+        asset_server.set_root(self.root);
+        asset_server.set_import_cache(self.import_cache);
+
+        if self.watch_for_changes {
+            asset_server.watch_for_changes(Self::WATCH_INTERVAL);
+            drop(asset_server);
+
+            app.add_system_to_stage(asset_watch_system.system(), stage::PRE_UPDATE);
+        }
+    }
 }
@@ -0,0 +1,123 @@
+use std::{
+    any::{type_name, Any, TypeId},
+    path::Path,
+    sync::Arc,
+};
+
+use crate::{AssetKey, AssetPath, Handle, HandleUntyped, ServerState};
+
+/// Loads assets of type `Asset` from the bytes of files with one of `extensions`.
+///
+/// Files containing several assets add them with [`LoadContext::add_labeled_asset`], assets
+/// referring to other files load them with [`LoadContext::load`].
+pub trait AssetLoader: Send + Sync + 'static {
+    type Asset: Send + Sync + 'static;
+
+    fn extensions(&self) -> &[&str];
+
+    fn load(&self, bytes: &[u8], context: &mut LoadContext) -> anyhow::Result<Self::Asset>;
+}
+
+pub(crate) struct LabeledAsset {
+    pub(crate) type_id: TypeId,
+    pub(crate) label: String,
+    pub(crate) asset: BoxedAsset,
+}
+
+pub struct LoadContext<'a> {
+    path: &'a Path,
+    server: &'a Arc<ServerState>,
+    labeled: Vec<LabeledAsset>,
+    dependencies: Vec<AssetKey>,
+}
+
+impl<'a> LoadContext<'a> {
+    #[inline]
+    pub(crate) fn new(path: &'a Path, server: &'a Arc<ServerState>) -> Self {
+        Self {
+            path,
+            server,
+            labeled: Vec::new(),
+            dependencies: Vec::new(),
+        }
+    }
+
+    /// The path being loaded, relative to the asset root.
+    #[inline]
+    pub fn path(&self) -> &Path {
+        self.path
+    }
+
+    /// Adds a sub-asset loaded from `"path#label"`.
+    #[inline]
+    pub fn add_labeled_asset<T: Send + Sync + 'static>(
+        &mut self,
+        label: impl Into<String>,
+        asset: T,
+    ) -> Handle<T> {
+        let label = label.into();
+        let handle = self.labeled_handle(&label);
+
+        self.labeled.push(LabeledAsset {
+            type_id: TypeId::of::<T>(),
+            label,
+            asset: Box::new(asset),
+        });
+
+        handle
+    }
+
+    /// Returns a weak handle to the sub-asset `label` of the file being loaded, for assets
+    /// referring to each other.
+    #[inline]
+    pub fn labeled_handle<T>(&self, label: &str) -> Handle<T> {
+        Handle::weak(AssetPath::new(self.path).with_label(label))
+    }
+
+    /// Loads `path`, relative to the asset root, as a dependency of the file being loaded.
+    ///
+    /// The file is reloaded when a dependency changes, store the handle in the asset to keep
+    /// the dependency alive.
+    #[inline]
+    pub fn load<T: Send + Sync + 'static>(&mut self, path: impl Into<AssetPath>) -> Handle<T> {
+        let path = path.into();
+
+        let key = (TypeId::of::<T>(), HandleUntyped::Path(path.clone()));
+        self.dependencies.push(key);
+
+        let handle = self.server.load(TypeId::of::<T>(), type_name::<T>(), path);
+        Handle::from_ref(handle)
+    }
+
+    #[inline]
+    pub(crate) fn finish(self) -> (Vec<LabeledAsset>, Vec<AssetKey>) {
+        (self.labeled, self.dependencies)
+    }
+}
+
+pub(crate) type BoxedAsset = Box<dyn Any + Send + Sync>;
+
+pub(crate) trait ErasedAssetLoader: Send + Sync + 'static {
+    fn loader_type_id(&self) -> TypeId;
+
+    fn asset_type_id(&self) -> TypeId;
+
+    fn load(&self, bytes: &[u8], context: &mut LoadContext) -> anyhow::Result<BoxedAsset>;
+}
+
+impl<T: AssetLoader> ErasedAssetLoader for T {
+    #[inline]
+    fn loader_type_id(&self) -> TypeId {
+        TypeId::of::<T>()
+    }
+
+    #[inline]
+    fn asset_type_id(&self) -> TypeId {
+        TypeId::of::<T::Asset>()
+    }
+
+    #[inline]
+    fn load(&self, bytes: &[u8], context: &mut LoadContext) -> anyhow::Result<BoxedAsset> {
+        Ok(Box::new(AssetLoader::load(self, bytes, context)?))
+    }
+}
@@ -0,0 +1,108 @@
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+/// Path of an asset relative to the asset root, with an optional label of a sub-asset in
+/// the file, written as `"helmet.gltf#Mesh0/Primitive0"`.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AssetPath {
+    path: PathBuf,
+    label: Option<String>,
+}
+
+impl AssetPath {
+    #[inline]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            label: None,
+        }
+    }
+
+    /// Splits `path` at the first `#` into the path and the label.
+    #[inline]
+    pub fn parse(path: &str) -> Self {
+        match path.split_once('#') {
+            Some((path, label)) => Self::new(path).with_label(label),
+            None => Self::new(path),
+        }
+    }
+
+    #[inline]
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    #[inline]
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    /// Returns the path of the file without the label.
+    #[inline]
+    pub fn file(&self) -> Self {
+        Self::new(self.path.clone())
+    }
+}
+
+impl Display for AssetPath {
+    #[inline]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.path.display())?;
+
+        if let Some(ref label) = self.label {
+            write!(f, "#{}", label)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<&str> for AssetPath {
+    #[inline]
+    fn from(path: &str) -> Self {
+        Self::parse(path)
+    }
+}
+
+impl From<&String> for AssetPath {
+    #[inline]
+    fn from(path: &String) -> Self {
+        Self::parse(path)
+    }
+}
+
+impl From<String> for AssetPath {
+    #[inline]
+    fn from(path: String) -> Self {
+        Self::parse(&path)
+    }
+}
+
+impl From<&Path> for AssetPath {
+    #[inline]
+    fn from(path: &Path) -> Self {
+        Self::new(path)
+    }
+}
+
+impl From<PathBuf> for AssetPath {
+    #[inline]
+    fn from(path: PathBuf) -> Self {
+        Self::new(path)
+    }
+}
+
+impl From<&AssetPath> for AssetPath {
+    #[inline]
+    fn from(path: &AssetPath) -> Self {
+        path.clone()
+    }
+}
@@ -0,0 +1,1156 @@
+use std::{
+    any::{type_name, TypeId},
+    collections::{hash_map::Entry, HashMap, HashSet},
+    io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, RwLock, Weak},
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::{
+    import_cached, meta_path, AssetImporter, AssetIo, AssetLoader, AssetPath, BoxedAsset,
+    ErasedAssetImporter, ErasedAssetLoader, Handle, HandleRef, HandleUntyped, LoadContext,
+    MemoryIo, Vfs,
+};
+
+#[derive(Clone, Debug)]
+pub enum LoadState {
+    NotLoaded,
+    Loading,
+    Loaded,
+    Failed(Arc<anyhow::Error>),
+}
+
+impl LoadState {
+    #[inline]
+    pub fn is_loaded(&self) -> bool {
+        matches!(self, Self::Loaded)
+    }
+
+    #[inline]
+    pub fn is_failed(&self) -> bool {
+        matches!(self, Self::Failed(_))
+    }
+
+    // lower is less ready
+    #[inline]
+    fn readiness(&self) -> u8 {
+        match self {
+            Self::Failed(_) => 0,
+            Self::NotLoaded => 1,
+            Self::Loading => 2,
+            Self::Loaded => 3,
+        }
+    }
+}
+
+pub(crate) type AssetKey = (TypeId, HandleUntyped);
+
+// a file and the loader loading it, a file can be loaded by a loader for each asset type
+type SourceKey = (TypeId, AssetPath);
+
+#[inline]
+fn source_key(loader: &dyn ErasedAssetLoader, file: &AssetPath) -> SourceKey {
+    (loader.loader_type_id(), file.clone())
+}
+
+#[inline]
+fn extension(path: &Path) -> String {
+    path.extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+struct LoadedAsset {
+    handle: Arc<HandleRef>,
+    asset: BoxedAsset,
+}
+
+// a file being loaded, and the assets requested from it which fail if it doesn't contain them
+struct PendingLoad {
+    loader: Arc<dyn ErasedAssetLoader>,
+    expected: Vec<AssetKey>,
+}
+
+// where the assets of a file came from, used to reload them when the file changes
+struct AssetSource {
+    handles: Vec<(AssetKey, Weak<HandleRef>)>,
+    loader: Arc<dyn ErasedAssetLoader>,
+    modified: Option<SystemTime>,
+}
+
+impl AssetSource {
+    // the assets still alive, expected when the file is reloaded
+    #[inline]
+    fn expected(&self) -> Vec<AssetKey> {
+        self.handles
+            .iter()
+            .filter(|(_, handle)| handle.strong_count() > 0)
+            .map(|(key, _)| key.clone())
+            .collect()
+    }
+}
+
+#[derive(Default)]
+struct Loaders {
+    by_type: HashMap<(TypeId, String), Arc<dyn ErasedAssetLoader>>,
+    // loads files for their labeled assets, which can be of any type
+    by_extension: HashMap<String, Arc<dyn ErasedAssetLoader>>,
+}
+
+// shared with loading tasks
+#[derive(Default)]
+pub(crate) struct ServerState {
+    root: RwLock<PathBuf>,
+    // read before the root directory
+    mounts: RwLock<Vfs>,
+    embedded: Arc<MemoryIo>,
+    loaders: RwLock<Loaders>,
+    importers: RwLock<HashMap<String, Arc<dyn ErasedAssetImporter>>>,
+    import_cache: RwLock<Option<PathBuf>>,
+    handles: Mutex<HashMap<AssetKey, Weak<HandleRef>>>,
+    load_states: RwLock<HashMap<AssetKey, LoadState>>,
+    loaded: Mutex<HashMap<TypeId, Vec<LoadedAsset>>>,
+    // files being loaded, locked before `handles` and `sources`
+    loading: Mutex<HashMap<SourceKey, PendingLoad>>,
+    sources: Mutex<HashMap<SourceKey, AssetSource>>,
+    // the assets each file loaded through its `LoadContext`
+    dependencies: RwLock<HashMap<AssetPath, Vec<AssetKey>>>,
+}
+
+impl ServerState {
+    #[inline]
+    fn set_load_state(&self, key: AssetKey, load_state: LoadState) {
+        self.load_states.write().unwrap().insert(key, load_state);
+    }
+
+    #[inline]
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        let mounts = self.mounts.read().unwrap();
+
+        if mounts.exists(path) {
+            return mounts.read(path);
+        }
+
+        drop(mounts);
+
+        std::fs::read(self.root.read().unwrap().join(path))
+    }
+
+    #[inline]
+    fn modified(&self, path: &Path) -> Option<SystemTime> {
+        let mounts = self.mounts.read().unwrap();
+
+        if mounts.exists(path) {
+            return mounts.modified(path);
+        }
+
+        drop(mounts);
+
+        let path = self.root.read().unwrap().join(path);
+        std::fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+
+    #[inline]
+    fn importer(&self, path: &Path) -> Option<Arc<dyn ErasedAssetImporter>> {
+        let importers = self.importers.read().unwrap();
+        importers.get(&extension(path)).cloned()
+    }
+
+    // imported files also change with their settings
+    #[inline]
+    fn source_modified(&self, path: &Path) -> Option<SystemTime> {
+        let modified = self.modified(path);
+
+        if self.importer(path).is_some() {
+            modified.max(self.modified(&meta_path(path)))
+        } else {
+            modified
+        }
+    }
+
+    // reads `path`, importing it if an importer is registered for its extension
+    #[inline]
+    fn read_source(&self, path: &Path) -> anyhow::Result<Vec<u8>> {
+        let bytes = self.read(path)?;
+
+        let importer = match self.importer(path) {
+            Some(importer) => importer,
+            None => return Ok(bytes),
+        };
+
+        let meta = match self.read(&meta_path(path)) {
+            Ok(meta) => Some(meta),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => None,
+            Err(error) => return Err(error.into()),
+        };
+
+        let cache = self.import_cache.read().unwrap().clone();
+
+        let meta = meta.as_deref();
+
+        import_cached(importer.as_ref(), cache.as_deref(), path, &bytes, meta)
+            .map_err(|error| error.context(format!("failed to import '{}'", path.display())))
+    }
+
+    #[inline]
+    fn load_state(&self, key: &AssetKey) -> LoadState {
+        self.load_states
+            .read()
+            .unwrap()
+            .get(key)
+            .cloned()
+            .unwrap_or(LoadState::NotLoaded)
+    }
+
+    #[inline]
+    pub(crate) fn load(
+        self: &Arc<Self>,
+        type_id: TypeId,
+        type_name: &str,
+        path: AssetPath,
+    ) -> Arc<HandleRef> {
+        let key = (type_id, HandleUntyped::Path(path.clone()));
+
+        let mut loading = self.loading.lock().unwrap();
+        let mut handles = self.handles.lock().unwrap();
+
+        if let Some(handle) = handles.get(&key).and_then(Weak::upgrade) {
+            handle.set_requested();
+            return handle;
+        }
+
+        let handle = Arc::new(HandleRef::new(key.1.clone()));
+        handle.set_requested();
+        handles.insert(key.clone(), Arc::downgrade(&handle));
+        handles.retain(|_, handle| handle.strong_count() > 0);
+        drop(handles);
+
+        // imported files are loaded by the loader for their processed form
+        let extension = match self.importer(path.path()) {
+            Some(importer) => importer.processed_extension().to_lowercase(),
+            None => extension(path.path()),
+        };
+
+        let loaders = self.loaders.read().unwrap();
+
+        // labeled assets come from the loader already loading the file if there's one
+        let loader = match path.label() {
+            Some(_) => self
+                .file_loader(&loading, &path.file())
+                .or_else(|| loaders.by_extension.get(&extension).cloned()),
+            None => loaders.by_type.get(&(type_id, extension)).cloned(),
+        };
+
+        let loader = match loader {
+            Some(loader) => loader,
+            None => {
+                let error = anyhow::anyhow!("no loader for '{}' producing '{}'", path, type_name);
+
+                self.set_load_state(key, LoadState::Failed(Arc::new(error)));
+                return handle;
+            }
+        };
+
+        drop(loaders);
+
+        self.set_load_state(key.clone(), LoadState::Loading);
+        self.queue_load(&mut loading, path.file(), loader, vec![key]);
+
+        handle
+    }
+
+    // the loader `file` is being or was loaded with
+    #[inline]
+    fn file_loader(
+        &self,
+        loading: &HashMap<SourceKey, PendingLoad>,
+        file: &AssetPath,
+    ) -> Option<Arc<dyn ErasedAssetLoader>> {
+        let pending = loading
+            .iter()
+            .find(|((_, path), _)| path == file)
+            .map(|(_, pending)| pending.loader.clone());
+
+        pending.or_else(|| {
+            let sources = self.sources.lock().unwrap();
+
+            sources
+                .iter()
+                .find(|((_, path), _)| path == file)
+                .map(|(_, source)| source.loader.clone())
+        })
+    }
+
+    // starts loading `file` with `loader` unless it's already being loaded, the `expected`
+    // assets fail if the file doesn't contain them
+    #[inline]
+    fn queue_load(
+        self: &Arc<Self>,
+        loading: &mut HashMap<SourceKey, PendingLoad>,
+        file: AssetPath,
+        loader: Arc<dyn ErasedAssetLoader>,
+        expected: Vec<AssetKey>,
+    ) {
+        match loading.entry(source_key(loader.as_ref(), &file)) {
+            Entry::Occupied(mut entry) => entry.get_mut().expected.extend(expected),
+            Entry::Vacant(entry) => {
+                entry.insert(PendingLoad {
+                    loader: loader.clone(),
+                    expected,
+                });
+
+                self.start_load(file, loader);
+            }
+        }
+    }
+
+    // loads `file` in the background and queues its assets for insertion
+    #[inline]
+    fn start_load(self: &Arc<Self>, file: AssetPath, loader: Arc<dyn ErasedAssetLoader>) {
+        let state = self.clone();
+
+        rayon::spawn(move || {
+            let modified = state.source_modified(file.path());
+
+            let mut context = LoadContext::new(file.path(), &state);
+
+            let result = state
+                .read_source(file.path())
+                .and_then(|bytes| loader.load(&bytes, &mut context));
+
+            let (labeled, dependencies) = context.finish();
+
+            let result = result.map(|asset| {
+                let mut assets = vec![(loader.asset_type_id(), file.clone(), asset)];
+
+                for labeled in labeled {
+                    let path = file.clone().with_label(labeled.label);
+                    assets.push((labeled.type_id, path, labeled.asset));
+                }
+
+                assets
+            });
+
+            let mut all_dependencies = state.dependencies.write().unwrap();
+            all_dependencies.insert(file.clone(), dependencies);
+            drop(all_dependencies);
+
+            state.finish_load(file, loader, modified, result);
+        });
+    }
+
+    // queues the assets loaded from `file` for insertion, and fails the ones expected from
+    // the file it doesn't contain
+    #[inline]
+    fn finish_load(
+        &self,
+        file: AssetPath,
+        loader: Arc<dyn ErasedAssetLoader>,
+        modified: Option<SystemTime>,
+        result: anyhow::Result<Vec<(TypeId, AssetPath, BoxedAsset)>>,
+    ) {
+        let source_key = source_key(loader.as_ref(), &file);
+
+        let mut loading = self.loading.lock().unwrap();
+        let mut handles = self.handles.lock().unwrap();
+
+        let expected = match loading.remove(&source_key) {
+            Some(pending) => pending.expected,
+            None => Vec::new(),
+        };
+
+        let (assets, error) = match result {
+            Ok(assets) => (assets, None),
+            Err(error) => {
+                let error = error.context(format!("failed to load '{}'", file));
+                (Vec::new(), Some(Arc::new(error)))
+            }
+        };
+
+        let mut loaded = Vec::with_capacity(assets.len());
+
+        for (type_id, path, asset) in assets {
+            let key = (type_id, HandleUntyped::Path(path));
+
+            let handle = match handles.get(&key).and_then(Weak::upgrade) {
+                Some(handle) => handle,
+                None => {
+                    let handle = Arc::new(HandleRef::new(key.1.clone()));
+                    handles.insert(key.clone(), Arc::downgrade(&handle));
+                    handle
+                }
+            };
+
+            loaded.push((type_id, LoadedAsset { handle, asset }));
+        }
+
+        // sub-assets no one requested live as long as a requested one
+        let keep_alive: Vec<_> = loaded
+            .iter()
+            .filter(|(_, loaded)| !loaded.handle.is_requested())
+            .map(|(_, loaded)| loaded.handle.clone())
+            .collect();
+        let keep_alive = Arc::new(keep_alive);
+
+        for (_, loaded) in &loaded {
+            if loaded.handle.is_requested() {
+                loaded.handle.set_keep_alive(keep_alive.clone());
+            }
+        }
+
+        let mut source_handles: Vec<_> = loaded
+            .iter()
+            .map(|(type_id, loaded)| {
+                let key = (*type_id, loaded.handle.id().clone());
+                (key, Arc::downgrade(&loaded.handle))
+            })
+            .collect();
+
+        for key in expected {
+            if source_handles.iter().any(|(source, _)| *source == key) {
+                continue;
+            }
+
+            let (path, handle) = match (&key.1, handles.get(&key)) {
+                (HandleUntyped::Path(path), Some(handle)) if handle.strong_count() > 0 => {
+                    (path, handle.clone())
+                }
+                _ => continue,
+            };
+
+            let error = match error {
+                Some(ref error) => error.clone(),
+                None => Arc::new(anyhow::anyhow!(
+                    "'{}' has no asset of the requested type",
+                    path
+                )),
+            };
+
+            self.set_load_state(key.clone(), LoadState::Failed(error));
+            source_handles.push((key, handle));
+        }
+
+        drop(handles);
+
+        let source = AssetSource {
+            handles: source_handles,
+            loader,
+            modified,
+        };
+
+        self.sources.lock().unwrap().insert(source_key, source);
+
+        let mut queue = self.loaded.lock().unwrap();
+
+        for (type_id, loaded) in loaded {
+            queue.entry(type_id).or_default().push(loaded);
+        }
+
+        drop(queue);
+        drop(loading);
+    }
+
+    // the files depending on any of `files`, directly or not
+    #[inline]
+    fn dependents(&self, files: &[AssetPath]) -> Vec<AssetPath> {
+        let dependencies = self.dependencies.read().unwrap();
+
+        let mut visited: HashSet<AssetPath> = files.iter().cloned().collect();
+        let mut stack = files.to_vec();
+        let mut dependents = Vec::new();
+
+        while let Some(file) = stack.pop() {
+            for (dependent, keys) in dependencies.iter() {
+                let depends = keys.iter().any(|(_, id)| match id {
+                    HandleUntyped::Path(path) => path.path() == file.path(),
+                    _ => false,
+                });
+
+                if depends && visited.insert(dependent.clone()) {
+                    dependents.push(dependent.clone());
+                    stack.push(dependent.clone());
+                }
+            }
+        }
+
+        dependents
+    }
+}
+
+/// Loads assets from files under `root` in the background, using the [`AssetLoader`]
+/// registered for the file extension and asset type.
+///
+/// Files are read from the [`AssetIo`]s mounted with [`mount`](Self::mount) before `root`,
+/// assets added with [`embedded_asset!`](crate::embedded_asset) are mounted at [`EMBEDDED`](Self::EMBEDDED).
+/// Files an [`AssetImporter`] is registered for are imported before being loaded, and the
+/// processed files are cached in [`import_cache`](Self::import_cache).
+///
+/// Loaded assets are inserted into [`Assets<T>`](crate::Assets) under the returned handle
+/// by a system added with [`add_asset`](crate::AssetAppBuilderExt::add_asset).
+pub struct AssetServer {
+    state: Arc<ServerState>,
+    watch: Option<Watch>,
+}
+
+struct Watch {
+    interval: Duration,
+    last_check: Mutex<Instant>,
+}
+
+impl Default for AssetServer {
+    #[inline]
+    fn default() -> Self {
+        Self::new("assets")
+    }
+}
+
+impl AssetServer {
+    pub const EMBEDDED: &'static str = "embedded";
+    pub const IMPORT_CACHE: &'static str = "imported";
+
+    #[inline]
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        let embedded = Arc::new(MemoryIo::new());
+
+        let mut mounts = Vfs::new();
+        mounts.mount(Self::EMBEDDED, embedded.clone());
+
+        let state = ServerState {
+            root: RwLock::new(root.into()),
+            mounts: RwLock::new(mounts),
+            embedded,
+            import_cache: RwLock::new(Some(PathBuf::from(Self::IMPORT_CACHE))),
+            ..Default::default()
+        };
+
+        Self {
+            state: Arc::new(state),
+            watch: None,
+        }
+    }
+
+    #[inline]
+    pub fn root(&self) -> PathBuf {
+        self.state.root.read().unwrap().clone()
+    }
+
+    #[inline]
+    pub fn set_root(&mut self, root: impl Into<PathBuf>) {
+        *self.state.root.write().unwrap() = root.into();
+    }
+
+    #[inline]
+    pub fn import_cache(&self) -> Option<PathBuf> {
+        self.state.import_cache.read().unwrap().clone()
+    }
+
+    /// Sets the directory processed files are cached in, defaults to [`IMPORT_CACHE`](Self::IMPORT_CACHE),
+    /// `None` imports files every time they're loaded.
+    #[inline]
+    pub fn set_import_cache(&mut self, import_cache: Option<PathBuf>) {
+        *self.state.import_cache.write().unwrap() = import_cache;
+    }
+
+    /// Mounts `io` at `point`, files found in it shadow those of earlier mounts and the root.
+    #[inline]
+    pub fn mount(&mut self, point: impl Into<PathBuf>, io: impl AssetIo) {
+        self.state.mounts.write().unwrap().mount(point, io);
+    }
+
+    #[inline]
+    pub fn unmount(&mut self, point: impl AsRef<Path>) {
+        self.state.mounts.write().unwrap().unmount(point);
+    }
+
+    /// Adds `bytes` under `EMBEDDED/path`, usually through [`embedded_asset!`](crate::embedded_asset).
+    #[inline]
+    pub fn embed(&self, path: impl Into<PathBuf>, bytes: &'static [u8]) {
+        self.state.embedded.insert(path, bytes);
+    }
+
+    /// Reads `path` from the mounts or the root.
+    #[inline]
+    pub fn read(&self, path: impl AsRef<Path>) -> io::Result<Vec<u8>> {
+        self.state.read(path.as_ref())
+    }
+
+    /// Reloads loaded assets when their file changes, checking every `interval`.
+    ///
+    /// Reloaded assets replace the old ones in place and send [`AssetEvent::Modified`](crate::AssetEvent::Modified),
+    /// files depending on a changed file are reloaded with it.
+    #[inline]
+    pub fn watch_for_changes(&mut self, interval: Duration) {
+        self.watch = Some(Watch {
+            interval,
+            last_check: Mutex::new(Instant::now()),
+        });
+    }
+
+    #[inline]
+    pub fn is_watching_for_changes(&self) -> bool {
+        self.watch.is_some()
+    }
+
+    /// Registers `loader` for each of its extensions, replacing loaders of the same asset type.
+    ///
+    /// Labeled assets are loaded by the loader already loading their file, or the last loader
+    /// registered for the extension.
+    #[inline]
+    pub fn add_loader<T: AssetLoader>(&mut self, loader: T) {
+        let extensions: Vec<String> = loader
+            .extensions()
+            .iter()
+            .map(|extension| extension.to_lowercase())
+            .collect();
+
+        let loader: Arc<dyn ErasedAssetLoader> = Arc::new(loader);
+        let mut loaders = self.state.loaders.write().unwrap();
+
+        for extension in extensions {
+            let key = (TypeId::of::<T::Asset>(), extension.clone());
+            loaders.by_type.insert(key, loader.clone());
+            loaders.by_extension.insert(extension, loader.clone());
+        }
+    }
+
+    /// Registers `importer` for each of its extensions, files with those extensions are
+    /// imported and loaded by the loader for the processed extension.
+    #[inline]
+    pub fn add_importer<T: AssetImporter>(&mut self, importer: T) {
+        let extensions: Vec<String> = importer
+            .extensions()
+            .iter()
+            .map(|extension| extension.to_lowercase())
+            .collect();
+
+        let importer: Arc<dyn ErasedAssetImporter> = Arc::new(importer);
+        let mut importers = self.state.importers.write().unwrap();
+
+        for extension in extensions {
+            importers.insert(extension, importer.clone());
+        }
+    }
+
+    /// Starts loading `path` in the background, loading the same path again returns the same
+    /// handle while it's alive.
+    ///
+    /// `"file#label"` loads the sub-asset `label` of `file`, the file is loaded once for all
+    /// of its sub-assets, which live as long as a handle to one of them does.
+    #[inline]
+    pub fn load<T: Send + Sync + 'static>(&self, path: impl Into<AssetPath>) -> Handle<T> {
+        let handle = self
+            .state
+            .load(TypeId::of::<T>(), type_name::<T>(), path.into());
+
+        Handle::from_ref(handle)
+    }
+
+    /// Reloads every file whose assets are alive and changed since it was loaded, along with
+    /// the files depending on it, does nothing unless [`watch_for_changes`](Self::watch_for_changes)
+    /// was called or the interval hasn't passed.
+    #[inline]
+    pub fn check_for_changes(&self) {
+        let watch = match self.watch {
+            Some(ref watch) => watch,
+            None => return,
+        };
+
+        let mut last_check = watch.last_check.lock().unwrap();
+
+        if last_check.elapsed() < watch.interval {
+            return;
+        }
+
+        *last_check = Instant::now();
+        drop(last_check);
+
+        let mut changed = Vec::new();
+        let mut sources = self.state.sources.lock().unwrap();
+
+        sources.retain(|(_, file), source| {
+            if !source
+                .handles
+                .iter()
+                .any(|(_, handle)| handle.strong_count() > 0)
+            {
+                return false;
+            }
+
+            let modified = self.state.source_modified(file.path());
+
+            if modified.is_some() && modified != source.modified {
+                source.modified = modified;
+                changed.push(file.clone());
+            }
+
+            true
+        });
+
+        let mut files = self.state.dependents(&changed);
+        files.extend(changed);
+
+        let reloads: Vec<_> = sources
+            .iter()
+            .filter(|((_, file), _)| files.contains(file))
+            .map(|((_, file), source)| (file.clone(), source.loader.clone(), source.expected()))
+            .collect();
+
+        drop(sources);
+
+        let mut loading = self.state.loading.lock().unwrap();
+
+        for (file, loader, expected) in reloads {
+            self.state.queue_load(&mut loading, file, loader, expected);
+        }
+    }
+
+    /// Loads the file of `handle` again, along with every asset loaded from it, does nothing
+    /// unless the file was loaded before.
+    #[inline]
+    pub fn reload<T: 'static>(&self, handle: &Handle<T>) {
+        let key = (TypeId::of::<T>(), handle.untyped().clone());
+
+        let sources = self.state.sources.lock().unwrap();
+
+        let source = sources.iter().find(|(_, source)| {
+            let mut keys = source.handles.iter().map(|(key, _)| key);
+            keys.any(|source| *source == key)
+        });
+
+        let (file, loader, expected) = match source {
+            Some(((_, file), source)) => (file.clone(), source.loader.clone(), source.expected()),
+            None => return,
+        };
+
+        drop(sources);
+
+        self.state.set_load_state(key, LoadState::Loading);
+
+        let mut loading = self.state.loading.lock().unwrap();
+        self.state.queue_load(&mut loading, file, loader, expected);
+    }
+
+    // evicted assets aren't loaded until they're loaded again
+    #[inline]
+    pub(crate) fn set_evicted<T: 'static>(&self, handle: &Handle<T>) {
+        let key = (TypeId::of::<T>(), handle.untyped().clone());
+        self.state.set_load_state(key, LoadState::NotLoaded);
+    }
+
+    #[inline]
+    pub fn load_state<T: 'static>(&self, handle: &Handle<T>) -> LoadState {
+        let key = (TypeId::of::<T>(), handle.untyped().clone());
+        self.state.load_state(&key)
+    }
+
+    /// Returns the least ready load state of the asset and every asset it depends on,
+    /// directly or not, a loading screen can wait until this is loaded.
+    #[inline]
+    pub fn load_state_recursive<T: 'static>(&self, handle: &Handle<T>) -> LoadState {
+        let key = (TypeId::of::<T>(), handle.untyped().clone());
+        let dependencies = self.state.dependencies.read().unwrap();
+
+        let mut visited = HashSet::new();
+        let mut stack = vec![key];
+        let mut load_state = LoadState::Loaded;
+
+        while let Some(key) = stack.pop() {
+            if !visited.insert(key.clone()) {
+                continue;
+            }
+
+            let state = self.state.load_state(&key);
+
+            if state.readiness() < load_state.readiness() {
+                load_state = state;
+            }
+
+            if let HandleUntyped::Path(ref path) = key.1 {
+                if let Some(keys) = dependencies.get(&path.file()) {
+                    stack.extend(keys.iter().cloned());
+                }
+            }
+        }
+
+        load_state
+    }
+
+    /// Returns the assets the file of `handle` loaded through its [`LoadContext`].
+    #[inline]
+    pub fn dependencies<T: 'static>(&self, handle: &Handle<T>) -> Vec<HandleUntyped> {
+        let file = match handle.untyped() {
+            HandleUntyped::Path(path) => path.file(),
+            _ => return Vec::new(),
+        };
+
+        let dependencies = self.state.dependencies.read().unwrap();
+
+        dependencies
+            .get(&file)
+            .map(|keys| keys.iter().map(|(_, id)| id.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Takes every `T` finished loading since the last call, marking them as loaded.
+    #[inline]
+    pub(crate) fn take_loaded<T: Send + Sync + 'static>(&self) -> Vec<(Handle<T>, T)> {
+        let loaded = match self.state.loaded.lock().unwrap().remove(&TypeId::of::<T>()) {
+            Some(loaded) => loaded,
+            None => return Vec::new(),
+        };
+
+        loaded
+            .into_iter()
+            .map(|loaded| {
+                let handle = Handle::from_ref(loaded.handle);
+
+                let key = (TypeId::of::<T>(), handle.untyped().clone());
+                self.state.set_load_state(key, LoadState::Loaded);
+
+                let asset = *loaded.asset.downcast::<T>().unwrap();
+                (handle, asset)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::mpsc, thread};
+
+    use ike_core::{App, AppRunner};
+
+    use super::*;
+    use crate::{AssetAppBuilderExt, AssetEvent, AssetEventReader, AssetPlugin, Assets};
+
+    // adds a `u32` labeled `label` for every "label: number" line
+    struct TextLoader;
+
+    impl AssetLoader for TextLoader {
+        type Asset = String;
+
+        fn extensions(&self) -> &[&str] {
+            &["txt"]
+        }
+
+        fn load(&self, bytes: &[u8], context: &mut LoadContext) -> anyhow::Result<String> {
+            let text = String::from_utf8(bytes.to_vec())?;
+
+            for line in text.lines() {
+                if let Some((label, number)) = line.split_once(':') {
+                    context.add_labeled_asset::<u32>(label, number.trim().parse()?);
+                }
+            }
+
+            Ok(text)
+        }
+    }
+
+    struct LenLoader;
+
+    impl AssetLoader for LenLoader {
+        type Asset = usize;
+
+        fn extensions(&self) -> &[&str] {
+            &["txt"]
+        }
+
+        fn load(&self, bytes: &[u8], _context: &mut LoadContext) -> anyhow::Result<usize> {
+            Ok(bytes.len())
+        }
+    }
+
+    // refers to the file named in a ".lnk" file
+    struct Link(Handle<String>);
+
+    struct LinkLoader;
+
+    impl AssetLoader for LinkLoader {
+        type Asset = Link;
+
+        fn extensions(&self) -> &[&str] {
+            &["lnk"]
+        }
+
+        fn load(&self, bytes: &[u8], context: &mut LoadContext) -> anyhow::Result<Link> {
+            let path = std::str::from_utf8(bytes)?.trim();
+            Ok(Link(context.load(path)))
+        }
+    }
+
+    struct Runner(mpsc::Sender<App>);
+
+    impl AppRunner for Runner {
+        fn run(&mut self, app: App) {
+            self.0.send(app).unwrap();
+        }
+    }
+
+    fn app(files: Arc<MemoryIo>) -> App {
+        let (sender, receiver) = mpsc::channel();
+
+        let mut app = App::new();
+        app.set_runner(Runner(sender));
+        app.add_plugin(AssetPlugin {
+            import_cache: None,
+            watch_for_changes: true,
+            ..Default::default()
+        });
+
+        let mut asset_server = app.world_mut().write_resource::<AssetServer>().unwrap();
+        asset_server.watch_for_changes(Duration::ZERO);
+        asset_server.mount("", files);
+        drop(asset_server);
+
+        app.add_asset::<String>();
+        app.add_asset::<u32>();
+        app.add_asset::<usize>();
+        app.add_asset::<Link>();
+        app.add_asset_loader(LenLoader);
+        app.add_asset_loader(TextLoader);
+        app.add_asset_loader(LinkLoader);
+        app.run();
+
+        receiver.recv().unwrap()
+    }
+
+    fn frames(app: &mut App, count: usize) {
+        for _ in 0..count {
+            app.execute();
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn load() {
+        let files = Arc::new(MemoryIo::new());
+        files.insert("a.txt", &b"text"[..]);
+
+        let mut app = app(files);
+
+        let asset_server = app.world().read_resource::<AssetServer>().unwrap();
+        let a = asset_server.load::<String>("a.txt");
+        let again = asset_server.load::<String>("a.txt");
+        let missing = asset_server.load::<String>("missing.txt");
+        let no_loader = asset_server.load::<u32>("b.txt");
+
+        assert_eq!(a, again);
+        assert!(asset_server.load_state(&no_loader).is_failed());
+        drop(asset_server);
+
+        frames(&mut app, 10);
+
+        let asset_server = app.world().read_resource::<AssetServer>().unwrap();
+        assert!(asset_server.load_state(&a).is_loaded());
+        assert!(asset_server.load_state(&missing).is_failed());
+        drop(asset_server);
+
+        let strings = app.world().read_resource::<Assets<String>>().unwrap();
+        assert_eq!(strings.get(&a).map(String::as_str), Some("text"));
+        assert_eq!(strings.len(), 1);
+        drop(strings);
+
+        let weak = a.clone_weak();
+        drop(a);
+        drop(again);
+        frames(&mut app, 1);
+
+        let strings = app.world().read_resource::<Assets<String>>().unwrap();
+        assert!(!strings.contains(&weak));
+    }
+
+    #[test]
+    fn reload() {
+        let files = Arc::new(MemoryIo::new());
+        files.insert("a.txt", &b"first"[..]);
+
+        let mut app = app(files.clone());
+
+        let asset_server = app.world().read_resource::<AssetServer>().unwrap();
+        let a = asset_server.load::<String>("a.txt");
+        drop(asset_server);
+
+        frames(&mut app, 10);
+
+        let mut reader = AssetEventReader::new();
+        let strings = app.world().read_resource::<Assets<String>>().unwrap();
+        assert_eq!(strings.read_events(&mut reader).count(), 0);
+        drop(strings);
+
+        thread::sleep(Duration::from_millis(10));
+        files.insert("a.txt", &b"second"[..]);
+
+        let mut modified = 0;
+
+        for _ in 0..10 {
+            frames(&mut app, 1);
+
+            let strings = app.world().read_resource::<Assets<String>>().unwrap();
+            modified += strings
+                .read_events(&mut reader)
+                .filter(|event| matches!(event, AssetEvent::Modified(handle) if *handle == a))
+                .count();
+        }
+
+        // reloaded once, in place
+        assert_eq!(modified, 1);
+
+        let strings = app.world().read_resource::<Assets<String>>().unwrap();
+        assert_eq!(strings.get(&a).map(String::as_str), Some("second"));
+        drop(strings);
+
+        // files whose assets were dropped aren't watched
+        let weak = a.clone_weak();
+        drop(a);
+        frames(&mut app, 1);
+
+        files.insert("a.txt", &b"third"[..]);
+        frames(&mut app, 10);
+
+        let strings = app.world().read_resource::<Assets<String>>().unwrap();
+        assert!(!strings.contains(&weak));
+    }
+
+    #[test]
+    fn labels() {
+        let files = Arc::new(MemoryIo::new());
+        files.insert("a.txt", &b"x: 1\ny: 2"[..]);
+
+        let mut app = app(files);
+
+        let asset_server = app.world().read_resource::<AssetServer>().unwrap();
+        let x = asset_server.load::<u32>("a.txt#x");
+        let missing = asset_server.load::<u32>("a.txt#z");
+        let wrong_type = asset_server.load::<String>("a.txt#x");
+        drop(asset_server);
+
+        frames(&mut app, 10);
+
+        let asset_server = app.world().read_resource::<AssetServer>().unwrap();
+        assert!(asset_server.load_state(&x).is_loaded());
+        assert!(asset_server.load_state(&missing).is_failed());
+        assert!(asset_server.load_state(&wrong_type).is_failed());
+        drop(asset_server);
+
+        // the file and its other sub-assets live as long as `x`
+        let y = Handle::<u32>::weak(AssetPath::parse("a.txt#y"));
+        let numbers = app.world().read_resource::<Assets<u32>>().unwrap();
+        assert_eq!(numbers.get(&x), Some(&1));
+        assert_eq!(numbers.get(&y), Some(&2));
+        drop(numbers);
+
+        frames(&mut app, 2);
+
+        let strings = app.world().read_resource::<Assets<String>>().unwrap();
+        let numbers = app.world().read_resource::<Assets<u32>>().unwrap();
+        assert_eq!(strings.len(), 1);
+        assert_eq!(numbers.get(&y), Some(&2));
+        drop(strings);
+        drop(numbers);
+
+        drop(x);
+        frames(&mut app, 2);
+
+        let strings = app.world().read_resource::<Assets<String>>().unwrap();
+        let numbers = app.world().read_resource::<Assets<u32>>().unwrap();
+        assert!(strings.is_empty());
+        assert!(numbers.is_empty());
+    }
+
+    #[test]
+    fn loaders() {
+        let files = Arc::new(MemoryIo::new());
+        files.insert("a.txt", &b"x: 1"[..]);
+
+        let mut app = app(files.clone());
+
+        // the same file loaded as different asset types by different loaders
+        let asset_server = app.world().read_resource::<AssetServer>().unwrap();
+        let text = asset_server.load::<String>("a.txt");
+        let len = asset_server.load::<usize>("a.txt");
+        let x = asset_server.load::<u32>("a.txt#x");
+        drop(asset_server);
+
+        frames(&mut app, 10);
+
+        let asset_server = app.world().read_resource::<AssetServer>().unwrap();
+        assert!(asset_server.load_state(&text).is_loaded());
+        assert!(asset_server.load_state(&len).is_loaded());
+        assert!(asset_server.load_state(&x).is_loaded());
+        drop(asset_server);
+
+        // and reloaded by each of them
+        thread::sleep(Duration::from_millis(10));
+        files.insert("a.txt", &b"x: 10"[..]);
+        frames(&mut app, 10);
+
+        let lens = app.world().read_resource::<Assets<usize>>().unwrap();
+        let numbers = app.world().read_resource::<Assets<u32>>().unwrap();
+        assert_eq!(lens.get(&len), Some(&5));
+        assert_eq!(numbers.get(&x), Some(&10));
+    }
+
+    #[test]
+    fn dependencies() {
+        let files = Arc::new(MemoryIo::new());
+        files.insert("a.txt", &b"text"[..]);
+        files.insert("b.lnk", &b"a.txt"[..]);
+        files.insert("c.lnk", &b"missing.txt"[..]);
+
+        let mut app = app(files.clone());
+
+        let asset_server = app.world().read_resource::<AssetServer>().unwrap();
+        let b = asset_server.load::<Link>("b.lnk");
+        let c = asset_server.load::<Link>("c.lnk");
+        drop(asset_server);
+
+        frames(&mut app, 10);
+
+        let asset_server = app.world().read_resource::<AssetServer>().unwrap();
+        let a = HandleUntyped::Path(AssetPath::parse("a.txt"));
+        assert_eq!(asset_server.dependencies(&b), [a]);
+
+        assert!(asset_server.load_state(&b).is_loaded());
+        assert!(asset_server.load_state_recursive(&b).is_loaded());
+
+        // loaded, but not the file it depends on
+        assert!(asset_server.load_state(&c).is_loaded());
+        assert!(asset_server.load_state_recursive(&c).is_failed());
+        drop(asset_server);
+
+        // files are reloaded with their dependencies
+        let mut reader = AssetEventReader::new();
+        let links = app.world().read_resource::<Assets<Link>>().unwrap();
+        links.read_events(&mut reader).count();
+        drop(links);
+
+        thread::sleep(Duration::from_millis(10));
+        files.insert("a.txt", &b"changed"[..]);
+
+        let mut modified = Vec::new();
+
+        for _ in 0..10 {
+            frames(&mut app, 1);
+
+            let links = app.world().read_resource::<Assets<Link>>().unwrap();
+            modified.extend(
+                links
+                    .read_events(&mut reader)
+                    .filter_map(|event| match event {
+                        AssetEvent::Modified(handle) => Some(handle.clone()),
+                        _ => None,
+                    }),
+            );
+        }
+
+        assert_eq!(modified, [b.clone_weak()]);
+
+        let links = app.world().read_resource::<Assets<Link>>().unwrap();
+        let strings = app.world().read_resource::<Assets<String>>().unwrap();
+        let text = &links.get(&b).unwrap().0;
+        assert_eq!(strings.get(text).map(String::as_str), Some("changed"));
+    }
+}
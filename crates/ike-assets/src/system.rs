@@ -1,7 +1,189 @@
-use ike_core::ResMut;
+use std::any::{type_name, TypeId};
 
-use crate::Assets;
+use ike_core::{diagnostic, Diagnostics, Res, ResMut, World};
+
+use crate::{AssetServer, AssetSize, Assets};
+
+pub fn asset_watch_system(asset_server: Res<AssetServer>) {
+    asset_server.check_for_changes();
+}
+
+pub fn asset_server_system<T: Send + Sync + 'static>(
+    asset_server: Res<AssetServer>,
+    mut assets: ResMut<Assets<T>>,
+) {
+    for (handle, asset) in asset_server.take_loaded::<T>() {
+        assets.replace(&handle, asset);
+    }
+}
 
 pub fn asset_system<T>(mut assets: ResMut<Assets<T>>) {
     assets.clean();
+    assets.update_events();
+}
+
+/// Evicts assets over the budget of [`Assets<T>`], and loads again the evicted assets
+/// accessed since.
+pub fn asset_budget_system<T: AssetSize + Send + Sync + 'static>(
+    asset_server: Res<AssetServer>,
+    mut assets: ResMut<Assets<T>>,
+) {
+    for handle in assets.evict() {
+        asset_server.set_evicted(&handle);
+    }
+
+    for handle in assets.take_reloads() {
+        asset_server.reload(&handle);
+    }
+}
+
+// measures the assets of one type
+type MeasureFn = fn(&World, &mut Diagnostics);
+
+/// The asset types measured by [`asset_diagnostics_system`], every asset is counted and
+/// the memory held by assets added with [`add_sized_asset`](crate::AssetAppBuilderExt::add_sized_asset)
+/// is measured.
+#[derive(Default)]
+pub struct AssetDiagnostics {
+    measures: Vec<(TypeId, MeasureFn)>,
+}
+
+impl AssetDiagnostics {
+    #[inline]
+    pub fn add<T: Send + Sync + 'static>(&mut self) {
+        fn measure<T: Send + Sync + 'static>(world: &World, diagnostics: &mut Diagnostics) {
+            if let Some(assets) = world.read_resource::<Assets<T>>() {
+                measure_count(&assets, diagnostics);
+            }
+        }
+
+        self.insert::<T>(measure::<T>);
+    }
+
+    #[inline]
+    pub fn add_sized<T: AssetSize + Send + Sync + 'static>(&mut self) {
+        fn measure<T: AssetSize + Send + Sync + 'static>(
+            world: &World,
+            diagnostics: &mut Diagnostics,
+        ) {
+            if let Some(assets) = world.read_resource::<Assets<T>>() {
+                let name = type_name::<T>();
+
+                measure_count(&assets, diagnostics);
+                diagnostics.measure(
+                    format!("{}{}", diagnostic::ASSET_BYTES, name),
+                    assets.cpu_size() as f64,
+                );
+                diagnostics.measure(
+                    format!("{}{}", diagnostic::ASSET_GPU_BYTES, name),
+                    assets.gpu_size() as f64,
+                );
+            }
+        }
+
+        self.insert::<T>(measure::<T>);
+    }
+
+    #[inline]
+    fn insert<T: 'static>(&mut self, measure: MeasureFn) {
+        match self
+            .measures
+            .iter_mut()
+            .find(|(id, _)| *id == TypeId::of::<T>())
+        {
+            Some((_, existing)) => *existing = measure,
+            None => self.measures.push((TypeId::of::<T>(), measure)),
+        }
+    }
+}
+
+#[inline]
+fn measure_count<T>(assets: &Assets<T>, diagnostics: &mut Diagnostics) {
+    diagnostics.measure(
+        format!("{}{}", diagnostic::ASSET_COUNT, type_name::<T>()),
+        assets.len() as f64,
+    );
+}
+
+/// Measures every asset type in [`AssetDiagnostics`], a single system so measuring doesn't
+/// serialize the systems of each asset type on [`Diagnostics`].
+pub fn asset_diagnostics_system(world: &World) {
+    let mut diagnostics = match world.write_resource::<Diagnostics>() {
+        Some(diagnostics) => diagnostics,
+        None => return,
+    };
+
+    let asset_diagnostics = match world.read_resource::<AssetDiagnostics>() {
+        Some(asset_diagnostics) => asset_diagnostics,
+        None => return,
+    };
+
+    for (_, measure) in &asset_diagnostics.measures {
+        measure(world, &mut diagnostics);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ike_core::{App, Diagnostic, FnSystem, System};
+
+    use super::*;
+    use crate::AssetAppBuilderExt;
+
+    struct Sized(usize);
+
+    impl AssetSize for Sized {
+        fn cpu_size(&self) -> usize {
+            self.0
+        }
+
+        fn gpu_size(&self) -> usize {
+            self.0 * 2
+        }
+    }
+
+    #[test]
+    fn diagnostics() {
+        let mut app = App::new();
+        app.add_asset::<String>();
+        app.add_sized_asset::<Sized>();
+
+        let systems = app
+            .plan()
+            .stages
+            .iter()
+            .flat_map(|stage| stage.schedule.systems())
+            .filter(|(_, system)| system.name.contains("asset_diagnostics_system"))
+            .count();
+        assert_eq!(systems, 1);
+
+        let world = app.world_mut();
+        world.insert_resource(Diagnostics::new());
+
+        let mut strings = world.write_resource::<Assets<String>>().unwrap();
+        let _a = strings.add(String::from("a"));
+        drop(strings);
+
+        let mut sized = world.write_resource::<Assets<Sized>>().unwrap();
+        let _b = sized.add(Sized(10));
+        let _c = sized.add(Sized(5));
+        drop(sized);
+
+        asset_diagnostics_system.system().run(world);
+
+        let diagnostics = world.read_resource::<Diagnostics>().unwrap();
+        let value = |prefix: &str, name: &str| {
+            let name = format!("{}{}", prefix, name);
+            diagnostics.get(&name).and_then(Diagnostic::value)
+        };
+
+        let string = type_name::<String>();
+        let sized = type_name::<Sized>();
+
+        assert_eq!(value(diagnostic::ASSET_COUNT, string), Some(1.0));
+        assert_eq!(value(diagnostic::ASSET_BYTES, string), None);
+        assert_eq!(value(diagnostic::ASSET_COUNT, sized), Some(2.0));
+        assert_eq!(value(diagnostic::ASSET_BYTES, sized), Some(15.0));
+        assert_eq!(value(diagnostic::ASSET_GPU_BYTES, sized), Some(30.0));
+    }
 }
@@ -1,6 +1,6 @@
-use std::{alloc::{alloc, dealloc, Layout}, any::TypeId, mem, ptr, sync::atomic::{AtomicU64, Ordering}};
+use std::{alloc::{alloc, dealloc, Layout}, borrow::Cow, mem, ptr, sync::atomic::{AtomicU64, Ordering}};
 
-use crate::{AtomicBorrow, Entity, ReadGuard, WriteGuard};
+use crate::{AtomicBorrow, ComponentDescriptor, ComponentId, ComponentInfo, Entity, OwnedComponent, ReadGuard, WriteGuard};
 
 pub trait AnyComponent: Send + Sync + 'static {}
 
@@ -24,9 +24,12 @@ impl ComponentState {
 }
 
 pub struct ComponentStorage {
-    ty: TypeId,
+    id: ComponentId,
+    name: Cow<'static, str>,
     layout: Layout,
-    drop: unsafe fn(*mut u8),
+    drop: Option<unsafe fn(*mut u8)>,
+    // moves a typed component out of the storage, dynamic storages don't have one
+    take: Option<unsafe fn(*mut u8) -> OwnedComponent>,
     len: usize,
     cap: usize,
     base: Option<*mut u8>,
@@ -45,10 +48,33 @@ impl ComponentStorage {
             unsafe { std::ptr::drop_in_place(component as *mut T) }
         }
 
+        unsafe fn take_fn<T: AnyComponent>(component: *mut u8) -> OwnedComponent {
+            OwnedComponent::new(unsafe { ptr::read(component as *mut T) })
+        }
+
         Self {
-            ty: TypeId::of::<T>(),
+            id: ComponentId::of::<T>(),
+            name: Cow::Borrowed(std::any::type_name::<T>()),
             layout: Layout::new::<T>().pad_to_align(),
-            drop: drop_fn::<T>,
+            drop: Some(drop_fn::<T>),
+            take: Some(take_fn::<T>),
+            len: 0,
+            cap: 0,
+            base: None,
+            entities: Vec::new(),
+            component_state: Vec::new(),
+            storage_borrow: AtomicBorrow::new(),
+        }
+    }
+
+    #[inline]
+    pub fn from_descriptor(id: ComponentId, descriptor: ComponentDescriptor) -> Self {
+        Self {
+            id,
+            name: descriptor.name,
+            layout: descriptor.layout.pad_to_align(),
+            drop: descriptor.drop,
+            take: None,
             len: 0,
             cap: 0,
             base: None,
@@ -59,8 +85,26 @@ impl ComponentStorage {
     }
 
     #[inline]
-    pub fn ty(&self) -> TypeId {
-        self.ty
+    pub fn id(&self) -> ComponentId {
+        self.id
+    }
+
+    #[inline]
+    pub fn info(&self) -> ComponentInfo<'_> {
+        ComponentInfo {
+            id: self.id,
+            name: &self.name,
+            layout: self.layout,
+        }
+    }
+
+    #[inline]
+    pub fn descriptor(&self) -> ComponentDescriptor {
+        ComponentDescriptor {
+            name: self.name.clone(),
+            layout: self.layout,
+            drop: self.drop,
+        }
     }
 
     #[inline]
@@ -148,6 +192,16 @@ impl ComponentStorage {
 
     #[inline]
     pub unsafe fn insert_unchecked<T: AnyComponent>(&mut self, entity: Entity, component: T, change_frame: u64) {
+        let mut component = mem::ManuallyDrop::new(component);
+
+        unsafe { self.insert_raw(entity, &mut *component as *mut T as *mut u8, change_frame) };
+    }
+
+    /// # Safety
+    /// - `component` must point to a valid value of this storage's component,
+    ///   ownership of which is moved into the storage.
+    #[inline]
+    pub unsafe fn insert_raw(&mut self, entity: Entity, component: *mut u8, change_frame: u64) {
         let idx = entity.idx() as usize;
 
         if self.cap <= idx {
@@ -157,26 +211,55 @@ impl ComponentStorage {
                 .resize_with(self.cap, || ComponentState::new());
         }
 
-        self.len += 1;
-
         let state = &mut self.component_state[idx];
-        state.gen = Some(entity.gen());
-        state.borrow = AtomicBorrow::new();
-        state.changed = AtomicU64::new(change_frame);
 
-        let ptr = unsafe { self.index_ptr(idx) as *mut T };
+        if state.gen.is_some() {
+            let ptr = unsafe { self.index_ptr(idx) };
 
-        unsafe {
-            ptr::copy_nonoverlapping(&component as *const T, ptr, 1);
+            if let Some(drop) = self.drop {
+                unsafe { drop(ptr) };
+            }
+
+            let state = &mut self.component_state[idx];
+            state.gen = Some(entity.gen());
+            state.changed = AtomicU64::new(change_frame);
+
+            if let Some(e) = self.entities.iter_mut().find(|e| e.idx() == entity.idx()) {
+                *e = entity;
+            }
+        } else {
+            self.len += 1;
+
+            state.gen = Some(entity.gen());
+            state.borrow = AtomicBorrow::new();
+            state.changed = AtomicU64::new(change_frame);
+
+            self.entities.push(entity);
         }
 
-        mem::forget(component);
+        let ptr = unsafe { self.index_ptr(idx) };
 
-        self.entities.push(entity);
+        unsafe {
+            ptr::copy_nonoverlapping(component, ptr, self.layout.size());
+        }
     }
 
     #[inline]
     pub unsafe fn remove_unchecked<T: AnyComponent>(&mut self, entity: &Entity) -> Option<T> {
+        let ptr = unsafe { self.remove_raw(entity)? } as *mut T;
+
+        let component = unsafe { ptr::read(ptr) };
+
+        Some(component)
+    }
+
+    /// Removes `entity` from the storage without dropping its component.
+    ///
+    /// # Safety
+    /// - the returned pointer is only valid until the storage is modified again,
+    ///   and the caller is responsible for moving out of or dropping the component.
+    #[inline]
+    pub unsafe fn remove_raw(&mut self, entity: &Entity) -> Option<*mut u8> {
         if !self.contains(entity) {
             return None;
         }
@@ -190,11 +273,21 @@ impl ComponentStorage {
 
         self.component_state[idx].gen = None;
 
-        let ptr = unsafe { self.index_ptr(idx) as *mut T };
+        Some(unsafe { self.index_ptr(idx) })
+    }
 
-        let component = unsafe { ptr::read(ptr) };
+    /// Removes the component of `entity`, moving it into an [`OwnedComponent`] for typed storages.
+    ///
+    /// # Safety
+    /// - see [`ComponentStorage::remove_raw`] for dynamic storages, where the pointer is returned.
+    #[inline]
+    pub(crate) unsafe fn take(&mut self, entity: &Entity) -> Option<Result<OwnedComponent, *mut u8>> {
+        let ptr = unsafe { self.remove_raw(entity)? };
 
-        Some(component)
+        Some(match self.take {
+            Some(take) => Ok(unsafe { take(ptr) }),
+            None => Err(ptr),
+        })
     }
 
     #[inline]
@@ -306,6 +399,75 @@ impl ComponentStorage {
             borrow: vec![&state.borrow, &self.storage_borrow],
         })
     }
+    /// # Safety
+    /// - the caller must uphold the borrow rules for the returned bytes.
+    #[inline]
+    #[allow(clippy::mut_from_ref)]
+    pub unsafe fn get_bytes_unchecked(&self, entity: &Entity) -> Option<&mut [u8]> {
+        if !self.contains(entity) {
+            return None;
+        }
+
+        let ptr = unsafe { self.index_ptr(entity.idx() as usize) };
+
+        Some(unsafe { std::slice::from_raw_parts_mut(ptr, self.layout.size()) })
+    }
+
+    /// # Safety
+    /// - the storage must hold plain byte components, see [`ComponentDescriptor`].
+    #[inline]
+    pub unsafe fn get_borrowed_bytes(&self, entity: &Entity) -> Option<ReadGuard<'_, [u8]>> {
+        if !self.contains(entity) {
+            return None;
+        }
+
+        if !self.borrow() {
+            return None;
+        }
+
+        let state = &self.component_state[entity.idx() as usize];
+
+        if !state.borrow.borrow() {
+            self.release();
+            return None;
+        }
+
+        Some(ReadGuard {
+            value: unsafe { self.get_bytes_unchecked(entity)? },
+            borrow: vec![&state.borrow, &self.storage_borrow],
+        })
+    }
+
+    /// # Safety
+    /// - the storage must hold plain byte components, see [`ComponentDescriptor`].
+    #[inline]
+    pub unsafe fn get_borrowed_bytes_mut(
+        &self,
+        entity: &Entity,
+        change_frame: u64,
+    ) -> Option<WriteGuard<'_, [u8]>> {
+        if !self.contains(entity) {
+            return None;
+        }
+
+        if !self.borrow_mut() {
+            return None;
+        }
+
+        let state = &self.component_state[entity.idx() as usize];
+
+        if !state.borrow.borrow_mut() {
+            self.release_mut();
+            return None;
+        }
+
+        state.changed.store(change_frame, Ordering::Release);
+
+        Some(WriteGuard {
+            value: unsafe { self.get_bytes_unchecked(entity)? },
+            borrow: vec![&state.borrow, &self.storage_borrow],
+        })
+    }
 }
 
 impl Drop for ComponentStorage {
@@ -315,7 +477,9 @@ impl Drop for ComponentStorage {
             if state.gen.is_some() {
                 let ptr = unsafe { self.index_ptr(idx) };
 
-                unsafe { (self.drop)(ptr) };
+                if let Some(drop) = self.drop {
+                    unsafe { drop(ptr) };
+                }
             } 
         }
 
@@ -1,6 +1,6 @@
-use std::{any::TypeId, collections::HashMap};
+use std::any::TypeId;
 
-use crate::{Component, ExclusiveSystem, Node, Plugin, Resource, Schedule, System, World};
+use crate::{Component, ComponentId, RelationKind, ExclusiveSystem, ExecutionPlan, StagePlan, Node, Plugin, Resource, Rng, Schedule, System, World};
 
 pub mod stage {
     pub const START: &str = "start";
@@ -15,17 +15,45 @@ pub trait AppRunner: 'static {
     fn run(&mut self, app: App);
 }
 
-#[derive(Clone, Debug, Default)]
+/// Frame timing, `time_since_startup` and `delta_time` follow the virtual clock,
+/// which stops while paused and is scaled by `time_scale`.
+#[derive(Clone, Debug)]
 pub struct Time {
     time: f32,
     delta_time: f32,
+    real_time: f32,
+    real_delta_time: f32,
+    time_scale: f32,
+    paused: bool,
+}
+
+impl Default for Time {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            time: 0.0,
+            delta_time: 0.0,
+            real_time: 0.0,
+            real_delta_time: 0.0,
+            time_scale: 1.0,
+            paused: false,
+        }
+    }
 }
 
 impl Time {
     #[inline]
     pub fn advance_frame(&mut self, delta_time: f32) {
-        self.time += delta_time;
-        self.delta_time = delta_time;
+        self.real_time += delta_time;
+        self.real_delta_time = delta_time;
+
+        if self.paused {
+            self.delta_time = 0.0;
+        } else {
+            self.delta_time = delta_time * self.time_scale;
+        }
+
+        self.time += self.delta_time;
     }
 
     #[inline]
@@ -38,9 +66,44 @@ impl Time {
         self.delta_time
     }
 
+    #[inline]
+    pub fn real_time_since_startup(&self) -> f32 {
+        self.real_time
+    }
+
+    #[inline]
+    pub fn real_delta_time(&self) -> f32 {
+        self.real_delta_time
+    }
+
     #[inline]
     pub fn frames_per_second(&self) -> f32 {
-        1.0 / self.delta_time
+        1.0 / self.real_delta_time
+    }
+
+    #[inline]
+    pub fn time_scale(&self) -> f32 {
+        self.time_scale
+    }
+
+    #[inline]
+    pub fn set_time_scale(&mut self, time_scale: f32) {
+        self.time_scale = time_scale;
+    }
+
+    #[inline]
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    #[inline]
+    pub fn unpause(&mut self) {
+        self.paused = false;
+    }
+
+    #[inline]
+    pub fn is_paused(&self) -> bool {
+        self.paused
     }
 }
 
@@ -66,6 +129,11 @@ impl AppBuilder {
         &mut self.app.world
     }
 
+    #[inline]
+    pub fn plan(&self) -> ExecutionPlan {
+        self.app.plan()
+    }
+
     #[inline]
     pub fn add_stage(&mut self, stage: &'static str) -> &mut Self {
         self.app.stages.push((stage, Schedule::default()));
@@ -173,8 +241,27 @@ impl AppBuilder {
             component.update(node, node.world());
         }
 
-        self.app.components.insert(TypeId::of::<T>(), update::<T>);
+        let components = &mut self.app.components;
+
+        if let Some((_, existing)) = components.iter_mut().find(|(id, _)| *id == TypeId::of::<T>()) {
+            *existing = update::<T>;
+        } else {
+            components.push((TypeId::of::<T>(), update::<T>));
+        }
+
+        self
+    }
 
+    #[inline]
+    pub fn register_relation<R: RelationKind>(&mut self) -> &mut Self {
+        self.world_mut().register_relation::<R>();
+        self
+    }
+
+    #[inline]
+    pub fn set_deterministic(&mut self, seed: u64) -> &mut Self {
+        self.world_mut().set_deterministic(true);
+        self.insert_resource(Rng::new(seed));
         self
     }
 
@@ -198,10 +285,12 @@ impl AppBuilder {
     }
 }
 
+type ComponentUpdate = fn(&mut Node);
+
 #[derive(Default)]
 pub struct App {
     world: World,
-    components: HashMap<TypeId, fn(&mut Node)>,
+    components: Vec<(TypeId, ComponentUpdate)>,
     startup: Schedule,
     stages: Vec<(&'static str, Schedule)>,
 }
@@ -231,6 +320,25 @@ impl App {
         &mut self.world
     }
 
+    #[inline]
+    pub fn stages(&self) -> impl Iterator<Item = (&'static str, &Schedule)> {
+        self.stages.iter().map(|(name, schedule)| (*name, schedule))
+    }
+
+    #[inline]
+    pub fn plan(&self) -> ExecutionPlan {
+        ExecutionPlan {
+            startup: self.startup.plan(),
+            stages: self
+                .stages()
+                .map(|(name, schedule)| StagePlan {
+                    name,
+                    schedule: schedule.plan(),
+                })
+                .collect(),
+        }
+    }
+
     #[inline]
     pub fn execute_startup(&mut self) {
         self.startup.execute(&mut self.world);
@@ -246,7 +354,7 @@ impl App {
     #[inline]
     pub fn update_components(&mut self) {
         for (id, update) in self.components.iter() {
-            if let Some(storage) = self.world.components.get(id) {
+            if let Some(storage) = self.world.components.get(&ComponentId::from(*id)) {
                 for entity in storage.entities() {
                     let mut node = self.world.get_node(*entity).unwrap();
 
@@ -261,6 +369,32 @@ impl App {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use crate::{FnSystem, Res, ResMut};
+
+    #[test]
+    fn plan() {
+        fn write(_value: ResMut<u32>) {}
+        fn read(_value: Res<u32>) {}
+
+        let mut app = App::new();
+        app.add_startup_system(write.system());
+        app.add_startup_system(read.system());
+        app.add_system(read.system());
+
+        let plan = app.plan();
+
+        assert_eq!(plan.startup.steps.len(), 2);
+
+        let ambiguities = plan.ambiguities();
+        assert_eq!(ambiguities.len(), 1);
+        assert_eq!(ambiguities[0].0, ExecutionPlan::STARTUP);
+
+        let dot = plan.to_dot();
+        assert!(dot.contains("label=\"startup\""));
+        assert!(dot.contains("label=\"update\""));
+    }
+
     #[test]
     fn temp() {
         fn t<'a>(a: &*mut *const f32, x: &'a f32) -> &'a Repr {
@@ -1,6 +1,80 @@
+use std::{alloc::Layout, any::TypeId, borrow::Cow};
+
 use crate::{AnyComponent, Node, World};
 
 #[allow(unused)]
 pub trait Component: AnyComponent {
     fn update(&mut self, node: &mut Node<'_>, world: &World) {}
 }
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ComponentId {
+    Type(TypeId),
+    Dynamic(u64),
+}
+
+impl ComponentId {
+    #[inline]
+    pub fn of<T: AnyComponent>() -> Self {
+        Self::Type(TypeId::of::<T>())
+    }
+
+    #[inline]
+    pub fn is_dynamic(&self) -> bool {
+        matches!(self, Self::Dynamic(_))
+    }
+}
+
+impl From<TypeId> for ComponentId {
+    #[inline]
+    fn from(type_id: TypeId) -> Self {
+        Self::Type(type_id)
+    }
+}
+
+/// Describes a component type that only exists at runtime.
+///
+/// Dynamic components are plain bytes, any byte pattern of `layout.size()` bytes
+/// must be a valid value.
+#[derive(Clone, Debug)]
+pub struct ComponentDescriptor {
+    pub(crate) name: Cow<'static, str>,
+    pub(crate) layout: Layout,
+    pub(crate) drop: Option<unsafe fn(*mut u8)>,
+}
+
+impl ComponentDescriptor {
+    #[inline]
+    pub fn new(name: impl Into<Cow<'static, str>>, layout: Layout) -> Self {
+        Self {
+            name: name.into(),
+            layout,
+            drop: None,
+        }
+    }
+
+    /// # Safety
+    /// - `drop` must be safe to call with a pointer to any value of this component.
+    #[inline]
+    pub unsafe fn with_drop(
+        name: impl Into<Cow<'static, str>>,
+        layout: Layout,
+        drop: unsafe fn(*mut u8),
+    ) -> Self {
+        Self {
+            name: name.into(),
+            layout,
+            drop: Some(drop),
+        }
+    }
+
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[inline]
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+}
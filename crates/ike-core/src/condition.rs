@@ -0,0 +1,130 @@
+use std::borrow::Cow;
+
+use crate::{Resource, System, SystemAccess, World};
+
+pub trait RunCondition: Fn(&World) -> bool + Send + Sync + 'static {}
+
+impl<T: Fn(&World) -> bool + Send + Sync + 'static> RunCondition for T {}
+
+/// A system that only runs when `condition` returns true.
+pub struct RunIf<S, C> {
+    system: S,
+    condition: C,
+}
+
+impl<S: System, C: RunCondition> System for RunIf<S, C> {
+    #[inline]
+    fn access(&self) -> SystemAccess {
+        self.system.access()
+    }
+
+    #[inline]
+    fn run(&mut self, world: &World) {
+        if (self.condition)(world) {
+            self.system.run(world);
+        }
+    }
+
+    #[inline]
+    fn name(&self) -> Cow<'static, str> {
+        self.system.name()
+    }
+}
+
+pub trait RunIfExt: System + Sized {
+    fn run_if<C: RunCondition>(self, condition: C) -> RunIf<Self, C>;
+}
+
+impl<S: System> RunIfExt for S {
+    #[inline]
+    fn run_if<C: RunCondition>(self, condition: C) -> RunIf<Self, C> {
+        RunIf {
+            system: self,
+            condition,
+        }
+    }
+}
+
+#[inline]
+pub fn resource_exists<T: Resource>() -> impl RunCondition + Copy {
+    |world: &World| world.has_resource::<T>()
+}
+
+#[inline]
+pub fn resource_added<T: Resource>() -> impl RunCondition + Copy {
+    |world: &World| world.is_resource_added::<T>()
+}
+
+#[inline]
+pub fn resource_changed<T: Resource>() -> impl RunCondition + Copy {
+    |world: &World| world.is_resource_changed::<T>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FnSystem, Res, ResMut};
+
+    #[derive(Default)]
+    struct Runs(u32);
+
+    #[test]
+    fn resource_changed_condition() {
+        fn write(mut value: ResMut<u32>) {
+            *value += 1;
+        }
+
+        fn read(value: ResMut<u32>) {
+            assert!(!value.is_changed());
+        }
+
+        fn count(mut runs: ResMut<Runs>, value: Res<u32>) {
+            assert!(value.is_changed());
+            runs.0 += 1;
+        }
+
+        let mut world = World::new();
+        world.insert_resource(0u32);
+        world.init_resource::<Runs>();
+
+        assert!(world.is_resource_added::<u32>());
+
+        world.clear_trackers();
+
+        assert!(!world.is_resource_added::<u32>());
+
+        let mut read = read.system();
+        let mut write = write.system();
+        let mut count = count.system().run_if(resource_changed::<u32>());
+
+        read.run(&world);
+        count.run(&world);
+        assert_eq!(world.read_resource::<Runs>().unwrap().0, 0);
+
+        write.run(&world);
+        count.run(&world);
+        assert_eq!(world.read_resource::<Runs>().unwrap().0, 1);
+
+        world.clear_trackers();
+
+        count.run(&world);
+        assert_eq!(world.read_resource::<Runs>().unwrap().0, 1);
+    }
+
+    #[test]
+    fn write_resource_changed() {
+        let mut world = World::new();
+        world.insert_resource(0u32);
+        world.clear_trackers();
+
+        let value = world.write_resource::<u32>().unwrap();
+        assert_eq!(*value, 0);
+        drop(value);
+
+        assert!(!world.is_resource_changed::<u32>());
+
+        *world.write_resource::<u32>().unwrap() += 1;
+
+        assert!(world.is_resource_changed::<u32>());
+    }
+}
@@ -0,0 +1,495 @@
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashSet, VecDeque},
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::PathBuf,
+};
+
+use crate::{
+    resource_exists, stage, AppBuilder, ExclusiveSystem, FnSystem, Plugin, Res, ResMut, RunIfExt,
+    Time, World,
+};
+
+/// Names of the diagnostics measured by the engine, prefixes are followed by a type or node name.
+///
+/// Times are measured in milliseconds.
+pub mod diagnostic {
+    pub const FRAME_TIME: &str = "frame_time";
+    pub const FPS: &str = "fps";
+    pub const ENTITY_COUNT: &str = "entity_count";
+    pub const COMPONENT_COUNT: &str = "component_count/";
+    pub const RENDER_NODE_TIME: &str = "render/node_time/";
+    pub const DRAW_CALLS: &str = "render/draw_calls";
+    pub const TRIANGLES: &str = "render/triangles";
+    pub const PHYSICS_STEP_TIME: &str = "physics/step_time";
+    pub const ASSET_COUNT: &str = "assets/count/";
+    pub const ASSET_BYTES: &str = "assets/bytes/";
+    pub const ASSET_GPU_BYTES: &str = "assets/gpu_bytes/";
+    pub const GPU_BYTES: &str = "render/gpu_bytes";
+}
+
+/// A measured value with a rolling history of the most recent measurements.
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+    name: Cow<'static, str>,
+    suffix: &'static str,
+    history: VecDeque<f64>,
+    max_history: usize,
+    sum: f64,
+}
+
+impl Diagnostic {
+    pub const DEFAULT_MAX_HISTORY: usize = 120;
+
+    #[inline]
+    pub fn new(name: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            name: name.into(),
+            suffix: "",
+            history: VecDeque::new(),
+            max_history: Self::DEFAULT_MAX_HISTORY,
+            sum: 0.0,
+        }
+    }
+
+    /// Sets the unit printed after values, like `"ms"`.
+    #[inline]
+    pub fn with_suffix(mut self, suffix: &'static str) -> Self {
+        self.suffix = suffix;
+        self
+    }
+
+    #[inline]
+    pub fn with_max_history(mut self, max_history: usize) -> Self {
+        self.set_max_history(max_history);
+        self
+    }
+
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[inline]
+    pub fn suffix(&self) -> &'static str {
+        self.suffix
+    }
+
+    #[inline]
+    pub fn max_history(&self) -> usize {
+        self.max_history
+    }
+
+    #[inline]
+    pub fn set_max_history(&mut self, max_history: usize) {
+        self.max_history = max_history.max(1);
+
+        while self.history.len() > self.max_history {
+            self.pop();
+        }
+    }
+
+    #[inline]
+    fn pop(&mut self) {
+        if let Some(value) = self.history.pop_front() {
+            self.sum -= value;
+        }
+    }
+
+    #[inline]
+    pub fn add_measurement(&mut self, value: f64) {
+        if self.history.len() == self.max_history {
+            self.pop();
+        }
+
+        self.history.push_back(value);
+        self.sum += value;
+    }
+
+    /// The most recent measurement.
+    #[inline]
+    pub fn value(&self) -> Option<f64> {
+        self.history.back().copied()
+    }
+
+    #[inline]
+    pub fn average(&self) -> Option<f64> {
+        if self.history.is_empty() {
+            None
+        } else {
+            Some(self.sum / self.history.len() as f64)
+        }
+    }
+
+    #[inline]
+    pub fn min(&self) -> Option<f64> {
+        self.history.iter().copied().reduce(f64::min)
+    }
+
+    #[inline]
+    pub fn max(&self) -> Option<f64> {
+        self.history.iter().copied().reduce(f64::max)
+    }
+
+    /// Measurements oldest first.
+    #[inline]
+    pub fn history(&self) -> impl Iterator<Item = f64> + '_ {
+        self.history.iter().copied()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.history.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.history.is_empty()
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.history.clear();
+        self.sum = 0.0;
+    }
+}
+
+/// Registry of [`Diagnostic`]s, ordered by name.
+///
+/// Engine plugins only measure into it when the resource exists, see [`diagnostic`] for their names.
+#[derive(Clone, Debug, Default)]
+pub struct Diagnostics {
+    diagnostics: BTreeMap<Cow<'static, str>, Diagnostic>,
+}
+
+impl Diagnostics {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `diagnostic`, replacing any with the same name.
+    #[inline]
+    pub fn add(&mut self, diagnostic: Diagnostic) {
+        self.diagnostics.insert(diagnostic.name.clone(), diagnostic);
+    }
+
+    #[inline]
+    pub fn get(&self, name: &str) -> Option<&Diagnostic> {
+        self.diagnostics.get(name)
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Diagnostic> {
+        self.diagnostics.get_mut(name)
+    }
+
+    #[inline]
+    pub fn remove(&mut self, name: &str) -> Option<Diagnostic> {
+        self.diagnostics.remove(name)
+    }
+
+    /// Adds a measurement to the diagnostic called `name`, creating it if it doesn't exist.
+    #[inline]
+    pub fn measure(&mut self, name: impl Into<Cow<'static, str>>, value: f64) {
+        let name = name.into();
+
+        match self.diagnostics.get_mut(&name) {
+            Some(diagnostic) => diagnostic.add_measurement(value),
+            None => {
+                let mut diagnostic = Diagnostic::new(name);
+                diagnostic.add_measurement(value);
+                self.add(diagnostic);
+            }
+        }
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics.values()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.diagnostics.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+}
+
+fn frame_diagnostics_system(world: &World, time: Res<Time>, mut diagnostics: ResMut<Diagnostics>) {
+    diagnostics.measure(diagnostic::FRAME_TIME, time.real_delta_time() as f64 * 1000.0);
+    diagnostics.measure(diagnostic::FPS, time.frames_per_second() as f64);
+
+    let mut entities = HashSet::new();
+
+    for storage in world.storages() {
+        entities.extend(storage.entities.iter().copied());
+
+        let name = format!("{}{}", diagnostic::COMPONENT_COUNT, storage.ty.name);
+        diagnostics.measure(name, storage.len as f64);
+    }
+
+    diagnostics.measure(diagnostic::ENTITY_COUNT, entities.len() as f64);
+}
+
+/// Measures frame time, fps, entity count and component counts every frame.
+pub struct FrameDiagnosticsPlugin;
+
+impl Plugin for FrameDiagnosticsPlugin {
+    #[inline]
+    fn build(self, app: &mut AppBuilder) {
+        app.init_resource::<Time>();
+        app.init_resource::<Diagnostics>();
+        app.add_system_to_stage(
+            frame_diagnostics_system
+                .system()
+                .run_if(resource_exists::<Diagnostics>()),
+            stage::END,
+        );
+    }
+}
+
+pub struct LogDiagnosticsSystem {
+    interval: f32,
+    last_log: Option<f32>,
+}
+
+impl ExclusiveSystem for LogDiagnosticsSystem {
+    #[inline]
+    fn run(&mut self, world: &mut World) {
+        let now = match world.read_resource::<Time>() {
+            Some(time) => time.real_time_since_startup(),
+            None => return,
+        };
+
+        match self.last_log {
+            Some(last_log) if now - last_log < self.interval => return,
+            None => {
+                self.last_log = Some(now);
+                return;
+            }
+            _ => self.last_log = Some(now),
+        }
+
+        let diagnostics = match world.read_resource::<Diagnostics>() {
+            Some(diagnostics) => diagnostics,
+            None => return,
+        };
+
+        println!("diagnostics:");
+
+        for diagnostic in diagnostics.iter() {
+            if let (Some(value), Some(average), Some(min), Some(max)) = (
+                diagnostic.value(),
+                diagnostic.average(),
+                diagnostic.min(),
+                diagnostic.max(),
+            ) {
+                let suffix = diagnostic.suffix();
+
+                println!(
+                    "  {:<40} {:>12.3}{} (avg {:.3}{}, min {:.3}{}, max {:.3}{})",
+                    diagnostic.name(),
+                    value,
+                    suffix,
+                    average,
+                    suffix,
+                    min,
+                    suffix,
+                    max,
+                    suffix,
+                );
+            }
+        }
+    }
+}
+
+/// Prints every diagnostic to stdout every `interval` real seconds.
+pub struct LogDiagnosticsPlugin {
+    pub interval: f32,
+}
+
+impl Default for LogDiagnosticsPlugin {
+    #[inline]
+    fn default() -> Self {
+        Self { interval: 1.0 }
+    }
+}
+
+impl Plugin for LogDiagnosticsPlugin {
+    #[inline]
+    fn build(self, app: &mut AppBuilder) {
+        app.init_resource::<Diagnostics>();
+        app.add_exclusive_system_to_stage(
+            LogDiagnosticsSystem {
+                interval: self.interval,
+                last_log: None,
+            },
+            stage::END,
+        );
+    }
+}
+
+/// Writes the latest value of every diagnostic as `frame,diagnostic,value` rows.
+pub struct CsvDiagnosticsSystem {
+    path: PathBuf,
+    writer: Option<BufWriter<File>>,
+    frame: u64,
+    failed: bool,
+}
+
+impl CsvDiagnosticsSystem {
+    #[inline]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            writer: None,
+            frame: 0,
+            failed: false,
+        }
+    }
+
+    #[inline]
+    fn write(&mut self, diagnostics: &Diagnostics) -> io::Result<()> {
+        let writer = match self.writer {
+            Some(ref mut writer) => writer,
+            None => {
+                let mut writer = BufWriter::new(File::create(&self.path)?);
+                writeln!(writer, "frame,diagnostic,value")?;
+                self.writer.insert(writer)
+            }
+        };
+
+        for diagnostic in diagnostics.iter() {
+            if let Some(value) = diagnostic.value() {
+                writeln!(writer, "{},{},{}", self.frame, csv_field(diagnostic.name()), value)?;
+            }
+        }
+
+        writer.flush()
+    }
+}
+
+impl ExclusiveSystem for CsvDiagnosticsSystem {
+    #[inline]
+    fn run(&mut self, world: &mut World) {
+        if self.failed {
+            return;
+        }
+
+        let diagnostics = match world.read_resource::<Diagnostics>() {
+            Some(diagnostics) => diagnostics,
+            None => return,
+        };
+
+        if let Err(err) = self.write(&diagnostics) {
+            eprintln!("failed to write diagnostics to {:?}: {}", self.path, err);
+            self.failed = true;
+        }
+
+        self.frame += 1;
+    }
+}
+
+#[inline]
+fn csv_field(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\n']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+/// Exports diagnostics to a csv file every frame, for comparing runs of headless benchmarks.
+pub struct CsvDiagnosticsPlugin {
+    pub path: PathBuf,
+}
+
+impl CsvDiagnosticsPlugin {
+    #[inline]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl Plugin for CsvDiagnosticsPlugin {
+    #[inline]
+    fn build(self, app: &mut AppBuilder) {
+        app.init_resource::<Diagnostics>();
+        app.add_exclusive_system_to_stage(CsvDiagnosticsSystem::new(self.path), stage::END);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::System;
+
+    #[test]
+    fn diagnostic_history() {
+        let mut diagnostic = Diagnostic::new("test").with_max_history(3);
+
+        assert_eq!(diagnostic.average(), None);
+
+        for value in [4.0, 1.0, 2.0, 3.0] {
+            diagnostic.add_measurement(value);
+        }
+
+        assert_eq!(diagnostic.history().collect::<Vec<_>>(), [1.0, 2.0, 3.0]);
+        assert_eq!(diagnostic.value(), Some(3.0));
+        assert_eq!(diagnostic.average(), Some(2.0));
+        assert_eq!(diagnostic.min(), Some(1.0));
+        assert_eq!(diagnostic.max(), Some(3.0));
+    }
+
+    #[test]
+    fn frame_diagnostics() {
+        let mut world = World::new();
+        world.init_resource::<Time>();
+        world.init_resource::<Diagnostics>();
+
+        let a = world.create_entity();
+        let b = world.create_entity();
+        world.insert(a, 0u32);
+        world.insert(b, 0u32);
+        world.insert(b, true);
+
+        world.write_resource::<Time>().unwrap().advance_frame(0.5);
+
+        frame_diagnostics_system.system().run(&world);
+
+        let diagnostics = world.read_resource::<Diagnostics>().unwrap();
+        let value = |name: &str| diagnostics.get(name).unwrap().value().unwrap();
+
+        assert_eq!(value(diagnostic::FRAME_TIME), 500.0);
+        assert_eq!(value(diagnostic::FPS), 2.0);
+        assert_eq!(value(diagnostic::ENTITY_COUNT), 2.0);
+        assert_eq!(value("component_count/u32"), 2.0);
+    }
+
+    #[test]
+    fn csv_export() {
+        let path = std::env::temp_dir().join(format!("ike_diagnostics_{}.csv", std::process::id()));
+
+        let mut world = World::new();
+        world.init_resource::<Diagnostics>();
+        world
+            .write_resource::<Diagnostics>()
+            .unwrap()
+            .measure("a,b", 1.5);
+
+        let mut system = CsvDiagnosticsSystem::new(&path);
+        system.run(&mut world);
+        system.run(&mut world);
+
+        let csv = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(csv, "frame,diagnostic,value\n0,\"a,b\",1.5\n1,\"a,b\",1.5\n");
+    }
+}
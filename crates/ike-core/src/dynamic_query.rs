@@ -0,0 +1,124 @@
+use std::slice::Iter as SliceIter;
+
+use crate::{Access, ComponentId, ComponentStorage, Entity, World};
+
+pub enum DynamicComponent<'a> {
+    Read(&'a [u8]),
+    Write(&'a mut [u8]),
+}
+
+impl<'a> DynamicComponent<'a> {
+    #[inline]
+    pub fn bytes(&self) -> &[u8] {
+        match self {
+            Self::Read(bytes) => bytes,
+            Self::Write(bytes) => bytes,
+        }
+    }
+
+    #[inline]
+    pub fn bytes_mut(&mut self) -> Option<&mut [u8]> {
+        match self {
+            Self::Read(_) => None,
+            Self::Write(bytes) => Some(bytes),
+        }
+    }
+}
+
+pub struct DynamicQuery<'a> {
+    storages: Vec<(&'a ComponentStorage, Access)>,
+    entities: SliceIter<'a, Entity>,
+    change_tick: u64,
+}
+
+impl<'a> DynamicQuery<'a> {
+    #[inline]
+    pub fn new(world: &'a World, access: &[(ComponentId, Access)]) -> Option<Self> {
+        let mut storages: Vec<(&'a ComponentStorage, Access)> = Vec::with_capacity(access.len());
+
+        for (i, (id, access_type)) in access.iter().enumerate() {
+            let duplicate = access[..i].iter().any(|(other, _)| other == id);
+
+            let storage = world
+                .components
+                .get(id)
+                .filter(|_| id.is_dynamic() && !duplicate);
+
+            let borrowed = storage.is_some_and(|storage| match access_type {
+                Access::Read => storage.borrow(),
+                Access::Write => storage.borrow_mut(),
+            });
+
+            if !borrowed {
+                release(&storages);
+                return None;
+            }
+
+            storages.push((storage.unwrap(), *access_type));
+        }
+
+        let entities = storages
+            .iter()
+            .map(|(storage, _)| storage.entities())
+            .min_by_key(|entities| entities.len())
+            .unwrap_or(&world.entities);
+
+        Some(Self {
+            storages,
+            entities: entities.iter(),
+            change_tick: world.change_tick(),
+        })
+    }
+}
+
+impl<'a> Iterator for DynamicQuery<'a> {
+    type Item = (Entity, Vec<DynamicComponent<'a>>);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        'entities: for entity in self.entities.by_ref() {
+            let mut components = Vec::with_capacity(self.storages.len());
+
+            for (storage, access) in &self.storages {
+                // SAFETY: the storage is borrowed according to access for the lifetime of the query,
+                // and every entity is only visited once.
+                let bytes = match unsafe { storage.get_bytes_unchecked(entity) } {
+                    Some(bytes) => bytes,
+                    None => continue 'entities,
+                };
+
+                components.push(match access {
+                    Access::Read => DynamicComponent::Read(bytes),
+                    Access::Write => DynamicComponent::Write(bytes),
+                });
+            }
+
+            for (storage, access) in &self.storages {
+                if *access == Access::Write {
+                    let changed = storage.get_change_marker(entity).unwrap();
+                    changed.store(self.change_tick, std::sync::atomic::Ordering::Release);
+                }
+            }
+
+            return Some((*entity, components));
+        }
+
+        None
+    }
+}
+
+impl<'a> Drop for DynamicQuery<'a> {
+    #[inline]
+    fn drop(&mut self) {
+        release(&self.storages);
+    }
+}
+
+fn release(storages: &[(&ComponentStorage, Access)]) {
+    for (storage, access) in storages {
+        match access {
+            Access::Read => storage.release(),
+            Access::Write => storage.release_mut(),
+        }
+    }
+}
@@ -1,4 +1,10 @@
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+};
 
 use crossbeam::queue::SegQueue;
 
@@ -31,6 +37,8 @@ pub struct EntityRegistry {
     idx: AtomicU64,
     gen: AtomicU64,
     free_indices: SegQueue<u64>,
+    // the generation of the entity alive at each used index
+    alive: Mutex<HashMap<u64, u64>>,
 }
 
 impl EntityRegistry {
@@ -47,15 +55,37 @@ impl EntityRegistry {
             self.idx.fetch_add(1, Ordering::SeqCst)
         };
 
-        Entity {
+        let entity = Entity {
             idx,
             gen: self.gen.load(Ordering::Acquire),
-        }
+        };
+
+        self.alive.lock().unwrap().insert(entity.idx, entity.gen);
+
+        entity
+    }
+
+    /// Whether `entity` was returned by [`next`](Self::next) and hasn't been freed since.
+    #[inline]
+    pub fn contains(&self, entity: &Entity) -> bool {
+        self.alive.lock().unwrap().get(&entity.idx) == Some(&entity.gen)
     }
 
+    /// Frees `entity` so its index can be reused, returns false without freeing anything if
+    /// it isn't alive, so freeing it twice never hands out its index twice.
     #[inline]
-    pub fn free(&self, entity: Entity) {
+    pub fn free(&self, entity: Entity) -> bool {
+        let mut alive = self.alive.lock().unwrap();
+
+        if alive.get(&entity.idx) != Some(&entity.gen) {
+            return false;
+        }
+
+        alive.remove(&entity.idx);
+
         self.gen.fetch_add(1, Ordering::Release);
         self.free_indices.push(entity.idx);
+
+        true
     }
 }
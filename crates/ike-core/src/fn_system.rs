@@ -1,9 +1,6 @@
-use std::marker::PhantomData;
+use std::{any::type_name, borrow::Cow, marker::PhantomData};
 
-use crate::{Access, Commands, ExclusiveSystem, Fetch, Query, QueryFilter, QueryMut, ReadGuard, Resource, System, SystemAccess, World, WriteGuard};
-
-pub type Res<'a, T> = ReadGuard<'a, T>;
-pub type ResMut<'a, T> = WriteGuard<'a, T>;
+use crate::{Access, Commands, ExclusiveSystem, Fetch, Query, QueryFilter, QueryMut, Res, ResMut, Resource, System, SystemAccess, World};
 
 pub trait SystemParam: Sized {
     type Fetch: for<'a> SystemParamFetch<'a>;
@@ -53,7 +50,12 @@ impl<'a, T: Resource> SystemParamFetch<'a> for ResFetch<T> {
 
     #[inline]
     fn get(world: &'a World) -> Self::Item {
-        world.read_resource().unwrap()
+        Res {
+            value: world.resources().read().unwrap(),
+            ticks: world.resources().ticks::<T>().unwrap(),
+            last_change_tick: world.last_change_tick(),
+            change_tick: world.change_tick(),
+        }
     }
 }
 
@@ -73,7 +75,12 @@ impl<'a, T: Resource> SystemParamFetch<'a> for ResMutFetch<T> {
 
     #[inline]
     fn get(world: &'a World) -> Self::Item {
-        world.write_resource().unwrap()
+        ResMut {
+            value: world.resources().write().unwrap(),
+            ticks: world.resources().ticks::<T>().unwrap(),
+            last_change_tick: world.last_change_tick(),
+            change_tick: world.change_tick(),
+        }
     }
 }
 
@@ -193,6 +200,11 @@ where
         world.increment_change_tick();
         self.func.run(world);
     }
+
+    #[inline]
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Borrowed(type_name::<F>())
+    }
 }
 
 impl<F: FnMut(&mut World) + Send + Sync + 'static> ExclusiveSystem for F {
@@ -0,0 +1,70 @@
+use std::{alloc::Layout, any::TypeId};
+
+use crate::{ComponentId, ComponentStorage, Entity};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TypeInfo {
+    pub id: TypeId,
+    pub name: &'static str,
+    pub layout: Layout,
+}
+
+impl TypeInfo {
+    #[inline]
+    pub fn of<T: 'static>() -> Self {
+        Self {
+            id: TypeId::of::<T>(),
+            name: std::any::type_name::<T>(),
+            layout: Layout::new::<T>(),
+        }
+    }
+
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.layout.size()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ComponentInfo<'a> {
+    pub id: ComponentId,
+    pub name: &'a str,
+    pub layout: Layout,
+}
+
+impl<'a> ComponentInfo<'a> {
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.layout.size()
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct StorageInfo<'a> {
+    pub ty: ComponentInfo<'a>,
+    pub len: usize,
+    pub capacity: usize,
+    pub entities: &'a [Entity],
+}
+
+impl<'a> StorageInfo<'a> {
+    #[inline]
+    pub(crate) fn new(storage: &'a ComponentStorage) -> Self {
+        Self {
+            ty: storage.info(),
+            len: storage.len(),
+            capacity: storage.capacity(),
+            entities: storage.entities(),
+        }
+    }
+
+    #[inline]
+    pub fn bytes(&self) -> usize {
+        self.ty.size() * self.len
+    }
+
+    #[inline]
+    pub fn allocated_bytes(&self) -> usize {
+        self.ty.size() * self.capacity
+    }
+}
@@ -5,15 +5,25 @@ mod app;
 mod borrow;
 mod commands;
 mod component;
+mod condition;
+mod diagnostics;
+mod dynamic_query;
 mod entity;
 mod fn_system;
 mod id;
+mod info;
 mod node;
+mod plan;
 mod plugin;
 mod query;
+mod relation;
 mod resources;
+mod rng;
+mod snapshot;
 mod spawn_node;
 mod system;
+mod transfer;
+mod timer;
 mod world;
 mod query_filter;
 
@@ -22,14 +32,24 @@ pub use app::*;
 pub use borrow::*;
 pub use commands::*;
 pub use component::*;
+pub use condition::*;
+pub use diagnostics::*;
+pub use dynamic_query::*;
 pub use entity::*;
 pub use fn_system::*;
 pub use id::*;
+pub use info::*;
 pub use node::*;
+pub use plan::*;
 pub use plugin::*;
 pub use query::*;
+pub use relation::*;
 pub use resources::*;
+pub use rng::*;
+pub use snapshot::*;
 pub use spawn_node::*;
 pub use system::*;
+pub use transfer::*;
+pub use timer::*;
 pub use world::*;
 pub use query_filter::*;
\ No newline at end of file
@@ -1,6 +1,6 @@
 use std::{any::TypeId, borrow::Cow, collections::HashMap, thread};
 
-use crate::{AnyComponent, BorrowLock, Entity, ReadGuard, World, WriteGuard};
+use crate::{AnyComponent, BorrowLock, ComponentId, Entity, ReadGuard, World, WriteGuard};
 
 pub(crate) struct OwnedComponent {
     insert: fn(Entity, Box<dyn AnyComponent>, &mut World),
@@ -28,6 +28,10 @@ impl OwnedComponent {
     pub fn insert(self, entity: Entity, world: &mut World) {
         (self.insert)(entity, self.component, world)
     }
+
+    pub fn as_mut_ptr(&mut self) -> *mut u8 {
+        &mut *self.component as *mut dyn AnyComponent as *mut u8
+    }
 }
 
 pub struct Node<'a> {
@@ -76,7 +80,7 @@ impl<'a> Node<'a> {
             }
         }
 
-        let storage = self.world.components.get(&type_id)?;
+        let storage = self.world.components.get(&ComponentId::from(type_id))?;
 
         unsafe { storage.get_borrowed(&self.entity) }
     }
@@ -96,7 +100,7 @@ impl<'a> Node<'a> {
             }
         }
 
-        let storage = self.world.components.get(&type_id)?;
+        let storage = self.world.components.get(&ComponentId::from(type_id))?;
 
         unsafe { storage.get_borrowed_mut(&self.entity, self.world.change_tick()) }
     }
@@ -0,0 +1,356 @@
+use std::{
+    borrow::Cow,
+    fmt::{self, Write},
+};
+
+use crate::{Access, AccessType, SystemAccess};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AccessInfo {
+    pub ty: AccessType,
+    pub name: &'static str,
+    pub access: Access,
+}
+
+impl fmt::Display for AccessInfo {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.ty {
+            AccessType::Component(_) => "component",
+            AccessType::Resource(_) => "resource",
+        };
+
+        write!(f, "{:?} {} {}", self.access, kind, self.name)
+    }
+}
+
+/// Why a system couldn't be placed in an earlier step.
+#[derive(Clone, Debug)]
+pub struct StepConflict {
+    pub step: usize,
+    pub world: bool,
+    pub access: Vec<AccessInfo>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SystemPlan {
+    pub name: Cow<'static, str>,
+    pub access: SystemAccess,
+    pub conflicts: Vec<StepConflict>,
+}
+
+#[derive(Clone, Debug)]
+pub struct StepPlan {
+    pub systems: Vec<SystemPlan>,
+}
+
+/// A pair of systems with conflicting access, ordered only by the order they were registered in.
+#[derive(Clone, Debug)]
+pub struct Ambiguity {
+    pub first: Cow<'static, str>,
+    pub second: Cow<'static, str>,
+    pub world: bool,
+    pub access: Vec<AccessInfo>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SchedulePlan {
+    pub exclusive_systems: Vec<Cow<'static, str>>,
+    pub steps: Vec<StepPlan>,
+}
+
+impl SchedulePlan {
+    #[inline]
+    pub fn systems(&self) -> impl Iterator<Item = (usize, &SystemPlan)> {
+        self.steps
+            .iter()
+            .enumerate()
+            .flat_map(|(i, step)| step.systems.iter().map(move |system| (i, system)))
+    }
+
+    #[inline]
+    pub fn ambiguities(&self) -> Vec<Ambiguity> {
+        self.indexed_ambiguities()
+            .into_iter()
+            .map(|(_, _, ambiguity)| ambiguity)
+            .collect()
+    }
+
+    // ambiguities with the indices of both systems in `systems`
+    #[inline]
+    fn indexed_ambiguities(&self) -> Vec<(usize, usize, Ambiguity)> {
+        let systems: Vec<_> = self.systems().collect();
+        let mut ambiguities = Vec::new();
+
+        for (i, (first_step, first)) in systems.iter().enumerate() {
+            for (j, (second_step, second)) in systems.iter().enumerate().skip(i + 1) {
+                if first_step == second_step {
+                    continue;
+                }
+
+                let world = first.access.world_conflict(&second.access);
+                let access = first.access.conflicts(&second.access);
+
+                if world || !access.is_empty() {
+                    let ambiguity = Ambiguity {
+                        first: first.name.clone(),
+                        second: second.name.clone(),
+                        world,
+                        access,
+                    };
+
+                    ambiguities.push((i, j, ambiguity));
+                }
+            }
+        }
+
+        ambiguities
+    }
+
+    #[inline]
+    pub fn to_dot(&self) -> String {
+        let mut dot = DotWriter::new();
+        dot.stage("schedule", self);
+        dot.finish()
+    }
+}
+
+impl fmt::Display for SchedulePlan {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for name in &self.exclusive_systems {
+            writeln!(f, "exclusive: {}", name)?;
+        }
+
+        for (i, step) in self.steps.iter().enumerate() {
+            writeln!(f, "step {}:", i)?;
+
+            for system in &step.systems {
+                writeln!(f, "  {}", system.name)?;
+
+                if system.access.world {
+                    writeln!(f, "    world")?;
+                }
+
+                for info in system.access.info() {
+                    writeln!(f, "    {}", info)?;
+                }
+
+                for conflict in &system.conflicts {
+                    write!(f, "    conflicts with step {}:", conflict.step)?;
+
+                    if conflict.world {
+                        write!(f, " world")?;
+                    }
+
+                    for info in &conflict.access {
+                        write!(f, " {}", info.name)?;
+                    }
+
+                    writeln!(f)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct StagePlan {
+    pub name: &'static str,
+    pub schedule: SchedulePlan,
+}
+
+/// The full execution plan of an app, stages run in order, followed by the steps of each stage.
+#[derive(Clone, Debug, Default)]
+pub struct ExecutionPlan {
+    pub startup: SchedulePlan,
+    pub stages: Vec<StagePlan>,
+}
+
+impl ExecutionPlan {
+    pub const STARTUP: &'static str = "startup";
+
+    // the startup schedule followed by every stage
+    #[inline]
+    fn schedules(&self) -> impl Iterator<Item = (&'static str, &SchedulePlan)> {
+        let stages = self
+            .stages
+            .iter()
+            .map(|stage| (stage.name, &stage.schedule));
+
+        std::iter::once((Self::STARTUP, &self.startup)).chain(stages)
+    }
+
+    /// Returns the ambiguities of the startup schedule and of every stage, along with the
+    /// name of the stage, [`STARTUP`](Self::STARTUP) for the startup schedule.
+    #[inline]
+    pub fn ambiguities(&self) -> Vec<(&'static str, Ambiguity)> {
+        self.schedules()
+            .flat_map(|(name, schedule)| {
+                schedule
+                    .ambiguities()
+                    .into_iter()
+                    .map(move |ambiguity| (name, ambiguity))
+            })
+            .collect()
+    }
+
+    #[inline]
+    pub fn to_dot(&self) -> String {
+        let mut dot = DotWriter::new();
+
+        for (name, schedule) in self.schedules() {
+            dot.stage(name, schedule);
+        }
+
+        dot.finish()
+    }
+}
+
+impl fmt::Display for ExecutionPlan {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}:", Self::STARTUP)?;
+        write!(f, "{}", self.startup)?;
+
+        for stage in &self.stages {
+            writeln!(f, "stage {}:", stage.name)?;
+            write!(f, "{}", stage.schedule)?;
+        }
+
+        Ok(())
+    }
+}
+
+struct DotWriter {
+    out: String,
+    next_node: usize,
+    next_cluster: usize,
+    // last node of the previous step, and the cluster it's in
+    previous: Option<(usize, Option<String>)>,
+}
+
+impl DotWriter {
+    #[inline]
+    fn new() -> Self {
+        Self {
+            out: String::from("digraph schedule {\n  compound=true;\n  node [shape=box];\n"),
+            next_node: 0,
+            next_cluster: 0,
+            previous: None,
+        }
+    }
+
+    #[inline]
+    fn node(&mut self, label: &str) -> usize {
+        let id = self.next_node;
+        self.next_node += 1;
+
+        let _ = writeln!(self.out, "    n{} [label=\"{}\"];", id, escape(label));
+
+        id
+    }
+
+    #[inline]
+    fn cluster(&mut self) -> String {
+        self.next_cluster += 1;
+        format!("cluster_{}", self.next_cluster - 1)
+    }
+
+    #[inline]
+    fn edge_from_previous(&mut self, node: usize, cluster: Option<String>) {
+        if let Some((previous, previous_cluster)) = self.previous.take() {
+            let mut attributes = Vec::new();
+
+            if let Some(previous_cluster) = previous_cluster {
+                attributes.push(format!("ltail={}", previous_cluster));
+            }
+
+            if let Some(cluster) = &cluster {
+                attributes.push(format!("lhead={}", cluster));
+            }
+
+            let _ = writeln!(
+                self.out,
+                "  n{} -> n{} [{}];",
+                previous,
+                node,
+                attributes.join(", ")
+            );
+        }
+
+        self.previous = Some((node, cluster));
+    }
+
+    #[inline]
+    fn stage(&mut self, name: &str, schedule: &SchedulePlan) {
+        let stage_cluster = self.cluster();
+        let _ = writeln!(
+            self.out,
+            "  subgraph {} {{\n  label=\"{}\";",
+            stage_cluster,
+            escape(name)
+        );
+
+        let mut nodes = Vec::new();
+
+        for system in &schedule.exclusive_systems {
+            let node = self.node(&format!("{} (exclusive)", system));
+            self.edge_from_previous(node, None);
+        }
+
+        for (i, step) in schedule.steps.iter().enumerate() {
+            let cluster = self.cluster();
+            let _ = writeln!(
+                self.out,
+                "  subgraph {} {{\n    label=\"step {}\";",
+                cluster, i
+            );
+
+            let first = self.next_node;
+
+            for system in &step.systems {
+                let node = self.node(&system.name);
+                nodes.push(node);
+            }
+
+            let _ = writeln!(self.out, "  }}");
+
+            if self.next_node > first {
+                self.edge_from_previous(first, Some(cluster.clone()));
+                self.previous = Some((self.next_node - 1, Some(cluster)));
+            }
+        }
+
+        let _ = writeln!(self.out, "  }}");
+
+        for (first, second, ambiguity) in schedule.indexed_ambiguities() {
+            let mut label: Vec<&str> = ambiguity.access.iter().map(|info| info.name).collect();
+
+            if ambiguity.world {
+                label.push("World");
+            }
+
+            let _ = writeln!(
+                self.out,
+                "  n{} -> n{} [style=dashed, color=red, dir=none, constraint=false, label=\"{}\"];",
+                nodes[first],
+                nodes[second],
+                escape(&label.join(", "))
+            );
+        }
+    }
+
+    #[inline]
+    fn finish(mut self) -> String {
+        self.out.push_str("}\n");
+        self.out
+    }
+}
+
+#[inline]
+fn escape(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -1,9 +1,9 @@
 use std::ops::{Deref, DerefMut};
 use std::slice::Iter as SliceIter;
 use std::sync::atomic::AtomicU64;
-use std::{any::TypeId, marker::PhantomData};
+use std::marker::PhantomData;
 
-use crate::{Access, AnyComponent, Entity, QueryFilter, SystemAccess, World};
+use crate::{Access, AnyComponent, ComponentId, Entity, QueryFilter, SystemAccess, World};
 
 pub trait Query {
     #[doc(hidden)]
@@ -26,18 +26,26 @@ pub unsafe trait Fetch<'a>: Sized {
     fn release(world: &World);
 }
 
+/// Marks fetches that only read, so their items may alias.
+///
+/// # Safety
+/// - [`Fetch::get`] must never hand out mutable access.
+pub unsafe trait ReadOnlyFetch {}
+
 impl<'a, T: AnyComponent> Query for &'a T {
     type Fetch = FetchRead<T>;
 }
 
 pub struct FetchRead<T>(PhantomData<fn() -> T>);
 
+unsafe impl<T> ReadOnlyFetch for FetchRead<T> {}
+
 unsafe impl<'a, T: AnyComponent> Fetch<'a> for FetchRead<T> {
     type Item = &'a T;
 
     #[inline]
     fn entities(world: &World) -> &[Entity] {
-        if let Some(storage) = world.components.get(&TypeId::of::<T>()) {
+        if let Some(storage) = world.components.get(&ComponentId::of::<T>()) {
             storage.entities()
         } else {
             &[]
@@ -56,7 +64,7 @@ unsafe impl<'a, T: AnyComponent> Fetch<'a> for FetchRead<T> {
 
     #[inline]
     unsafe fn get(world: &'a World, entity: Entity) -> Option<Self::Item> {
-        let storage = world.components.get(&TypeId::of::<T>())?;
+        let storage = world.components.get(&ComponentId::of::<T>())?;
 
         unsafe { storage.get_unchecked(&entity) }
     }
@@ -123,7 +131,7 @@ unsafe impl<'a, T: AnyComponent> Fetch<'a> for FetchWrite<T> {
 
     #[inline]
     fn entities(world: &World) -> &[Entity] {
-        if let Some(storage) = world.components.get(&TypeId::of::<T>()) {
+        if let Some(storage) = world.components.get(&ComponentId::of::<T>()) {
             storage.entities()
         } else {
             &[]
@@ -142,7 +150,7 @@ unsafe impl<'a, T: AnyComponent> Fetch<'a> for FetchWrite<T> {
 
     #[inline]
     unsafe fn get(world: &'a World, entity: Entity) -> Option<Self::Item> {
-        let storage = world.components.get(&TypeId::of::<T>())?;
+        let storage = world.components.get(&ComponentId::of::<T>())?;
 
         let component = unsafe { storage.get_unchecked_mut(&entity)? };
 
@@ -165,6 +173,8 @@ impl Query for Entity {
 
 pub struct EntityFetch;
 
+unsafe impl ReadOnlyFetch for EntityFetch {}
+
 unsafe impl<'a> Fetch<'a> for EntityFetch {
     type Item = Entity;
 
@@ -190,10 +200,47 @@ unsafe impl<'a> Fetch<'a> for EntityFetch {
     fn release(_world: &World) {}
 }
 
+pub struct Name;
+
+impl Query for Name {
+    type Fetch = NameFetch;
+}
+
+pub struct NameFetch;
+
+unsafe impl ReadOnlyFetch for NameFetch {}
+
+unsafe impl<'a> Fetch<'a> for NameFetch {
+    type Item = &'a str;
+
+    #[inline]
+    fn entities(world: &World) -> &[Entity] {
+        &world.entities
+    }
+
+    #[inline]
+    fn access(_access: &mut SystemAccess) {}
+
+    #[inline]
+    fn borrow(_world: &World) -> bool {
+        true
+    }
+
+    #[inline]
+    unsafe fn get(world: &'a World, entity: Entity) -> Option<Self::Item> {
+        world.get_node_name(&entity)
+    }
+
+    #[inline]
+    fn release(_world: &World) {}
+}
+
 impl Query for () {
     type Fetch = ();
 }
 
+unsafe impl ReadOnlyFetch for () {}
+
 unsafe impl<'a> Fetch<'a> for () {
     type Item = ();
 
@@ -219,6 +266,9 @@ unsafe impl<'a> Fetch<'a> for () {
     fn release(_world: &World) {}
 }
 
+/// Iterating a query directly visits entities in the order of the driving storage, which
+/// changes as components are removed. Use [`QueryMut::stable`] or [`QueryMut::sorted_by_key`]
+/// when the order matters.
 pub struct QueryMut<'a, Q: Query, F: QueryFilter = ()> {
     entities: SliceIter<'a, Entity>,
     world: &'a World,
@@ -247,6 +297,212 @@ impl<'a, Q: Query, F: QueryFilter> QueryMut<'a, Q, F> {
 
         unsafe { Q::Fetch::get(self.world, entity) }
     }
+
+    /// Returns the items of all `entities` at once, or `None` if any of them doesn't match
+    /// the query or the same entity is passed more than once.
+    #[inline]
+    pub fn get_many_mut<const N: usize>(&mut self, entities: [Entity; N]) -> Option<[QueryItem<'_, Q>; N]> {
+        for (i, entity) in entities.iter().enumerate() {
+            if entities[..i].contains(entity) {
+                return None;
+            }
+        }
+
+        // SAFETY: the entities are distinct, so none of the items alias.
+        unsafe { get_many::<Q, F, N>(self.world, entities) }
+    }
+
+    // entities that match the query, in iteration order
+    #[inline]
+    fn matching(&self) -> Vec<Entity> {
+        Q::Fetch::entities(self.world)
+            .iter()
+            .filter(|entity| {
+                F::filter(self.world, entity) && unsafe { Q::Fetch::get(self.world, **entity) }.is_some()
+            })
+            .copied()
+            .collect()
+    }
+
+    /// Iterates the remaining items ordered by [`Entity`] index, the order doesn't depend on
+    /// when components were inserted or removed.
+    #[inline]
+    pub fn stable(&mut self) -> Stable<'_, Q, F> {
+        let mut entities: Vec<Entity> = self.entities.by_ref().copied().collect();
+        entities.sort_unstable();
+
+        Stable {
+            world: self.world,
+            entities: entities.into_iter(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the remaining items sorted by `f`, items with equal keys are ordered by
+    /// [`Entity`] index.
+    #[inline]
+    pub fn sorted_by_key<K: Ord>(
+        &mut self,
+        mut f: impl FnMut(&QueryItem<'_, Q>) -> K,
+    ) -> std::vec::IntoIter<QueryItem<'_, Q>> {
+        let mut items: Vec<_> = self.stable().collect();
+        items.sort_by_key(|item| f(item));
+
+        items.into_iter()
+    }
+
+    /// Iterates every combination of `K` distinct items, like every pair for `K = 2`.
+    #[inline]
+    pub fn iter_combinations<const K: usize>(&self) -> Combinations<'_, Q, F, K>
+    where
+        Q::Fetch: ReadOnlyFetch,
+    {
+        Combinations {
+            world: self.world,
+            indices: CombinationIndices::new(self.matching()),
+            marker: PhantomData,
+        }
+    }
+
+    /// Calls `f` with every combination of `K` distinct items, mutable queries can't
+    /// hand out combinations through an iterator since an item is part of many combinations.
+    #[inline]
+    pub fn for_each_combination_mut<const K: usize>(&mut self, mut f: impl FnMut([QueryItem<'_, Q>; K])) {
+        let mut indices = CombinationIndices::<K>::new(self.matching());
+
+        while let Some(entities) = indices.next() {
+            // SAFETY: combinations are made of distinct entities, and the items are dropped
+            // before the next combination is fetched.
+            if let Some(items) = unsafe { get_many::<Q, F, K>(self.world, entities) } {
+                f(items);
+            }
+        }
+    }
+}
+
+/// # Safety
+/// - `entities` must be distinct, unless `Q` is read only.
+#[inline]
+unsafe fn get_many<'a, Q: Query, F: QueryFilter, const N: usize>(
+    world: &'a World,
+    entities: [Entity; N],
+) -> Option<[QueryItem<'a, Q>; N]> {
+    let mut items = Vec::with_capacity(N);
+
+    for entity in entities {
+        if !F::filter(world, &entity) {
+            return None;
+        }
+
+        items.push(unsafe { <Q::Fetch as Fetch<'a>>::get(world, entity)? });
+    }
+
+    items.try_into().ok()
+}
+
+struct CombinationIndices<const K: usize> {
+    entities: Vec<Entity>,
+    indices: [usize; K],
+    done: bool,
+}
+
+impl<const K: usize> CombinationIndices<K> {
+    #[inline]
+    fn new(entities: Vec<Entity>) -> Self {
+        let mut indices = [0; K];
+
+        for (i, index) in indices.iter_mut().enumerate() {
+            *index = i;
+        }
+
+        Self {
+            done: K == 0 || K > entities.len(),
+            entities,
+            indices,
+        }
+    }
+
+    #[inline]
+    fn next(&mut self) -> Option<[Entity; K]> {
+        if self.done {
+            return None;
+        }
+
+        let mut entities = [Entity::from_raw(0, 0); K];
+
+        for (entity, index) in entities.iter_mut().zip(self.indices) {
+            *entity = self.entities[index];
+        }
+
+        // advance the right most index that can still move, and reset the ones after it
+        let len = self.entities.len();
+
+        match (0..K).rev().find(|&i| self.indices[i] < len - K + i) {
+            Some(i) => {
+                self.indices[i] += 1;
+
+                for j in i + 1..K {
+                    self.indices[j] = self.indices[j - 1] + 1;
+                }
+            }
+            None => self.done = true,
+        }
+
+        Some(entities)
+    }
+}
+
+pub struct Combinations<'a, Q: Query, F: QueryFilter, const K: usize> {
+    world: &'a World,
+    indices: CombinationIndices<K>,
+    marker: PhantomData<(Q::Fetch, F)>,
+}
+
+impl<'a, Q: Query, F: QueryFilter, const K: usize> Iterator for Combinations<'a, Q, F, K>
+where
+    Q::Fetch: ReadOnlyFetch,
+{
+    type Item = [QueryItem<'a, Q>; K];
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let entities = self.indices.next()?;
+
+            // SAFETY: the query is read only, so items are allowed to alias.
+            if let Some(items) = unsafe { get_many::<Q, F, K>(self.world, entities) } {
+                return Some(items);
+            }
+        }
+    }
+}
+
+pub struct Stable<'a, Q: Query, F: QueryFilter> {
+    world: &'a World,
+    entities: std::vec::IntoIter<Entity>,
+    marker: PhantomData<(Q::Fetch, F)>,
+}
+
+impl<'a, Q: Query, F: QueryFilter> Iterator for Stable<'a, Q, F> {
+    type Item = QueryItem<'a, Q>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        for entity in self.entities.by_ref() {
+            if !F::filter(self.world, &entity) {
+                continue;
+            }
+
+            // SAFETY: entities are distinct and the query is borrowed mutably.
+            let item = unsafe { <Q::Fetch as Fetch<'a>>::get(self.world, entity) };
+
+            if item.is_some() {
+                return item;
+            }
+        }
+
+        None
+    }
 }
 
 impl<'a, Q: Query, F: QueryFilter> Iterator for QueryMut<'a, Q, F> {
@@ -324,6 +580,8 @@ macro_rules! tuple_impl {
 			}
 		}
 
+		unsafe impl<$($name: ReadOnlyFetch),*> ReadOnlyFetch for ($($name,)*) {}
+
 		impl<'a, $($name: Query),*> Query for ($($name,)*) {
 			type Fetch = ($($name::Fetch,)*);
 		}
@@ -341,3 +599,93 @@ macro_rules! tuples {
 }
 
 tuples!(tuple_impl, A, B, C, D, E, F, G, H, I, J, K);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_many_mut() {
+        let mut world = World::new();
+
+        let a = world.create_entity();
+        let b = world.create_entity();
+        let c = world.create_entity();
+
+        world.insert(a, 1i32);
+        world.insert(b, 2i32);
+        world.insert(c, true);
+
+        let mut query = world.query::<&mut i32, ()>().unwrap();
+
+        let [mut x, mut y] = query.get_many_mut([a, b]).unwrap();
+        std::mem::swap(&mut *x, &mut *y);
+
+        assert!(query.get_many_mut([a, a]).is_none());
+        assert!(query.get_many_mut([a, c]).is_none());
+
+        drop(query);
+
+        assert_eq!(*world.get_component::<i32>(&a).unwrap(), 2);
+        assert_eq!(*world.get_component::<i32>(&b).unwrap(), 1);
+    }
+
+    #[test]
+    fn combinations() {
+        let mut world = World::new();
+
+        for i in 0..4 {
+            let entity = world.create_entity();
+            world.insert(entity, i as i32);
+        }
+
+        let query = world.query::<&i32, ()>().unwrap();
+
+        let pairs: Vec<_> = query.iter_combinations().map(|[a, b]| (*a, *b)).collect();
+        assert_eq!(pairs, vec![(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)]);
+        assert_eq!(query.iter_combinations::<3>().count(), 4);
+        assert_eq!(query.iter_combinations::<5>().count(), 0);
+
+        drop(query);
+
+        let mut query = world.query::<&mut i32, ()>().unwrap();
+
+        query.for_each_combination_mut(|[mut a, mut b]| {
+            *a += 1;
+            *b += 1;
+        });
+
+        drop(query);
+
+        let values: Vec<i32> = world.query::<&i32, ()>().unwrap().copied().collect();
+        assert_eq!(values, vec![3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn stable_order() {
+        let mut world = World::new();
+
+        let entities: Vec<_> = (0..4).map(|_| world.create_entity()).collect();
+
+        for (i, entity) in entities.iter().enumerate().rev() {
+            world.insert(*entity, i as u32);
+            world.insert(*entity, (i % 2) as i32);
+        }
+
+        world.remove::<u32>(&entities[3]);
+        world.insert(entities[3], 3u32);
+
+        let mut query = world.query::<(&u32, &i32), ()>().unwrap();
+        let stable: Vec<_> = query.stable().map(|(i, _)| *i).collect();
+        assert_eq!(stable, [0, 1, 2, 3]);
+        drop(query);
+
+        let mut query = world.query::<(&u32, &i32), ()>().unwrap();
+        let sorted: Vec<_> = query
+            .sorted_by_key(|(_, value)| -**value)
+            .map(|(i, _)| *i)
+            .collect();
+
+        assert_eq!(sorted, [1, 3, 0, 2]);
+    }
+}
@@ -1,6 +1,6 @@
-use std::{any::TypeId, marker::PhantomData};
+use std::marker::PhantomData;
 
-use crate::{AnyComponent, Entity, World};
+use crate::{AnyComponent, ComponentId, Entity, World};
 
 pub trait QueryFilter {
 	fn filter(world: &World, entity: &Entity) -> bool;
@@ -29,7 +29,7 @@ pub struct Changed<T>(PhantomData<fn() -> T>);
 impl<T: AnyComponent> QueryFilter for Changed<T> {
 	#[inline]
 	fn filter(world: &World, entity: &Entity) -> bool {
-		if let Some(storage) = world.components.get(&TypeId::of::<T>()) {
+		if let Some(storage) = world.components.get(&ComponentId::of::<T>()) {
 			storage.changed(entity, world.last_change_tick(), world.change_tick())
 		} else {
 			false
@@ -0,0 +1,304 @@
+use std::{any::type_name, collections::HashMap, fmt, marker::PhantomData, sync::atomic::Ordering};
+
+use crate::{
+    Access, ComponentId, ComponentStorage, Entity, EntityMap, MapEntities, SystemAccess,
+    SystemParam, SystemParamFetch, World,
+};
+
+/// A relationship of kind `R` from the entity this is inserted on to `target`.
+///
+/// The reverse index of a relation kind is maintained by the world once it's registered
+/// with [`World::register_relation`], and relations targeting a despawned entity are removed.
+/// Relations replaced through `&mut` access are reindexed when the world is dequeued, or by
+/// [`World::sync_relations`].
+pub struct Relation<R> {
+    target: Entity,
+    marker: PhantomData<fn() -> R>,
+}
+
+impl<R> Relation<R> {
+    #[inline]
+    pub fn new(target: Entity) -> Self {
+        Self {
+            target,
+            marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    pub fn target(&self) -> Entity {
+        self.target
+    }
+}
+
+impl<R> Clone for Relation<R> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<R> Copy for Relation<R> {}
+
+impl<R> PartialEq for Relation<R> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.target == other.target
+    }
+}
+
+impl<R> Eq for Relation<R> {}
+
+impl<R> fmt::Debug for Relation<R> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Relation")
+            .field(&type_name::<R>())
+            .field(&self.target)
+            .finish()
+    }
+}
+
+impl<R> MapEntities for Relation<R> {
+    #[inline]
+    fn map_entities(&mut self, map: &EntityMap) -> bool {
+        match map.map(self.target) {
+            Some(target) => {
+                self.target = target;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+pub trait RelationKind: Send + Sync + 'static {}
+
+impl<T: Send + Sync + 'static> RelationKind for T {}
+
+pub(crate) struct RelationIndex {
+    // reads the target of a relation component
+    pub(crate) target: unsafe fn(*const u8) -> Entity,
+    pub(crate) remove: fn(&mut World, &Entity),
+    sources: HashMap<Entity, Vec<Entity>>,
+    targets: HashMap<Entity, Entity>,
+    // relations changed at or after this tick may not be indexed yet
+    synced_tick: u64,
+}
+
+impl RelationIndex {
+    #[inline]
+    pub(crate) fn of<R: RelationKind>() -> Self {
+        unsafe fn target<R>(relation: *const u8) -> Entity {
+            unsafe { &*(relation as *const Relation<R>) }.target
+        }
+
+        fn remove<R: RelationKind>(world: &mut World, entity: &Entity) {
+            world.remove::<Relation<R>>(entity);
+        }
+
+        Self {
+            target: target::<R>,
+            remove: remove::<R>,
+            sources: HashMap::new(),
+            targets: HashMap::new(),
+            synced_tick: 0,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn sources(&self, target: &Entity) -> &[Entity] {
+        self.sources.get(target).map_or(&[], Vec::as_slice)
+    }
+
+    /// Links `source` to `target`, unlinking it from its previous target.
+    #[inline]
+    pub(crate) fn link(&mut self, source: Entity, target: Entity) {
+        if self.targets.get(&source) == Some(&target) {
+            return;
+        }
+
+        self.unlink(&source);
+        self.targets.insert(source, target);
+        self.sources.entry(target).or_default().push(source);
+    }
+
+    #[inline]
+    pub(crate) fn unlink(&mut self, source: &Entity) {
+        let target = match self.targets.remove(source) {
+            Some(target) => target,
+            None => return,
+        };
+
+        if let Some(sources) = self.sources.get_mut(&target) {
+            sources.retain(|e| e != source);
+
+            if sources.is_empty() {
+                self.sources.remove(&target);
+            }
+        }
+    }
+
+    /// Relinks the relations in `storage` changed since the last sync.
+    ///
+    /// # Safety
+    /// - `storage` must hold the relation components of this index.
+    #[inline]
+    pub(crate) unsafe fn sync(&mut self, storage: &ComponentStorage, change_tick: u64) {
+        for entity in storage.entities() {
+            let tick = storage.get_change_marker(entity).unwrap();
+
+            if tick.load(Ordering::Acquire) < self.synced_tick {
+                continue;
+            }
+
+            let relation = unsafe { storage.get_bytes_unchecked(entity) }.unwrap();
+            let target = unsafe { (self.target)(relation.as_ptr()) };
+
+            self.link(*entity, target);
+        }
+
+        self.synced_tick = change_tick;
+    }
+}
+
+/// System param for following relations of kind `R`.
+pub struct Relations<'a, R> {
+    world: &'a World,
+    marker: PhantomData<fn() -> R>,
+}
+
+impl<'a, R: RelationKind> Relations<'a, R> {
+    #[inline]
+    pub fn new(world: &'a World) -> Self {
+        Self {
+            world,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns every entity with a relation of kind `R` targeting `target`.
+    #[inline]
+    pub fn sources(&self, target: &Entity) -> &'a [Entity] {
+        self.world.relation_sources::<R>(target)
+    }
+
+    #[inline]
+    pub fn target(&self, source: &Entity) -> Option<Entity> {
+        self.world.relation_target::<R>(source)
+    }
+}
+
+impl<'a, R: RelationKind> SystemParam for Relations<'a, R> {
+    type Fetch = RelationsFetch<R>;
+}
+
+pub struct RelationsFetch<R>(PhantomData<fn() -> R>);
+
+impl<'a, R: RelationKind> SystemParamFetch<'a> for RelationsFetch<R> {
+    type Item = Relations<'a, R>;
+
+    #[inline]
+    fn access(access: &mut SystemAccess) {
+        access.borrow_component::<Relation<R>>(Access::Read);
+    }
+
+    #[inline]
+    fn get(world: &'a World) -> Self::Item {
+        Relations::new(world)
+    }
+}
+
+#[inline]
+pub(crate) fn relation_id<R: RelationKind>() -> ComponentId {
+    ComponentId::of::<Relation<R>>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FnSystem, System};
+
+    struct Targets;
+    struct OwnedBy;
+
+    #[test]
+    fn relations() {
+        let mut world = World::new();
+        world.register_relation::<Targets>();
+
+        let a = world.create_entity();
+        let b = world.create_entity();
+        let c = world.create_entity();
+
+        world.insert(a, Relation::<Targets>::new(c));
+        world.insert_relation::<Targets>(b, c);
+        world.insert_relation::<OwnedBy>(c, a);
+
+        assert_eq!(world.relation_sources::<Targets>(&c), &[a, b]);
+        assert_eq!(world.relation_target::<OwnedBy>(&c), Some(a));
+
+        world.insert_relation::<Targets>(b, a);
+        assert_eq!(world.relation_sources::<Targets>(&c), &[a]);
+        assert_eq!(world.relation_sources::<Targets>(&a), &[b]);
+
+        assert!(world.despawn(&c));
+
+        assert!(!world.contains_component::<Relation<Targets>>(&a));
+        assert!(world.relation_sources::<Targets>(&c).is_empty());
+        assert!(world.relation_sources::<OwnedBy>(&a).is_empty());
+        assert_eq!(world.relation_sources::<Targets>(&a), &[b]);
+
+        // despawning again, or through a stale target, leaves the new entities alone
+        assert!(!world.despawn(&c));
+
+        let d = world.create_entity();
+        let e = world.create_entity();
+        assert_ne!(d, e);
+
+        world.insert_relation::<Targets>(d, e);
+        assert!(!world.despawn(&c));
+
+        assert!(world.contains_entity(&d));
+        assert!(world.contains_entity(&e));
+        assert_eq!(world.relation_target::<Targets>(&d), Some(e));
+        assert_eq!(world.relation_sources::<Targets>(&e), &[d]);
+        assert_eq!(world.relation_sources::<Targets>(&a), &[b]);
+
+        let mut system = (move |relations: Relations<Targets>| {
+            assert_eq!(relations.sources(&a), &[b]);
+            assert_eq!(relations.target(&b), Some(a));
+        })
+        .system();
+
+        system.run(&world);
+    }
+
+    #[test]
+    fn replaced_relations() {
+        let mut world = World::new();
+
+        let a = world.create_entity();
+        let b = world.create_entity();
+        let c = world.create_entity();
+
+        world.insert(a, Relation::<Targets>::new(b));
+        world.register_relation::<Targets>();
+        assert_eq!(world.relation_sources::<Targets>(&b), &[a]);
+
+        *world.get_component_mut::<Relation<Targets>>(&a).unwrap() = Relation::new(c);
+        world.dequeue();
+
+        assert!(world.relation_sources::<Targets>(&b).is_empty());
+        assert_eq!(world.relation_sources::<Targets>(&c), &[a]);
+
+        // replaced again in the same tick
+        *world.get_component_mut::<Relation<Targets>>(&a).unwrap() = Relation::new(b);
+        world.sync_relations();
+
+        assert_eq!(world.relation_sources::<Targets>(&b), &[a]);
+
+        world.despawn(&b);
+        assert!(!world.contains_component::<Relation<Targets>>(&a));
+    }
+}
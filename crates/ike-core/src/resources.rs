@@ -1,14 +1,67 @@
-use std::{any::TypeId, collections::HashMap};
+use std::{
+    any::TypeId,
+    collections::HashMap,
+    ops::{Deref, DerefMut},
+    sync::atomic::{AtomicU64, Ordering},
+};
 
-use crate::{BorrowLock, ReadGuard, WriteGuard};
+use crate::{BorrowLock, ReadGuard, TypeInfo, WriteGuard};
 
 pub trait Resource: Send + Sync + 'static {}
 
 impl<T: Send + Sync + 'static> Resource for T {}
 
+#[inline]
+fn is_newer(tick: u64, last_change_tick: u64, change_tick: u64) -> bool {
+    change_tick.wrapping_sub(tick) < change_tick.wrapping_sub(last_change_tick)
+}
+
+#[derive(Debug, Default)]
+pub struct ResourceTicks {
+    added: AtomicU64,
+    changed: AtomicU64,
+}
+
+impl ResourceTicks {
+    #[inline]
+    pub fn new(change_tick: u64) -> Self {
+        Self {
+            added: AtomicU64::new(change_tick),
+            changed: AtomicU64::new(change_tick),
+        }
+    }
+
+    #[inline]
+    pub fn added(&self) -> u64 {
+        self.added.load(Ordering::Acquire)
+    }
+
+    #[inline]
+    pub fn changed(&self) -> u64 {
+        self.changed.load(Ordering::Acquire)
+    }
+
+    #[inline]
+    pub fn mark_changed(&self, change_tick: u64) {
+        self.changed.store(change_tick, Ordering::Release);
+    }
+
+    #[inline]
+    pub fn is_added(&self, last_change_tick: u64, change_tick: u64) -> bool {
+        is_newer(self.added(), last_change_tick, change_tick)
+    }
+
+    #[inline]
+    pub fn is_changed(&self, last_change_tick: u64, change_tick: u64) -> bool {
+        is_newer(self.changed(), last_change_tick, change_tick)
+    }
+}
+
 #[derive(Default)]
 pub struct Resources {
     inner: HashMap<TypeId, BorrowLock<dyn Resource>>,
+    info: HashMap<TypeId, TypeInfo>,
+    ticks: HashMap<TypeId, ResourceTicks>,
 }
 
 impl Resources {
@@ -17,14 +70,25 @@ impl Resources {
         Self::default()
     }
 
+    /// # Safety
+    /// - `resource` must be of the type described by `info`.
     #[inline]
-    pub unsafe fn insert_raw(&mut self, type_id: TypeId, resource: BorrowLock<dyn Resource>) {
-        self.inner.insert(type_id, resource);
+    pub unsafe fn insert_raw(
+        &mut self,
+        info: TypeInfo,
+        resource: BorrowLock<dyn Resource>,
+        change_tick: u64,
+    ) {
+        self.inner.insert(info.id, resource);
+        self.info.insert(info.id, info);
+        self.ticks.insert(info.id, ResourceTicks::new(change_tick));
     }
 
     #[inline]
     pub fn remove_raw(&mut self, type_id: TypeId) {
         self.inner.remove(&type_id);
+        self.info.remove(&type_id);
+        self.ticks.remove(&type_id);
     }
 
     #[inline]
@@ -34,8 +98,14 @@ impl Resources {
 
     #[inline]
     pub fn insert<T: Resource>(&mut self, resource: T) {
-        self.inner
-            .insert(TypeId::of::<T>(), BorrowLock::from_box(Box::new(resource)));
+        self.insert_ticked(resource, 0);
+    }
+
+    #[inline]
+    pub fn insert_ticked<T: Resource>(&mut self, resource: T, change_tick: u64) {
+        let resource: Box<dyn Resource> = Box::new(resource);
+        let resource = BorrowLock::from_box(resource);
+        unsafe { self.insert_raw(TypeInfo::of::<T>(), resource, change_tick) };
     }
 
     #[inline]
@@ -46,10 +116,27 @@ impl Resources {
     #[inline]
     pub fn remove<T: Resource>(&mut self) -> Option<T> {
         let resource = self.inner.remove(&TypeId::of::<T>())?;
+        self.info.remove(&TypeId::of::<T>());
+        self.ticks.remove(&TypeId::of::<T>());
 
         Some(unsafe { *Box::from_raw(resource.into_raw() as *mut T) })
     }
 
+    #[inline]
+    pub fn info(&self) -> impl Iterator<Item = TypeInfo> + '_ {
+        self.info.values().copied()
+    }
+
+    #[inline]
+    pub fn ticks<T: Resource>(&self) -> Option<&ResourceTicks> {
+        self.ticks_raw(TypeId::of::<T>())
+    }
+
+    #[inline]
+    pub fn ticks_raw(&self, type_id: TypeId) -> Option<&ResourceTicks> {
+        self.ticks.get(&type_id)
+    }
+
     #[inline]
     pub fn read<T: Resource>(&self) -> Option<ReadGuard<T>> {
         let read = self.inner.get(&TypeId::of::<T>())?.read()?;
@@ -73,6 +160,81 @@ impl Resources {
     }
 }
 
+pub struct Res<'a, T: ?Sized> {
+    pub(crate) value: ReadGuard<'a, T>,
+    pub(crate) ticks: &'a ResourceTicks,
+    pub(crate) last_change_tick: u64,
+    pub(crate) change_tick: u64,
+}
+
+impl<'a, T: ?Sized> Res<'a, T> {
+    #[inline]
+    pub fn is_added(&self) -> bool {
+        self.ticks.is_added(self.last_change_tick, self.change_tick)
+    }
+
+    #[inline]
+    pub fn is_changed(&self) -> bool {
+        self.ticks.is_changed(self.last_change_tick, self.change_tick)
+    }
+}
+
+impl<'a, T: ?Sized> Deref for Res<'a, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+pub struct ResMut<'a, T: ?Sized> {
+    pub(crate) value: WriteGuard<'a, T>,
+    pub(crate) ticks: &'a ResourceTicks,
+    pub(crate) last_change_tick: u64,
+    pub(crate) change_tick: u64,
+}
+
+impl<'a, T: ?Sized> ResMut<'a, T> {
+    #[inline]
+    pub fn is_added(&self) -> bool {
+        self.ticks.is_added(self.last_change_tick, self.change_tick)
+    }
+
+    #[inline]
+    pub fn is_changed(&self) -> bool {
+        self.ticks.is_changed(self.last_change_tick, self.change_tick)
+    }
+
+    #[inline]
+    pub fn unmarked(&mut self) -> &mut T {
+        &mut self.value
+    }
+
+    #[inline]
+    pub fn mark_changed(&self) {
+        self.ticks.mark_changed(self.change_tick);
+    }
+}
+
+impl<'a, T: ?Sized> Deref for ResMut<'a, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<'a, T: ?Sized> DerefMut for ResMut<'a, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.mark_changed();
+
+        &mut self.value
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
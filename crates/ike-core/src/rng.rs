@@ -0,0 +1,93 @@
+/// Small seedable random number generator (splitmix64).
+///
+/// The sequence only depends on the seed, which makes it usable for replays and lockstep.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Default for Rng {
+    #[inline]
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl Rng {
+    #[inline]
+    pub const fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    #[inline]
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    #[inline]
+    pub fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    #[inline]
+    pub fn next_bool(&mut self) -> bool {
+        self.next_u64() >> 63 == 1
+    }
+
+    /// Returns a value in `0.0..1.0`.
+    #[inline]
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Returns a value in `min..max`.
+    #[inline]
+    pub fn range_f32(&mut self, min: f32, max: f32) -> f32 {
+        min + (max - min) * self.next_f32()
+    }
+
+    /// Returns a value in `min..max`.
+    #[inline]
+    pub fn range_u64(&mut self, min: u64, max: u64) -> u64 {
+        assert!(min < max, "empty range");
+
+        min + self.next_u64() % (max - min)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeded() {
+        let mut a = Rng::new(1234);
+        let mut b = Rng::new(1234);
+        let mut c = Rng::new(4321);
+
+        let a: Vec<_> = (0..16).map(|_| a.next_u64()).collect();
+        let b: Vec<_> = (0..16).map(|_| b.next_u64()).collect();
+        let c: Vec<_> = (0..16).map(|_| c.next_u64()).collect();
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn ranges() {
+        let mut rng = Rng::new(0);
+
+        for _ in 0..1024 {
+            let f = rng.range_f32(-2.0, 3.0);
+            assert!((-2.0..3.0).contains(&f));
+
+            let u = rng.range_u64(5, 10);
+            assert!((5..10).contains(&u));
+        }
+    }
+}
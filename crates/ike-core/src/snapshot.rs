@@ -0,0 +1,399 @@
+use std::{
+    any::Any,
+    collections::{BTreeMap, BTreeSet},
+    sync::{atomic::Ordering, Arc},
+};
+
+use crate::{AnyComponent, ComponentId, Entity, Resource, TypeInfo, World};
+
+type Data = Box<dyn Any + Send + Sync>;
+
+// entities mapped to the change tick and value of their component
+type ComponentData<T> = BTreeMap<Entity, (u64, Arc<T>)>;
+
+#[derive(Clone, Copy)]
+struct ComponentFns {
+    info: TypeInfo,
+    capture: fn(&World, Option<&Data>) -> Data,
+    restore: fn(&mut World, &Data),
+    entities: fn(&Data) -> BTreeSet<Entity>,
+    changed: fn(&Data, &Data) -> Vec<Entity>,
+}
+
+impl ComponentFns {
+    #[inline]
+    fn of<T: AnyComponent + Clone>() -> Self {
+        fn capture<T: AnyComponent + Clone>(world: &World, base: Option<&Data>) -> Data {
+            let base = base.and_then(|base| base.downcast_ref::<ComponentData<T>>());
+
+            let mut data = ComponentData::<T>::new();
+
+            if let Some(storage) = world.components.get(&ComponentId::of::<T>()) {
+                for entity in storage.entities() {
+                    let tick = storage
+                        .get_change_marker(entity)
+                        .unwrap()
+                        .load(Ordering::Acquire);
+
+                    let unchanged = base
+                        .and_then(|base| base.get(entity))
+                        .filter(|(base_tick, _)| *base_tick == tick);
+
+                    let value = match unchanged {
+                        Some((_, value)) => value.clone(),
+                        None => Arc::new(
+                            unsafe { storage.get_unchecked::<T>(entity) }
+                                .unwrap()
+                                .clone(),
+                        ),
+                    };
+
+                    data.insert(*entity, (tick, value));
+                }
+            }
+
+            Box::new(data)
+        }
+
+        fn restore<T: AnyComponent + Clone>(world: &mut World, data: &Data) {
+            let data = data.downcast_ref::<ComponentData<T>>().unwrap();
+
+            let removed: Vec<Entity> = match world.components.get(&ComponentId::of::<T>()) {
+                Some(storage) => storage
+                    .entities()
+                    .iter()
+                    .filter(|entity| !data.contains_key(entity))
+                    .copied()
+                    .collect(),
+                None => Vec::new(),
+            };
+
+            for entity in removed {
+                world.remove::<T>(&entity);
+            }
+
+            for (entity, (_, value)) in data {
+                world.insert(*entity, T::clone(value));
+            }
+        }
+
+        fn entities<T: AnyComponent + Clone>(data: &Data) -> BTreeSet<Entity> {
+            let data = data.downcast_ref::<ComponentData<T>>().unwrap();
+
+            data.keys().copied().collect()
+        }
+
+        fn changed<T: AnyComponent + Clone>(from: &Data, to: &Data) -> Vec<Entity> {
+            let from = from.downcast_ref::<ComponentData<T>>().unwrap();
+            let to = to.downcast_ref::<ComponentData<T>>().unwrap();
+
+            to.iter()
+                .filter(|(entity, (tick, _))| match from.get(entity) {
+                    Some((from_tick, _)) => from_tick != tick,
+                    None => false,
+                })
+                .map(|(entity, _)| *entity)
+                .collect()
+        }
+
+        Self {
+            info: TypeInfo::of::<T>(),
+            capture: capture::<T>,
+            restore: restore::<T>,
+            entities: entities::<T>,
+            changed: changed::<T>,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct ResourceFns {
+    info: TypeInfo,
+    capture: fn(&World) -> Option<Data>,
+    restore: fn(&mut World, Option<&Data>),
+}
+
+impl ResourceFns {
+    #[inline]
+    fn of<T: Resource + Clone>() -> Self {
+        fn capture<T: Resource + Clone>(world: &World) -> Option<Data> {
+            let resource = world.read_resource::<T>()?;
+
+            Some(Box::new(T::clone(&resource)))
+        }
+
+        // resources missing from the snapshot are removed
+        fn restore<T: Resource + Clone>(world: &mut World, data: Option<&Data>) {
+            match data {
+                Some(data) => world.insert_resource(data.downcast_ref::<T>().unwrap().clone()),
+                None => {
+                    world.remove_resource::<T>();
+                }
+            }
+        }
+
+        Self {
+            info: TypeInfo::of::<T>(),
+            capture: capture::<T>,
+            restore: restore::<T>,
+        }
+    }
+}
+
+/// The component and resource types captured by a [`Snapshot`].
+#[derive(Clone, Default)]
+pub struct SnapshotTypes {
+    components: Vec<ComponentFns>,
+    resources: Vec<ResourceFns>,
+}
+
+impl SnapshotTypes {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn component<T: AnyComponent + Clone>(&mut self) -> &mut Self {
+        if !self
+            .components
+            .iter()
+            .any(|fns| fns.info == TypeInfo::of::<T>())
+        {
+            self.components.push(ComponentFns::of::<T>());
+        }
+
+        self
+    }
+
+    #[inline]
+    pub fn resource<T: Resource + Clone>(&mut self) -> &mut Self {
+        if !self
+            .resources
+            .iter()
+            .any(|fns| fns.info == TypeInfo::of::<T>())
+        {
+            self.resources.push(ResourceFns::of::<T>());
+        }
+
+        self
+    }
+}
+
+pub struct Snapshot {
+    change_tick: u64,
+    components: Vec<(ComponentFns, Data)>,
+    resources: Vec<(ResourceFns, Option<Data>)>,
+}
+
+impl Snapshot {
+    /// Captures the components and resources of `types`, advancing the change tick of `world`.
+    #[inline]
+    pub fn capture(world: &World, types: &SnapshotTypes) -> Self {
+        Self::capture_inner(world, types, None)
+    }
+
+    /// Captures a snapshot sharing every component that hasn't changed since `base` was captured.
+    #[inline]
+    pub fn capture_incremental(world: &World, types: &SnapshotTypes, base: &Snapshot) -> Self {
+        Self::capture_inner(world, types, Some(base))
+    }
+
+    #[inline]
+    fn capture_inner(world: &World, types: &SnapshotTypes, base: Option<&Snapshot>) -> Self {
+        let components = types
+            .components
+            .iter()
+            .map(|fns| {
+                let base = base.and_then(|base| base.component_data(&fns.info));
+
+                (*fns, (fns.capture)(world, base))
+            })
+            .collect();
+
+        let resources = types
+            .resources
+            .iter()
+            .map(|fns| (*fns, (fns.capture)(world)))
+            .collect();
+
+        // changes made after capturing get a later tick than the captured components, so
+        // they're never mistaken for the captured values
+        let change_tick = world.increment_change_tick();
+
+        Self {
+            change_tick,
+            components,
+            resources,
+        }
+    }
+
+    #[inline]
+    fn component_data(&self, info: &TypeInfo) -> Option<&Data> {
+        self.components
+            .iter()
+            .find(|(fns, _)| fns.info == *info)
+            .map(|(_, data)| data)
+    }
+
+    #[inline]
+    pub fn change_tick(&self) -> u64 {
+        self.change_tick
+    }
+
+    #[inline]
+    pub fn entities(&self) -> BTreeSet<Entity> {
+        self.components
+            .iter()
+            .flat_map(|(fns, data)| (fns.entities)(data))
+            .collect()
+    }
+
+    #[inline]
+    pub fn restore(&self, world: &mut World) {
+        for (fns, data) in &self.components {
+            (fns.restore)(world, data);
+        }
+
+        for (fns, data) in &self.resources {
+            (fns.restore)(world, data.as_ref());
+        }
+    }
+
+    /// Returns the changes needed to go from `self` to `other`.
+    #[inline]
+    pub fn diff(&self, other: &Snapshot) -> SnapshotDiff {
+        let from = self.entities();
+        let to = other.entities();
+
+        let mut changed = Vec::new();
+
+        for (fns, data) in &other.components {
+            if let Some(from_data) = self.component_data(&fns.info) {
+                for entity in (fns.changed)(from_data, data) {
+                    changed.push((entity, fns.info));
+                }
+            }
+        }
+
+        SnapshotDiff {
+            added: to.difference(&from).copied().collect(),
+            removed: from.difference(&to).copied().collect(),
+            changed,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SnapshotDiff {
+    pub added: Vec<Entity>,
+    pub removed: Vec<Entity>,
+    pub changed: Vec<(Entity, TypeInfo)>,
+}
+
+impl SnapshotDiff {
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot() {
+        let mut world = World::new();
+
+        let a = world.create_entity();
+        let b = world.create_entity();
+
+        world.insert(a, 1i32);
+        world.insert(b, 2i32);
+        world.insert(b, true);
+        world.insert_resource(5u64);
+
+        let mut types = SnapshotTypes::new();
+        types
+            .component::<i32>()
+            .component::<bool>()
+            .resource::<u64>();
+
+        let first = Snapshot::capture(&world, &types);
+
+        let c = world.create_entity();
+        world.insert(c, 3i32);
+        *world.get_component_mut::<i32>(&a).unwrap() = 10;
+        world.remove::<i32>(&b);
+        world.remove::<bool>(&b);
+        *world.write_resource::<u64>().unwrap() = 6;
+
+        let second = Snapshot::capture_incremental(&world, &types, &first);
+
+        let diff = first.diff(&second);
+        assert_eq!(diff.added, vec![c]);
+        assert_eq!(diff.removed, vec![b]);
+        assert_eq!(diff.changed, vec![(a, TypeInfo::of::<i32>())]);
+
+        assert!(second.diff(&second).is_empty());
+
+        first.restore(&mut world);
+
+        assert_eq!(*world.get_component::<i32>(&a).unwrap(), 1);
+        assert_eq!(*world.get_component::<i32>(&b).unwrap(), 2);
+        assert!(world.contains_component::<bool>(&b));
+        assert!(!world.contains_component::<i32>(&c));
+        assert_eq!(*world.read_resource::<u64>().unwrap(), 5);
+    }
+
+    #[test]
+    fn same_frame_changes() {
+        let mut world = World::new();
+
+        let a = world.create_entity();
+        world.insert(a, 1i32);
+
+        let mut types = SnapshotTypes::new();
+        types.component::<i32>();
+
+        let first = Snapshot::capture(&world, &types);
+        *world.get_component_mut::<i32>(&a).unwrap() = 2;
+        let second = Snapshot::capture_incremental(&world, &types, &first);
+        *world.get_component_mut::<i32>(&a).unwrap() = 3;
+        let third = Snapshot::capture_incremental(&world, &types, &second);
+
+        assert_eq!(
+            first.diff(&second).changed,
+            vec![(a, TypeInfo::of::<i32>())]
+        );
+        assert_eq!(
+            second.diff(&third).changed,
+            vec![(a, TypeInfo::of::<i32>())]
+        );
+
+        second.restore(&mut world);
+        assert_eq!(*world.get_component::<i32>(&a).unwrap(), 2);
+
+        first.restore(&mut world);
+        assert_eq!(*world.get_component::<i32>(&a).unwrap(), 1);
+    }
+
+    #[test]
+    fn restore_resources() {
+        let mut world = World::new();
+
+        let mut types = SnapshotTypes::new();
+        types.resource::<u32>().resource::<u64>();
+
+        world.insert_resource(1u32);
+        let snapshot = Snapshot::capture(&world, &types);
+
+        world.insert_resource(2u32);
+        world.insert_resource(3u64);
+        snapshot.restore(&mut world);
+
+        assert_eq!(*world.read_resource::<u32>().unwrap(), 1);
+        assert!(world.read_resource::<u64>().is_none());
+    }
+}
@@ -1,11 +1,15 @@
 use std::{
-    any::TypeId,
-    collections::{BTreeMap, HashMap},
+    any::{type_name, TypeId},
+    borrow::Cow,
+    collections::BTreeMap,
 };
 
 use rayon::iter::{IntoParallelRefMutIterator, ParallelIterator};
 
-use crate::{AnyComponent, BorrowLock, Resource, World};
+use crate::{
+    AccessInfo, AnyComponent, BorrowLock, Resource, SchedulePlan, StepConflict, StepPlan,
+    SystemPlan, World,
+};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Access {
@@ -44,20 +48,63 @@ pub enum AccessType {
 #[derive(Clone, Default, Debug)]
 pub struct SystemAccess {
     pub access: BTreeMap<AccessType, Access>,
+    pub names: BTreeMap<AccessType, &'static str>,
     pub world: bool,
 }
 
 impl SystemAccess {
     #[inline]
     pub fn borrow_component<T: AnyComponent>(&mut self, access: Access) {
-        self.access
-            .insert(AccessType::Component(TypeId::of::<T>()), access);
+        let ty = AccessType::Component(TypeId::of::<T>());
+
+        self.access.insert(ty, access);
+        self.names.insert(ty, type_name::<T>());
     }
 
     #[inline]
     pub fn borrow_resource<T: Resource>(&mut self, access: Access) {
+        let ty = AccessType::Resource(TypeId::of::<T>());
+
+        self.access.insert(ty, access);
+        self.names.insert(ty, type_name::<T>());
+    }
+
+    #[inline]
+    pub fn name(&self, ty: &AccessType) -> &'static str {
+        self.names.get(ty).copied().unwrap_or("<unknown>")
+    }
+
+    #[inline]
+    pub fn info(&self) -> Vec<AccessInfo> {
         self.access
-            .insert(AccessType::Component(TypeId::of::<T>()), access);
+            .iter()
+            .map(|(ty, access)| AccessInfo {
+                ty: *ty,
+                name: self.name(ty),
+                access: *access,
+            })
+            .collect()
+    }
+
+    /// Returns true if either access borrows the whole world while the other borrows anything.
+    #[inline]
+    pub fn world_conflict(&self, other: &Self) -> bool {
+        (self.world && (other.world || !other.access.is_empty()))
+            || (other.world && !self.access.is_empty())
+    }
+
+    /// Returns the entries of `self` that can't be borrowed at the same time as `other`.
+    #[inline]
+    pub fn conflicts(&self, other: &Self) -> Vec<AccessInfo> {
+        self.info()
+            .into_iter()
+            .filter(|info| {
+                other
+                    .access
+                    .get(&info.ty)
+                    .is_some_and(|access| !info.access.compatible(access))
+            })
+            .collect()
     }
 
     #[inline]
@@ -96,6 +143,8 @@ impl SystemAccess {
     pub fn combine(&mut self, other: Self) {
         self.world |= other.world;
 
+        self.names.extend(other.names);
+
         for (ty, access) in other.access {
             if let Some(this_access) = self.access.get_mut(&ty) {
                 *this_access = (*this_access).max(access);
@@ -108,86 +157,299 @@ impl SystemAccess {
 
 pub trait ExclusiveSystem: Send + Sync + 'static {
     fn run(&mut self, world: &mut World);
+
+    #[inline]
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Borrowed(type_name::<Self>())
+    }
 }
 
 pub trait System: Send + Sync + 'static {
     fn access(&self) -> SystemAccess;
 
     fn run(&mut self, world: &World);
+
+    #[inline]
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Borrowed(type_name::<Self>())
+    }
+}
+
+// systems are kept in registration order, adding a system of the same type replaces it in place.
+fn insert_ordered<T>(systems: &mut Vec<(TypeId, T)>, id: TypeId, system: T) {
+    if let Some((_, existing)) = systems.iter_mut().find(|(existing_id, _)| *existing_id == id) {
+        *existing = system;
+    } else {
+        systems.push((id, system));
+    }
 }
 
 #[derive(Default)]
 pub struct ScheduleStep {
     pub access: SystemAccess,
-    pub systems: HashMap<TypeId, BorrowLock<dyn System>>,
+    pub systems: Vec<(TypeId, BorrowLock<dyn System>)>,
+    /// The earlier steps each system conflicted with when it was added.
+    pub conflicts: Vec<(TypeId, Vec<StepConflict>)>,
 }
 
 #[derive(Default)]
 pub struct Schedule {
     steps: Vec<ScheduleStep>,
-    exclusive_systems: HashMap<TypeId, Box<dyn ExclusiveSystem>>,
+    exclusive_systems: Vec<(TypeId, Box<dyn ExclusiveSystem>)>,
 }
 
 impl Schedule {
     #[inline]
     pub fn add_system<T: System>(&mut self, system: T) {
         let access = system.access();
+        let mut conflicts = Vec::new();
 
-        for step in &mut self.steps {
+        for (i, step) in self.steps.iter_mut().enumerate() {
             if step.access.compatible(&access) {
                 step.access.combine(access);
 
-                step.systems
-                    .insert(TypeId::of::<T>(), BorrowLock::from_box(Box::new(system)));
+                insert_ordered(
+                    &mut step.systems,
+                    TypeId::of::<T>(),
+                    BorrowLock::from_box(Box::new(system)),
+                );
+                insert_ordered(&mut step.conflicts, TypeId::of::<T>(), conflicts);
 
                 return;
             }
+
+            conflicts.push(StepConflict {
+                step: i,
+                world: access.world_conflict(&step.access),
+                access: access.conflicts(&step.access),
+            });
         }
 
         let mut step = ScheduleStep {
             access,
-            systems: HashMap::new(),
+            systems: Vec::new(),
+            conflicts: Vec::new(),
         };
 
-        step.systems
-            .insert(TypeId::of::<T>(), BorrowLock::from_box(Box::new(system)));
+        insert_ordered(
+            &mut step.systems,
+            TypeId::of::<T>(),
+            BorrowLock::from_box(Box::new(system)),
+        );
+        insert_ordered(&mut step.conflicts, TypeId::of::<T>(), conflicts);
 
         self.steps.push(step);
     }
 
     #[inline]
     pub fn add_exclusive_system<T: ExclusiveSystem>(&mut self, system: T) {
-        self.exclusive_systems
-            .insert(TypeId::of::<T>(), Box::new(system));
+        insert_ordered(
+            &mut self.exclusive_systems,
+            TypeId::of::<T>(),
+            Box::new(system),
+        );
     }
 
     #[inline]
     pub fn execute(&mut self, world: &mut World) {
-        for system in self.exclusive_systems.values_mut() {
+        for (_, system) in &mut self.exclusive_systems {
             system.run(world);
 
             world.dequeue();
         }
 
         for step in &mut self.steps {
-            step.systems.par_iter_mut().for_each(|(_, system)| {
-                let system = system.get_mut();
+            // running a step sequentially keeps commands and entity allocation in registration order
+            if world.is_deterministic() {
+                for (_, system) in &mut step.systems {
+                    system.get_mut().run(world);
+                }
+            } else {
+                step.systems.par_iter_mut().for_each(|(_, system)| {
+                    let system = system.get_mut();
 
-                system.run(world);
-            });
+                    system.run(world);
+                });
+            }
 
             world.dequeue();
         }
     }
 
+    #[inline]
+    pub fn plan(&self) -> SchedulePlan {
+        let exclusive_systems = self
+            .exclusive_systems
+            .iter()
+            .map(|(_, system)| system.name())
+            .collect();
+
+        let steps = self
+            .steps
+            .iter()
+            .map(|step| {
+                let systems = step
+                    .systems
+                    .iter()
+                    .map(|(id, system)| {
+                        let system = system.read().unwrap();
+
+                        let conflicts = step
+                            .conflicts
+                            .iter()
+                            .find(|(conflict_id, _)| conflict_id == id)
+                            .map(|(_, conflicts)| conflicts.clone())
+                            .unwrap_or_default();
+
+                        SystemPlan {
+                            name: system.name(),
+                            access: system.access(),
+                            conflicts,
+                        }
+                    })
+                    .collect();
+
+                StepPlan { systems }
+            })
+            .collect();
+
+        SchedulePlan {
+            exclusive_systems,
+            steps,
+        }
+    }
+
+    #[deprecated(note = "print or inspect `Schedule::plan` instead")]
     #[inline]
     pub fn dump(&self) {
-        for step in &self.steps {
-            println!("step:");
+        println!("{}", self.plan().to_string().trim_end());
+    }
+}
 
-            for (id, system) in &step.systems {
-                println!("{:?}: {:?}", id, system.read().unwrap().access());
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Commands, Entity, FnSystem, Name, QueryMut, Res, ResMut};
+
+    #[test]
+    fn plan() {
+        fn write(_value: ResMut<u32>) {}
+        fn read(_value: Res<u32>) {}
+        fn query(_query: QueryMut<&mut u32>) {}
+
+        let mut schedule = Schedule::default();
+        schedule.add_system(write.system());
+        schedule.add_system(read.system());
+        schedule.add_system(query.system());
+
+        let plan = schedule.plan();
+
+        assert_eq!(plan.steps.len(), 2);
+        assert_eq!(plan.steps[0].systems.len(), 2);
+        assert!(plan.steps[0].systems[1].name.ends_with("query"));
+
+        let read = &plan.steps[1].systems[0];
+        assert!(read.name.ends_with("read"));
+        assert_eq!(read.conflicts.len(), 1);
+        assert_eq!(read.conflicts[0].access[0].name, "u32");
+
+        let ambiguities = plan.ambiguities();
+        assert_eq!(ambiguities.len(), 1);
+        assert!(ambiguities[0].first.ends_with("write"));
+        assert!(ambiguities[0].second.ends_with("read"));
+
+        let dot = plan.to_dot();
+        assert!(dot.starts_with("digraph"));
+        assert!(dot.contains("style=dashed"));
+    }
+
+    // resources and components of the same type are stored apart and don't conflict
+    #[test]
+    fn resource_access() {
+        fn write_resource(mut value: ResMut<u32>) {
+            *value += 1;
+        }
+
+        fn write_components(query: QueryMut<&mut u32>) {
+            for mut value in query {
+                *value += 1;
             }
         }
+
+        fn read_resource(_value: Res<u32>) {}
+
+        let mut schedule = Schedule::default();
+        schedule.add_system(write_resource.system());
+        schedule.add_system(write_components.system());
+        schedule.add_system(read_resource.system());
+
+        let plan = schedule.plan();
+
+        assert_eq!(plan.steps.len(), 2);
+        assert_eq!(plan.steps[0].systems.len(), 2);
+
+        let access = &plan.steps[0].systems[0].access;
+        assert_eq!(
+            access.access.keys().next(),
+            Some(&AccessType::Resource(TypeId::of::<u32>()))
+        );
+
+        let read = &plan.steps[1].systems[0];
+        assert_eq!(read.conflicts[0].access[0].to_string(), "Read resource u32");
+
+        let mut world = World::new();
+        world.insert_resource(0u32);
+
+        let entity = world.create_entity();
+        world.insert(entity, 0u32);
+
+        schedule.execute(&mut world);
+
+        assert_eq!(*world.read_resource::<u32>().unwrap(), 1);
+        assert_eq!(*world.get_component::<u32>(&entity).unwrap(), 1);
+    }
+
+    #[test]
+    fn deterministic() {
+        fn spawn_a(commands: Commands) {
+            commands.spawn_node("a");
+        }
+
+        fn spawn_b(commands: Commands) {
+            commands.spawn_node("b");
+        }
+
+        fn run() -> Vec<(Entity, String)> {
+            let mut world = World::new();
+            world.set_deterministic(true);
+
+            let mut schedule = Schedule::default();
+            schedule.add_system(spawn_a.system());
+            schedule.add_system(spawn_b.system());
+
+            for _ in 0..8 {
+                schedule.execute(&mut world);
+            }
+
+            let mut nodes: Vec<_> = world
+                .query::<(Entity, Name), ()>()
+                .unwrap()
+                .map(|(entity, name)| (entity, String::from(name)))
+                .collect();
+
+            nodes.sort();
+
+            nodes
+        }
+
+        let nodes = run();
+
+        assert_eq!(nodes.len(), 16);
+        assert_eq!(nodes[0].1, "a");
+        assert_eq!(nodes[1].1, "b");
+
+        for _ in 0..8 {
+            assert_eq!(run(), nodes);
+        }
     }
 }
@@ -0,0 +1,302 @@
+use crate::{stage, AppBuilder, FnSystem, Plugin, QueryMut, Res, Time};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Stopwatch {
+    elapsed: f32,
+    paused: bool,
+}
+
+impl Stopwatch {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn tick(&mut self, delta_time: f32) -> &Self {
+        if !self.paused {
+            self.elapsed += delta_time;
+        }
+
+        self
+    }
+
+    #[inline]
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    #[inline]
+    pub fn set_elapsed(&mut self, elapsed: f32) {
+        self.elapsed = elapsed;
+    }
+
+    #[inline]
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    #[inline]
+    pub fn unpause(&mut self) {
+        self.paused = false;
+    }
+
+    #[inline]
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    #[inline]
+    pub fn reset(&mut self) {
+        self.elapsed = 0.0;
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimerMode {
+    Once,
+    Repeating,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Timer {
+    stopwatch: Stopwatch,
+    duration: f32,
+    mode: TimerMode,
+    finished: bool,
+    times_finished_this_tick: u32,
+}
+
+impl Timer {
+    #[inline]
+    pub fn new(duration: f32, mode: TimerMode) -> Self {
+        Self {
+            stopwatch: Stopwatch::new(),
+            duration,
+            mode,
+            finished: false,
+            times_finished_this_tick: 0,
+        }
+    }
+
+    #[inline]
+    pub fn once(duration: f32) -> Self {
+        Self::new(duration, TimerMode::Once)
+    }
+
+    #[inline]
+    pub fn repeating(duration: f32) -> Self {
+        Self::new(duration, TimerMode::Repeating)
+    }
+
+    #[inline]
+    pub fn tick(&mut self, delta_time: f32) -> &Self {
+        let repeating = self.mode == TimerMode::Repeating;
+
+        if self.stopwatch.is_paused() || (self.finished && !repeating) {
+            self.times_finished_this_tick = 0;
+            self.finished &= !repeating;
+
+            return self;
+        }
+
+        self.stopwatch.tick(delta_time);
+        self.finished = self.elapsed() >= self.duration;
+
+        if !self.finished {
+            self.times_finished_this_tick = 0;
+        } else if repeating && self.duration > 0.0 {
+            self.times_finished_this_tick = (self.elapsed() / self.duration) as u32;
+            self.stopwatch.set_elapsed(self.elapsed() % self.duration);
+        } else if repeating {
+            self.times_finished_this_tick = 1;
+            self.stopwatch.set_elapsed(0.0);
+        } else {
+            self.times_finished_this_tick = 1;
+            self.stopwatch.set_elapsed(self.duration);
+        }
+
+        self
+    }
+
+    #[inline]
+    pub fn finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Returns true if the timer finished during the last tick.
+    #[inline]
+    pub fn just_finished(&self) -> bool {
+        self.times_finished_this_tick > 0
+    }
+
+    /// Repeating timers can finish several times in one tick when the delta exceeds the duration.
+    #[inline]
+    pub fn times_finished_this_tick(&self) -> u32 {
+        self.times_finished_this_tick
+    }
+
+    #[inline]
+    pub fn elapsed(&self) -> f32 {
+        self.stopwatch.elapsed()
+    }
+
+    #[inline]
+    pub fn remaining(&self) -> f32 {
+        (self.duration - self.elapsed()).max(0.0)
+    }
+
+    #[inline]
+    pub fn percent(&self) -> f32 {
+        if self.duration > 0.0 {
+            (self.elapsed() / self.duration).clamp(0.0, 1.0)
+        } else {
+            1.0
+        }
+    }
+
+    #[inline]
+    pub fn percent_left(&self) -> f32 {
+        1.0 - self.percent()
+    }
+
+    #[inline]
+    pub fn duration(&self) -> f32 {
+        self.duration
+    }
+
+    #[inline]
+    pub fn set_duration(&mut self, duration: f32) {
+        self.duration = duration;
+    }
+
+    #[inline]
+    pub fn mode(&self) -> TimerMode {
+        self.mode
+    }
+
+    #[inline]
+    pub fn set_mode(&mut self, mode: TimerMode) {
+        self.mode = mode;
+    }
+
+    #[inline]
+    pub fn pause(&mut self) {
+        self.stopwatch.pause();
+    }
+
+    #[inline]
+    pub fn unpause(&mut self) {
+        self.stopwatch.unpause();
+    }
+
+    #[inline]
+    pub fn is_paused(&self) -> bool {
+        self.stopwatch.is_paused()
+    }
+
+    #[inline]
+    pub fn reset(&mut self) {
+        self.stopwatch.reset();
+        self.finished = false;
+        self.times_finished_this_tick = 0;
+    }
+}
+
+fn timer_system(
+    time: Res<Time>,
+    timers: QueryMut<&mut Timer>,
+    stopwatches: QueryMut<&mut Stopwatch>,
+) {
+    for mut timer in timers {
+        timer.tick(time.delta_time());
+    }
+
+    for mut stopwatch in stopwatches {
+        stopwatch.tick(time.delta_time());
+    }
+}
+
+/// Ticks every [`Timer`] and [`Stopwatch`] component with the virtual clock of [`Time`].
+pub struct TimerPlugin;
+
+impl Plugin for TimerPlugin {
+    #[inline]
+    fn build(self, app: &mut AppBuilder) {
+        app.init_resource::<Time>();
+        app.add_system_to_stage(timer_system.system(), stage::PRE_UPDATE);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{System, World};
+
+    #[test]
+    fn timer() {
+        let mut timer = Timer::once(1.0);
+
+        assert!(!timer.tick(0.5).just_finished());
+        assert_eq!(timer.percent(), 0.5);
+        assert!(timer.tick(0.75).just_finished());
+        assert!(timer.finished());
+        assert!(!timer.tick(0.5).just_finished());
+        assert_eq!(timer.elapsed(), 1.0);
+
+        let mut timer = Timer::repeating(1.0);
+
+        assert_eq!(timer.tick(2.5).times_finished_this_tick(), 2);
+        assert_eq!(timer.elapsed(), 0.5);
+        assert!(!timer.tick(0.25).finished());
+
+        timer.pause();
+        assert_eq!(timer.tick(1.0).elapsed(), 0.75);
+    }
+
+    #[test]
+    fn timer_system_virtual_time() {
+        let mut world = World::new();
+        world.init_resource::<Time>();
+
+        let entity = world.create_entity();
+        world.insert(entity, Timer::once(1.0));
+        world.insert(entity, Stopwatch::new());
+
+        let mut system = timer_system.system();
+
+        world.write_resource::<Time>().unwrap().set_time_scale(2.0);
+        world.write_resource::<Time>().unwrap().advance_frame(0.25);
+        system.run(&world);
+
+        assert_eq!(
+            world.get_component::<Stopwatch>(&entity).unwrap().elapsed(),
+            0.5
+        );
+
+        world.write_resource::<Time>().unwrap().pause();
+        world.write_resource::<Time>().unwrap().advance_frame(1.0);
+        system.run(&world);
+
+        assert_eq!(
+            world.get_component::<Timer>(&entity).unwrap().elapsed(),
+            0.5
+        );
+        assert_eq!(
+            world
+                .read_resource::<Time>()
+                .unwrap()
+                .real_time_since_startup(),
+            1.25
+        );
+
+        world.write_resource::<Time>().unwrap().unpause();
+        world.write_resource::<Time>().unwrap().advance_frame(0.25);
+        system.run(&world);
+
+        assert!(world
+            .get_component::<Timer>(&entity)
+            .unwrap()
+            .just_finished());
+    }
+}
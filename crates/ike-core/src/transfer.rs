@@ -0,0 +1,316 @@
+use std::{
+    alloc::{alloc, dealloc, handle_alloc_error, Layout},
+    collections::HashMap,
+    ptr,
+};
+
+use crate::{AnyComponent, ComponentDescriptor, ComponentId, Entity, OwnedComponent};
+
+pub trait MapEntities {
+    /// Remaps the entities the component refers to, returns false if it refers to an entity
+    /// missing from `map`, in which case the component is dropped.
+    fn map_entities(&mut self, map: &EntityMap) -> bool;
+}
+
+pub(crate) type EntityMapper = unsafe fn(*mut u8, &EntityMap) -> bool;
+
+#[inline]
+pub(crate) fn entity_mapper<T: AnyComponent + MapEntities>() -> EntityMapper {
+    unsafe fn map<T: MapEntities>(component: *mut u8, map: &EntityMap) -> bool {
+        unsafe { &mut *(component as *mut T) }.map_entities(map)
+    }
+
+    map::<T>
+}
+
+/// Maps entities of one world to entities of another.
+#[derive(Clone, Debug, Default)]
+pub struct EntityMap {
+    map: HashMap<Entity, Entity>,
+}
+
+impl EntityMap {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn insert(&mut self, from: Entity, to: Entity) {
+        self.map.insert(from, to);
+    }
+
+    #[inline]
+    pub fn get(&self, entity: &Entity) -> Option<Entity> {
+        self.map.get(entity).copied()
+    }
+
+    /// Returns the mapped entity, or `None` if `entity` isn't in the map.
+    #[inline]
+    pub fn map(&self, entity: Entity) -> Option<Entity> {
+        self.get(&entity)
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, Entity)> + '_ {
+        self.map.iter().map(|(from, to)| (*from, *to))
+    }
+}
+
+// an owned dynamic component, stored in an allocation with the component's layout
+pub(crate) struct DynamicComponentData {
+    pub(crate) descriptor: ComponentDescriptor,
+    data: *mut u8,
+}
+
+unsafe impl Send for DynamicComponentData {}
+unsafe impl Sync for DynamicComponentData {}
+
+impl DynamicComponentData {
+    /// # Safety
+    /// - `component` must point to a valid value described by `descriptor`, ownership of which
+    ///   is moved into the returned value.
+    #[inline]
+    pub(crate) unsafe fn new(descriptor: ComponentDescriptor, component: *mut u8) -> Self {
+        let data = if descriptor.layout.size() == 0 {
+            descriptor.layout.align() as *mut u8
+        } else {
+            let data = unsafe { alloc(descriptor.layout) };
+
+            if data.is_null() {
+                handle_alloc_error(descriptor.layout);
+            }
+
+            data
+        };
+
+        unsafe { ptr::copy_nonoverlapping(component, data, descriptor.layout.size()) };
+
+        Self { descriptor, data }
+    }
+
+    #[inline]
+    pub(crate) fn bytes(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.data, self.descriptor.layout.size()) }
+    }
+
+    // frees the allocation without dropping the component, after it has been copied elsewhere
+    #[inline]
+    pub(crate) fn forget(mut self) {
+        self.descriptor.drop = None;
+    }
+
+    #[inline]
+    fn layout(&self) -> Layout {
+        self.descriptor.layout
+    }
+}
+
+impl Drop for DynamicComponentData {
+    #[inline]
+    fn drop(&mut self) {
+        if let Some(drop) = self.descriptor.drop {
+            unsafe { drop(self.data) };
+        }
+
+        if self.layout().size() > 0 {
+            unsafe { dealloc(self.data, self.layout()) };
+        }
+    }
+}
+
+pub(crate) enum TakenComponent {
+    Typed {
+        id: ComponentId,
+        component: OwnedComponent,
+        mapper: Option<EntityMapper>,
+    },
+    Dynamic(DynamicComponentData),
+}
+
+/// An entity and all its components, removed from a [`World`](crate::World)
+/// with [`World::take_entity`](crate::World::take_entity).
+pub struct TakenEntity {
+    pub(crate) entity: Entity,
+    pub(crate) name: Option<String>,
+    pub(crate) components: Vec<TakenComponent>,
+}
+
+impl TakenEntity {
+    /// The entity in the world it was taken from.
+    #[inline]
+    pub fn entity(&self) -> Entity {
+        self.entity
+    }
+
+    #[inline]
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{alloc::Layout, thread};
+
+    use super::*;
+    use crate::World;
+
+    struct Link(Entity);
+
+    impl MapEntities for Link {
+        fn map_entities(&mut self, map: &EntityMap) -> bool {
+            match map.map(self.0) {
+                Some(entity) => {
+                    self.0 = entity;
+                    true
+                }
+                None => false,
+            }
+        }
+    }
+
+    #[test]
+    fn transfer() {
+        let mut from = World::new();
+        from.register_entity_mapper::<Link>();
+
+        let position = from.register_dynamic_component(ComponentDescriptor::new(
+            "position",
+            Layout::new::<[f32; 2]>(),
+        ));
+
+        let parent = from.spawn_node("parent").entity();
+        let child = from.create_entity();
+        from.dequeue();
+
+        from.insert(parent, String::from("data"));
+        from.insert_bytes(parent, position, &[1u8; 8]);
+        from.insert(child, Link(parent));
+
+        let mut to = thread::spawn(|| {
+            let mut to = World::new();
+
+            let other = to.create_entity();
+            to.insert(other, String::from("other"));
+
+            to
+        })
+        .join()
+        .unwrap();
+
+        let taken = vec![
+            from.take_entity(&parent).unwrap(),
+            from.take_entity(&child).unwrap(),
+        ];
+        let map = to.insert_taken_many(taken);
+
+        assert!(from.find_node("parent").is_none());
+        assert!(!from.contains_component::<String>(&parent));
+        assert!(!from.contains_component::<Link>(&child));
+
+        let new_parent = map.get(&parent).unwrap();
+        let new_child = map.get(&child).unwrap();
+
+        assert_eq!(to.find_node("parent").unwrap().entity(), new_parent);
+        assert_eq!(*to.get_component::<String>(&new_parent).unwrap(), "data");
+        assert_eq!(to.get_component::<Link>(&new_child).unwrap().0, new_parent);
+
+        let (id, _) = to
+            .storages()
+            .map(|storage| (storage.ty.id, storage.ty.name))
+            .find(|(_, name)| *name == "position")
+            .unwrap();
+
+        assert_eq!(&*to.get_bytes(&new_parent, id).unwrap(), &[1u8; 8]);
+    }
+
+    #[test]
+    fn unmapped_references() {
+        let mut from = World::new();
+        from.register_entity_mapper::<Link>();
+
+        let parent = from.create_entity();
+        let child = from.create_entity();
+        from.insert(child, Link(parent));
+        from.insert(child, 1i32);
+
+        let mut to = World::new();
+        let new_child = to.insert_taken(from.take_entity(&child).unwrap());
+
+        assert!(!to.contains_component::<Link>(&new_child));
+        assert_eq!(*to.get_component::<i32>(&new_child).unwrap(), 1);
+
+        let mut map = EntityMap::new();
+        map.insert(parent, new_child);
+
+        assert_eq!(map.map(parent), Some(new_child));
+        assert_eq!(map.map(child), None);
+    }
+
+    #[test]
+    fn take_twice() {
+        let mut world = World::new();
+
+        let a = world.create_entity();
+        world.insert(a, 1u32);
+
+        assert!(world.take_entity(&a).is_some());
+        assert!(world.take_entity(&a).is_none());
+        assert!(!world.contains_entity(&a));
+
+        // the index of `a` is only handed out once
+        let b = world.create_entity();
+        let c = world.create_entity();
+        assert_ne!(b, c);
+
+        world.insert(b, 10u32);
+        world.insert(c, 20u32);
+
+        assert_eq!(*world.get_component::<u32>(&b).unwrap(), 10);
+        assert_eq!(*world.get_component::<u32>(&c).unwrap(), 20);
+    }
+
+    #[test]
+    fn stale_entities() {
+        let mut world = World::new();
+
+        let a = world.create_entity();
+        world.despawn(&a);
+
+        let b = world.create_entity();
+        world.insert(b, 10u32);
+        assert_eq!(b.idx(), a.idx());
+
+        // a stale handle to the reused index doesn't take the new entity
+        assert!(world.take_entity(&a).is_none());
+        assert!(world.contains_entity(&b));
+        assert_eq!(*world.get_component::<u32>(&b).unwrap(), 10);
+
+        // nor does one that was never created
+        assert!(world.take_entity(&Entity::from_raw(100, 0)).is_none());
+
+        let c = world.create_entity();
+        assert_ne!(c.idx(), b.idx());
+    }
+}
@@ -2,25 +2,31 @@ use std::{any::TypeId, borrow::Cow, collections::HashMap, sync::atomic::{AtomicU
 
 use crossbeam::queue::SegQueue;
 
-use crate::{AnyComponent, BorrowLock, ComponentStorage, Entity, EntityRegistry, Node, OwnedComponent, Query, QueryFilter, QueryMut, ReadGuard, Resource, Resources, WriteGuard};
+use crate::{Access, AnyComponent, BorrowLock, ComponentDescriptor, ComponentId, ComponentInfo, ComponentStorage, DynamicComponentData, DynamicQuery, Entity, EntityMap, EntityMapper, EntityRegistry, MapEntities, Node, Relation, RelationIndex, RelationKind, relation_id, OwnedComponent, Query, QueryFilter, QueryMut, ReadGuard, ResMut, Resource, Resources, StorageInfo, TakenComponent, TakenEntity, TypeInfo, WriteGuard, entity_mapper};
 
 enum Command {
     Insert(Entity, OwnedComponent),
+    InsertBytes(Entity, ComponentId, Vec<u8>),
     InsertNode(Entity, String),
-    InsertResource(TypeId, BorrowLock<dyn Resource>),
+    InsertResource(TypeInfo, BorrowLock<dyn Resource>),
     RemoveResource(TypeId),
-    InitResource(TypeId, BorrowLock<dyn Resource>),
+    InitResource(TypeInfo, BorrowLock<dyn Resource>),
 }
 
 pub struct World {
-    pub(crate) components: HashMap<TypeId, ComponentStorage>,
+    pub(crate) components: HashMap<ComponentId, ComponentStorage>,
+    next_dynamic_component: u64,
+    entity_mappers: HashMap<ComponentId, EntityMapper>,
+    relations: HashMap<ComponentId, RelationIndex>,
     pub(crate) entities: Vec<Entity>,
     nodes: HashMap<Entity, String>,
+    node_names: HashMap<String, Vec<Entity>>,
     resources: Resources,
     commands: SegQueue<Command>,
     entity_registry: EntityRegistry,
     change_tick: AtomicU64,
     last_change_tick: u64,
+    deterministic: bool,
 }
 
 impl Default for World {
@@ -35,32 +41,49 @@ impl World {
     pub fn new() -> Self {
         Self {
             components: HashMap::new(),
+            next_dynamic_component: 0,
+            entity_mappers: HashMap::new(),
+            relations: HashMap::new(),
             entities: Vec::new(),
             nodes: HashMap::new(),
+            node_names: HashMap::new(),
             resources: Resources::new(),
             commands: SegQueue::new(),
             entity_registry: EntityRegistry::new(),
             change_tick: AtomicU64::new(1),
             last_change_tick: 0,
+            deterministic: false,
         }
     }
 
+    #[inline]
+    pub fn set_deterministic(&mut self, deterministic: bool) {
+        self.deterministic = deterministic;
+    }
+
+    #[inline]
+    pub fn is_deterministic(&self) -> bool {
+        self.deterministic
+    }
+
     #[inline]
     pub fn insert_resource<T: Resource>(&mut self, resource: T) {
-        self.resources.insert(resource);
+        let change_tick = self.change_tick();
+        self.resources.insert_ticked(resource, change_tick);
     }
 
     #[inline]
     pub fn init_resource<T: Resource + Default>(&mut self) {
         if !self.resources.contains::<T>() {
-            self.resources.insert(T::default());
+            let change_tick = self.change_tick();
+            self.resources.insert_ticked(T::default(), change_tick);
         }
     }
 
     #[inline]
     pub fn queue_insert_resource<T: Resource>(&self, resource: T) {
         self.commands.push(Command::InsertResource(
-            TypeId::of::<T>(),
+            TypeInfo::of::<T>(),
             BorrowLock::from_box(Box::new(resource)),
         ));
     }
@@ -69,7 +92,7 @@ impl World {
     pub fn queue_init_resource<T: Resource + Default>(&self) {
         if !self.resources.contains::<T>() {
             self.commands.push(Command::InitResource(
-                TypeId::of::<T>(),
+                TypeInfo::of::<T>(),
                 BorrowLock::from_box(Box::new(T::default())),
             ));
         }
@@ -91,9 +114,29 @@ impl World {
         self.resources.read()
     }
 
+    /// The resource is marked changed when it's mutably dereferenced.
+    #[inline]
+    pub fn write_resource<T: Resource>(&self) -> Option<ResMut<'_, T>> {
+        Some(ResMut {
+            value: self.resources.write()?,
+            ticks: self.resources.ticks::<T>()?,
+            last_change_tick: self.last_change_tick(),
+            change_tick: self.change_tick(),
+        })
+    }
+
+    #[inline]
+    pub fn is_resource_added<T: Resource>(&self) -> bool {
+        self.resources
+            .ticks::<T>()
+            .is_some_and(|ticks| ticks.is_added(self.last_change_tick(), self.change_tick()))
+    }
+
     #[inline]
-    pub fn write_resource<T: Resource>(&self) -> Option<WriteGuard<T>> {
-        self.resources.write()
+    pub fn is_resource_changed<T: Resource>(&self) -> bool {
+        self.resources
+            .ticks::<T>()
+            .is_some_and(|ticks| ticks.is_changed(self.last_change_tick(), self.change_tick()))
     }
 
     #[inline]
@@ -111,11 +154,50 @@ impl World {
         self.entity_registry.next()
     }
 
+    /// Whether `entity` was created by this world and hasn't been despawned or taken since.
+    #[inline]
+    pub fn contains_entity(&self, entity: &Entity) -> bool {
+        self.entity_registry.contains(entity)
+    }
+
     #[inline]
     pub fn set_node_name(&mut self, entity: &Entity, name: impl Into<String>) {
-        self.nodes
-            .get_mut(entity)
-            .map(|node_name| *node_name = name.into());
+        if self.nodes.contains_key(entity) {
+            self.insert_node_name(*entity, name.into());
+        }
+    }
+
+    #[inline]
+    fn insert_node_name(&mut self, entity: Entity, name: String) {
+        if let Some(old_name) = self.nodes.insert(entity, name.clone()) {
+            if let Some(entities) = self.node_names.get_mut(&old_name) {
+                entities.retain(|e| *e != entity);
+
+                if entities.is_empty() {
+                    self.node_names.remove(&old_name);
+                }
+            }
+        } else {
+            self.entities.push(entity);
+        }
+
+        self.node_names.entry(name).or_default().push(entity);
+    }
+
+    fn remove_node_name(&mut self, entity: &Entity) -> Option<String> {
+        let name = self.nodes.remove(entity)?;
+
+        if let Some(entities) = self.node_names.get_mut(&name) {
+            entities.retain(|e| e != entity);
+
+            if entities.is_empty() {
+                self.node_names.remove(&name);
+            }
+        }
+
+        self.entities.retain(|e| e != entity);
+
+        Some(name)
     }
 
     #[inline]
@@ -151,6 +233,27 @@ impl World {
         })
     }
 
+    #[inline]
+    pub fn find_node(&self, name: &str) -> Option<Node<'_>> {
+        let entity = *self.node_names.get(name)?.first()?;
+
+        self.get_node(entity)
+    }
+
+    #[inline]
+    pub fn find_nodes<'a>(&'a self, name: &str) -> impl Iterator<Item = Node<'a>> + 'a {
+        self.node_names
+            .get(name)
+            .into_iter()
+            .flatten()
+            .filter_map(move |entity| self.get_node(*entity))
+    }
+
+    #[inline]
+    pub fn get_node_name(&self, entity: &Entity) -> Option<&str> {
+        self.nodes.get(entity).map(String::as_str)
+    }
+
     #[inline]
     pub fn query<Q: Query, F: QueryFilter>(&self) -> Option<QueryMut<'_, Q, F>> {
         QueryMut::new(self)
@@ -158,22 +261,349 @@ impl World {
 
     #[inline]
     pub fn has<T: AnyComponent>(&self) -> bool {
-        self.components.contains_key(&TypeId::of::<T>())
+        self.components.contains_key(&ComponentId::of::<T>())
     }
 
     #[inline]
     pub fn insert<T: AnyComponent>(&mut self, entity: Entity, component: T) {
         let change_tick = self.change_tick();
 
+        if let Some(index) = self.relations.get_mut(&ComponentId::of::<T>()) {
+            // SAFETY: relation indices are only registered for their relation component.
+            let target = unsafe { (index.target)(&component as *const T as *const u8) };
+
+            index.link(entity, target);
+        }
+
         let storage = self
             .components
-            .entry(TypeId::of::<T>())
+            .entry(ComponentId::of::<T>())
             .or_insert_with(ComponentStorage::new::<T>);
 
         // SAFETY: type in the storage matches T, since we got it with the TypeId.
         unsafe { storage.insert_unchecked(entity, component, change_tick) };
     }
 
+    #[inline]
+    pub fn remove<T: AnyComponent>(&mut self, entity: &Entity) -> Option<T> {
+        self.unlink_relation(ComponentId::of::<T>(), entity);
+
+        let storage = self.components.get_mut(&ComponentId::of::<T>())?;
+
+        // SAFETY: type in the storage matches T, since we got it with the ComponentId.
+        unsafe { storage.remove_unchecked(entity) }
+    }
+
+    /// Registers `T` to have its entity references remapped when moved between worlds.
+    #[inline]
+    pub fn register_entity_mapper<T: AnyComponent + MapEntities>(&mut self) {
+        self.entity_mappers
+            .insert(ComponentId::of::<T>(), entity_mapper::<T>());
+    }
+
+    #[inline]
+    pub fn register_relation<R: RelationKind>(&mut self) {
+        let id = relation_id::<R>();
+
+        if self.relations.contains_key(&id) {
+            return;
+        }
+
+        let mut index = RelationIndex::of::<R>();
+
+        if let Some(storage) = self.components.get(&id) {
+            // SAFETY: the storage of id holds Relation<R>.
+            unsafe { index.sync(storage, self.change_tick()) };
+        }
+
+        self.relations.insert(id, index);
+        self.register_entity_mapper::<Relation<R>>();
+    }
+
+    #[inline]
+    pub fn insert_relation<R: RelationKind>(&mut self, source: Entity, target: Entity) {
+        self.register_relation::<R>();
+        self.insert(source, Relation::<R>::new(target));
+    }
+
+    /// Returns every entity with a relation of kind `R` targeting `target`.
+    #[inline]
+    pub fn relation_sources<R: RelationKind>(&self, target: &Entity) -> &[Entity] {
+        match self.relations.get(&relation_id::<R>()) {
+            Some(index) => index.sources(target),
+            None => &[],
+        }
+    }
+
+    #[inline]
+    pub fn relation_target<R: RelationKind>(&self, source: &Entity) -> Option<Entity> {
+        let relation = self.get_component::<Relation<R>>(source)?;
+
+        Some(relation.target())
+    }
+
+    /// Reindexes registered relations replaced through `&mut` access, called by [`World::dequeue`].
+    #[inline]
+    pub fn sync_relations(&mut self) {
+        let change_tick = self.change_tick();
+
+        for (id, index) in &mut self.relations {
+            if let Some(storage) = self.components.get(id) {
+                // SAFETY: relation indices are only registered for their relation component.
+                unsafe { index.sync(storage, change_tick) };
+            }
+        }
+    }
+
+    fn unlink_relation(&mut self, id: ComponentId, entity: &Entity) {
+        if let Some(index) = self.relations.get_mut(&id) {
+            index.unlink(entity);
+        }
+    }
+
+    /// Removes `entity` with all its components and its name, so it can be inserted into another world.
+    ///
+    /// Registered relations targeting `entity` are removed, returns `None` if `entity` isn't
+    /// [alive](World::contains_entity).
+    #[inline]
+    pub fn take_entity(&mut self, entity: &Entity) -> Option<TakenEntity> {
+        if !self.contains_entity(entity) {
+            return None;
+        }
+
+        let sources: Vec<_> = self
+            .relations
+            .values()
+            .map(|index| (index.remove, index.sources(entity).to_vec()))
+            .collect();
+
+        for (remove, sources) in sources {
+            for source in sources {
+                remove(self, &source);
+            }
+        }
+
+        let mut ids: Vec<ComponentId> = self.components.keys().copied().collect();
+        ids.sort();
+
+        let mut components = Vec::new();
+
+        for id in ids {
+            self.unlink_relation(id, entity);
+
+            let storage = self.components.get_mut(&id).unwrap();
+
+            // SAFETY: the removed component is moved out of the storage right away.
+            let component = match unsafe { storage.take(entity) } {
+                Some(Ok(component)) => TakenComponent::Typed {
+                    id,
+                    component,
+                    mapper: self.entity_mappers.get(&id).copied(),
+                },
+                Some(Err(ptr)) => TakenComponent::Dynamic(unsafe {
+                    DynamicComponentData::new(storage.descriptor(), ptr)
+                }),
+                None => continue,
+            };
+
+            components.push(component);
+        }
+
+        let name = self.remove_node_name(entity);
+        self.entity_registry.free(*entity);
+
+        Some(TakenEntity {
+            entity: *entity,
+            name,
+            components,
+        })
+    }
+
+    /// Removes `entity` with all its components, see [`World::take_entity`], returns whether
+    /// it was alive.
+    #[inline]
+    pub fn despawn(&mut self, entity: &Entity) -> bool {
+        self.take_entity(entity).is_some()
+    }
+
+    /// Inserts `taken` as a new entity, see [`World::insert_taken_many`].
+    #[inline]
+    pub fn insert_taken(&mut self, taken: TakenEntity) -> Entity {
+        let entity = self.create_entity();
+
+        let mut map = EntityMap::new();
+        map.insert(taken.entity(), entity);
+
+        self.insert_taken_as(entity, taken, &map);
+
+        entity
+    }
+
+    /// Inserts taken entities as new entities, references between them are remapped
+    /// for components registered with [`World::register_entity_mapper`] in either world.
+    #[inline]
+    pub fn insert_taken_many(&mut self, taken: impl IntoIterator<Item = TakenEntity>) -> EntityMap {
+        let taken: Vec<TakenEntity> = taken.into_iter().collect();
+
+        let mut map = EntityMap::new();
+
+        let entities: Vec<Entity> = taken
+            .iter()
+            .map(|taken| {
+                let entity = self.create_entity();
+                map.insert(taken.entity(), entity);
+                entity
+            })
+            .collect();
+
+        for (entity, taken) in entities.into_iter().zip(taken) {
+            self.insert_taken_as(entity, taken, &map);
+        }
+
+        map
+    }
+
+    /// Inserts the components of `taken` on `entity`, remapping entity references with `map`,
+    /// components referring to entities missing from `map` are dropped.
+    #[inline]
+    pub fn insert_taken_as(&mut self, entity: Entity, taken: TakenEntity, map: &EntityMap) {
+        for component in taken.components {
+            match component {
+                TakenComponent::Typed {
+                    id,
+                    mut component,
+                    mapper,
+                } => {
+                    if let Some(mapper) = self.entity_mappers.get(&id).copied().or(mapper) {
+                        // SAFETY: mappers are registered for the type of the component.
+                        if !unsafe { mapper(component.as_mut_ptr(), map) } {
+                            continue;
+                        }
+                    }
+
+                    component.insert(entity, self);
+                }
+                TakenComponent::Dynamic(data) => {
+                    let id = self.dynamic_component_for(&data.descriptor);
+
+                    self.insert_bytes(entity, id, data.bytes());
+                    data.forget();
+                }
+            }
+        }
+
+        if let Some(name) = taken.name {
+            self.insert_node_name(entity, name);
+        }
+    }
+
+    // finds a dynamic component with the same name and layout, or registers a new one
+    fn dynamic_component_for(&mut self, descriptor: &ComponentDescriptor) -> ComponentId {
+        let existing = self.components.iter().find(|(id, storage)| {
+            let info = storage.info();
+
+            id.is_dynamic() && info.name == descriptor.name() && info.layout == descriptor.layout()
+        });
+
+        match existing {
+            Some((id, _)) => *id,
+            None => self.register_dynamic_component(descriptor.clone()),
+        }
+    }
+
+    #[inline]
+    pub fn register_dynamic_component(&mut self, descriptor: ComponentDescriptor) -> ComponentId {
+        let id = ComponentId::Dynamic(self.next_dynamic_component);
+        self.next_dynamic_component += 1;
+
+        self.components
+            .insert(id, ComponentStorage::from_descriptor(id, descriptor));
+
+        id
+    }
+
+    #[inline]
+    pub fn component_info(&self, id: ComponentId) -> Option<ComponentInfo<'_>> {
+        self.components.get(&id).map(ComponentStorage::info)
+    }
+
+    // whether `id` is a registered dynamic component `len` bytes long
+    #[inline]
+    fn accepts_bytes(&self, id: ComponentId, len: usize) -> bool {
+        if !id.is_dynamic() {
+            return false;
+        }
+
+        self.components
+            .get(&id)
+            .is_some_and(|storage| storage.info().size() == len)
+    }
+
+    /// Inserts the bytes of a dynamic component, returns false without inserting if `id` isn't
+    /// a registered dynamic component or `bytes` don't match its size.
+    #[inline]
+    pub fn insert_bytes(&mut self, entity: Entity, id: ComponentId, bytes: &[u8]) -> bool {
+        if !self.accepts_bytes(id, bytes.len()) {
+            return false;
+        }
+
+        let change_tick = self.change_tick();
+
+        let storage = self.components.get_mut(&id).unwrap();
+
+        // SAFETY: dynamic components are plain bytes and storage copies exactly `bytes.len()` bytes.
+        unsafe { storage.insert_raw(entity, bytes.as_ptr() as *mut u8, change_tick) };
+
+        true
+    }
+
+    /// Queues [`World::insert_bytes`], the bytes are checked now and false is returned without
+    /// queueing them if they'd be refused.
+    #[inline]
+    pub fn queue_insert_bytes(
+        &self,
+        entity: Entity,
+        id: ComponentId,
+        bytes: impl Into<Vec<u8>>,
+    ) -> bool {
+        let bytes = bytes.into();
+
+        if !self.accepts_bytes(id, bytes.len()) {
+            return false;
+        }
+
+        self.commands.push(Command::InsertBytes(entity, id, bytes));
+
+        true
+    }
+
+    #[inline]
+    pub fn get_bytes(&self, entity: &Entity, id: ComponentId) -> Option<ReadGuard<'_, [u8]>> {
+        if !id.is_dynamic() {
+            return None;
+        }
+
+        let storage = self.components.get(&id)?;
+
+        unsafe { storage.get_borrowed_bytes(entity) }
+    }
+
+    #[inline]
+    pub fn get_bytes_mut(&self, entity: &Entity, id: ComponentId) -> Option<WriteGuard<'_, [u8]>> {
+        if !id.is_dynamic() {
+            return None;
+        }
+
+        let storage = self.components.get(&id)?;
+
+        unsafe { storage.get_borrowed_bytes_mut(entity, self.change_tick()) }
+    }
+
+    #[inline]
+    pub fn query_dynamic(&self, access: &[(ComponentId, Access)]) -> Option<DynamicQuery<'_>> {
+        DynamicQuery::new(self, access)
+    }
+
     #[inline]
     pub fn queue_insert<T: AnyComponent>(&self, entity: Entity, component: T) {
         self.commands
@@ -187,7 +617,7 @@ impl World {
 
     #[inline]
     pub fn contains_component<T: AnyComponent>(&self, entity: &Entity) -> bool {
-        if let Some(storage) = self.components.get(&TypeId::of::<T>()) {
+        if let Some(storage) = self.components.get(&ComponentId::of::<T>()) {
             storage.contains(entity)
         } else {
             false
@@ -196,14 +626,14 @@ impl World {
 
     #[inline]
     pub fn get_component<T: AnyComponent>(&self, entity: &Entity) -> Option<ReadGuard<T>> {
-        let storage = self.components.get(&TypeId::of::<T>())?;
+        let storage = self.components.get(&ComponentId::of::<T>())?;
 
         unsafe { storage.get_borrowed(entity) }
     }
 
     #[inline]
     pub fn get_component_mut<T: AnyComponent>(&self, entity: &Entity) -> Option<WriteGuard<T>> {
-        let storage = self.components.get(&TypeId::of::<T>())?;
+        let storage = self.components.get(&ComponentId::of::<T>())?;
 
         unsafe { storage.get_borrowed_mut(entity, self.change_tick()) }
     }
@@ -214,17 +644,42 @@ impl World {
             if storage.borrow_mut() {
                 storage.release_mut();
 
-                println!("{:?} free", storage.ty());
+                println!("{} free", storage.info().name);
             } else if storage.borrow() {
                 storage.release();
 
-                println!("{:?} shared", storage.ty());
+                println!("{} shared", storage.info().name);
             } else {
-                println!("{:?} unique", storage.ty());
+                println!("{} unique", storage.info().name);
             }
         }
     }
 
+    #[inline]
+    pub fn entity_components(&self, entity: &Entity) -> impl Iterator<Item = ComponentInfo<'_>> {
+        let entity = *entity;
+
+        self.components
+            .values()
+            .filter(move |storage| storage.contains(&entity))
+            .map(ComponentStorage::info)
+    }
+
+    #[inline]
+    pub fn storage(&self, id: ComponentId) -> Option<StorageInfo<'_>> {
+        self.components.get(&id).map(StorageInfo::new)
+    }
+
+    #[inline]
+    pub fn storages(&self) -> impl Iterator<Item = StorageInfo<'_>> {
+        self.components.values().map(StorageInfo::new)
+    }
+
+    #[inline]
+    pub fn resources_info(&self) -> impl Iterator<Item = TypeInfo> + '_ {
+        self.resources.info()
+    }
+
     #[inline]
     pub fn nodes(&self) -> impl Iterator<Item = Node> + '_ {
         self.nodes.iter().map(|(entity, name)| Node {
@@ -240,23 +695,27 @@ impl World {
         while let Some(command) = self.commands.pop() {
             match command {
                 Command::Insert(entity, component) => component.insert(entity, self),
-                Command::InsertNode(entity, name) => {
-                    self.nodes.insert(entity, name);
-                    self.entities.push(entity);
+                Command::InsertBytes(entity, id, bytes) => {
+                    self.insert_bytes(entity, id, &bytes);
                 }
-                Command::InsertResource(type_id, resource) => unsafe {
-                    self.resources.insert_raw(type_id, resource);
+                Command::InsertNode(entity, name) => self.insert_node_name(entity, name),
+                Command::InsertResource(info, resource) => unsafe {
+                    let change_tick = self.change_tick();
+                    self.resources.insert_raw(info, resource, change_tick);
                 },
                 Command::RemoveResource(type_id) => {
                     self.resources.remove_raw(type_id);
                 }
-                Command::InitResource(type_id, resource) => {
-                    if !self.resources.contains_raw(type_id) {
-                        unsafe { self.resources.insert_raw(type_id, resource) };
+                Command::InitResource(info, resource) => {
+                    if !self.resources.contains_raw(info.id) {
+                        let change_tick = self.change_tick();
+                        unsafe { self.resources.insert_raw(info, resource, change_tick) };
                     }
                 }
             }
         }
+
+        self.sync_relations();
     }
 
     #[inline]
@@ -281,7 +740,7 @@ impl World {
 
     #[inline]
     pub(crate) fn borrow<T: AnyComponent>(&self) -> bool {
-        if let Some(storage) = self.components.get(&TypeId::of::<T>()) {
+        if let Some(storage) = self.components.get(&ComponentId::of::<T>()) {
             storage.borrow()
         } else {
             true
@@ -290,7 +749,7 @@ impl World {
 
     #[inline]
     pub(crate) fn borrow_mut<T: AnyComponent>(&self) -> bool {
-        if let Some(storage) = self.components.get(&TypeId::of::<T>()) {
+        if let Some(storage) = self.components.get(&ComponentId::of::<T>()) {
             storage.borrow_mut()
         } else {
             true
@@ -299,14 +758,14 @@ impl World {
 
     #[inline]
     pub(crate) fn release<T: AnyComponent>(&self) {
-        if let Some(storage) = self.components.get(&TypeId::of::<T>()) {
+        if let Some(storage) = self.components.get(&ComponentId::of::<T>()) {
             storage.release();
         }
     }
 
     #[inline]
     pub(crate) fn release_mut<T: AnyComponent>(&self) {
-        if let Some(storage) = self.components.get(&TypeId::of::<T>()) {
+        if let Some(storage) = self.components.get(&ComponentId::of::<T>()) {
             storage.release_mut();
         }
     }
@@ -315,6 +774,7 @@ impl World {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::Name;
 
     #[test]
     fn entities() {
@@ -365,4 +825,139 @@ mod tests {
 
         assert!(world.query::<&bool, ()>().is_none());
     }
+
+    #[test]
+    fn find_node() {
+        let mut world = World::new();
+
+        let a = world.spawn_node("foo").entity();
+        let b = world.spawn_node("bar").entity();
+        let c = world.spawn_node("foo").entity();
+
+        world.dequeue();
+
+        assert_eq!(world.find_node("foo").unwrap().entity(), a);
+        assert_eq!(world.find_node("bar").unwrap().entity(), b);
+        assert!(world.find_node("baz").is_none());
+
+        let foos: Vec<_> = world.find_nodes("foo").map(|node| node.entity()).collect();
+        assert_eq!(foos, vec![a, c]);
+
+        world.set_node_name(&a, "baz");
+
+        assert_eq!(world.find_node("foo").unwrap().entity(), c);
+        assert_eq!(world.find_node("baz").unwrap().entity(), a);
+
+        let mut names: Vec<_> = world.query::<Name, ()>().unwrap().collect();
+        names.sort();
+        assert_eq!(names, vec!["bar", "baz", "foo"]);
+    }
+
+    #[test]
+    fn info() {
+        let mut world = World::new();
+
+        let a = world.create_entity();
+        let b = world.create_entity();
+
+        world.insert(a, 1u32);
+        world.insert(a, 2u64);
+        world.insert(b, 3u64);
+        world.insert_resource(false);
+
+        let mut components: Vec<_> = world.entity_components(&a).map(|info| info.name).collect();
+        components.sort();
+        assert_eq!(components, vec!["u32", "u64"]);
+
+        let storage = world.storage(ComponentId::of::<u64>()).unwrap();
+        assert_eq!(storage.ty.name, "u64");
+        assert_eq!(storage.ty.layout, TypeInfo::of::<u64>().layout);
+        assert_eq!(storage.len, 2);
+        assert_eq!(storage.bytes(), 16);
+        assert_eq!(storage.entities, &[a, b]);
+
+        assert_eq!(world.storages().count(), 2);
+
+        let resources: Vec<_> = world.resources_info().collect();
+        assert_eq!(resources, vec![TypeInfo::of::<bool>()]);
+    }
+
+    #[test]
+    fn invalid_bytes() {
+        let mut world = World::new();
+
+        let health = world.register_dynamic_component(ComponentDescriptor::new(
+            "health",
+            std::alloc::Layout::new::<[u8; 4]>(),
+        ));
+
+        // so u32 has a typed storage
+        world.insert(world.create_entity(), 0u32);
+
+        let a = world.create_entity();
+
+        // bytes not matching the size of the component are refused, when they're queued too
+        assert!(!world.insert_bytes(a, health, &[1, 2, 3]));
+        assert!(!world.queue_insert_bytes(a, health, [1, 2, 3, 4, 5]));
+        world.dequeue();
+
+        assert!(world.get_bytes(&a, health).is_none());
+
+        // as are components that aren't dynamic or registered
+        assert!(!world.insert_bytes(a, ComponentId::of::<u32>(), &[1, 2, 3, 4]));
+        assert!(!world.queue_insert_bytes(a, ComponentId::Dynamic(100), [1, 2, 3, 4]));
+        world.dequeue();
+
+        assert!(!world.contains_component::<u32>(&a));
+
+        assert!(world.queue_insert_bytes(a, health, [1, 2, 3, 4]));
+        world.dequeue();
+
+        assert_eq!(&*world.get_bytes(&a, health).unwrap(), &[1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn dynamic_components() {
+        let mut world = World::new();
+
+        let health = world.register_dynamic_component(ComponentDescriptor::new(
+            "health",
+            std::alloc::Layout::new::<[u8; 4]>(),
+        ));
+        let tag = world
+            .register_dynamic_component(ComponentDescriptor::new("tag", std::alloc::Layout::new::<()>()));
+
+        let a = world.create_entity();
+        let b = world.create_entity();
+
+        world.insert_bytes(a, health, &[1, 2, 3, 4]);
+        world.insert_bytes(b, health, &[5, 6, 7, 8]);
+        world.queue_insert_bytes(b, tag, []);
+        world.dequeue();
+
+        assert_eq!(world.component_info(health).unwrap().name, "health");
+        assert_eq!(&*world.get_bytes(&a, health).unwrap(), &[1, 2, 3, 4]);
+        assert!(world.get_bytes(&a, tag).is_none());
+
+        world.get_bytes_mut(&a, health).unwrap()[0] = 9;
+        assert_eq!(&*world.get_bytes(&a, health).unwrap(), &[9, 2, 3, 4]);
+
+        let query = world
+            .query_dynamic(&[(health, Access::Write), (tag, Access::Read)])
+            .unwrap();
+
+        let items: Vec<_> = query
+            .map(|(entity, mut components)| {
+                components[0].bytes_mut().unwrap()[0] = 0;
+                entity
+            })
+            .collect();
+
+        assert_eq!(items, vec![b]);
+        assert_eq!(&*world.get_bytes(&b, health).unwrap(), &[0, 6, 7, 8]);
+
+        assert!(world
+            .query_dynamic(&[(health, Access::Write), (health, Access::Read)])
+            .is_none());
+    }
 }
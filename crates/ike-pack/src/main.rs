@@ -0,0 +1,127 @@
+use std::path::Path;
+
+use ike_assets::{ArchiveBuilder, ArchiveIo, Compression};
+
+const USAGE: &str = "usage:
+    ike-pack pack <dir> <archive> [--store]   packs every file under <dir>
+    ike-pack list <archive>                   lists the entries of <archive>
+    ike-pack verify <archive>                 checks the hash of every entry";
+
+fn pack(dir: &Path, archive: &Path, compression: Compression) -> anyhow::Result<()> {
+    let mut builder = ArchiveBuilder::new();
+    builder.set_compression(compression);
+    builder.add_dir(dir)?;
+    builder.save(archive)?;
+
+    println!(
+        "packed {} files into '{}'",
+        builder.len(),
+        archive.display()
+    );
+
+    Ok(())
+}
+
+fn list(archive: &Path) -> anyhow::Result<()> {
+    let archive = ArchiveIo::open(archive)?;
+
+    let mut entries: Vec<_> = archive.entries().collect();
+    entries.sort_by_key(|(path, _)| *path);
+
+    let mut size = 0;
+    let mut uncompressed_size = 0;
+
+    for (path, entry) in entries {
+        println!(
+            "{:08x} {:>12} {:>12} {:?} {}",
+            entry.hash(),
+            entry.size(),
+            entry.uncompressed_size(),
+            entry.compression(),
+            path.display()
+        );
+
+        size += entry.size();
+        uncompressed_size += entry.uncompressed_size();
+    }
+
+    println!(
+        "{} entries, {} bytes, {} uncompressed",
+        archive.len(),
+        size,
+        uncompressed_size
+    );
+
+    Ok(())
+}
+
+fn verify(archive: &Path) -> anyhow::Result<()> {
+    let archive = ArchiveIo::open(archive)?;
+    let failed = archive.verify();
+
+    for (path, error) in &failed {
+        println!("{}: {}", path.display(), error);
+    }
+
+    if failed.is_empty() {
+        println!("{} entries ok", archive.len());
+        Ok(())
+    } else {
+        anyhow::bail!("{} of {} entries failed", failed.len(), archive.len())
+    }
+}
+
+fn main() -> anyhow::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    match args.as_slice() {
+        ["pack", dir, archive] => pack(dir.as_ref(), archive.as_ref(), Compression::Deflate),
+        ["pack", dir, archive, "--store"] => {
+            pack(dir.as_ref(), archive.as_ref(), Compression::None)
+        }
+        ["list", archive] => list(archive.as_ref()),
+        ["verify", archive] => verify(archive.as_ref()),
+        _ => {
+            eprintln!("{}", USAGE);
+            std::process::exit(2);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use ike_assets::AssetIo;
+
+    use super::*;
+
+    #[test]
+    fn pack_and_verify() {
+        let dir = std::env::temp_dir().join("ike_pack_test");
+        let _ = fs::remove_dir_all(&dir);
+
+        let assets = dir.join("assets");
+        fs::create_dir_all(assets.join("textures")).unwrap();
+        fs::write(assets.join("a.txt"), "a".repeat(64)).unwrap();
+        fs::write(assets.join("textures").join("b.txt"), "b").unwrap();
+
+        let archive = dir.join("assets.pack");
+        pack(&assets, &archive, Compression::Deflate).unwrap();
+        list(&archive).unwrap();
+        verify(&archive).unwrap();
+
+        let io = ArchiveIo::open(&archive).unwrap();
+        assert_eq!(io.len(), 2);
+        assert_eq!(io.read(Path::new("textures/b.txt")).unwrap(), b"b");
+        drop(io);
+
+        let mut bytes = fs::read(&archive).unwrap();
+        *bytes.last_mut().unwrap() ^= 1;
+        fs::write(&archive, bytes).unwrap();
+        assert!(verify(&archive).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -2,7 +2,7 @@ use std::{collections::HashMap, num::NonZeroU32};
 
 use bytemuck::{bytes_of, cast_slice, Zeroable};
 use glam::{Mat4, Quat, Vec3};
-use ike_assets::{Assets, Handle};
+use ike_assets::{AssetEvent, AssetEventReader, Assets, Handle, HandleUntyped};
 use ike_core::World;
 use ike_render::*;
 use ike_transform::{GlobalTransform, Transform};
@@ -474,22 +474,51 @@ struct MeshRaw {
     joint_count: u32,
 }
 
+const NORMAL_MAP_FLAG_BIT: u32 = 1;
+
+// the vertex buffers bound by the pbr pipeline, in order
+const MESH_ATTRIBUTES: [&str; 5] = [
+    Mesh::POSITION,
+    Mesh::NORMAL,
+    Mesh::UV,
+    Mesh::TANGENT,
+    Mesh::COLOR,
+];
+
 struct MaterialResources {
-    mesh_buffer: wgpu::Buffer,
     group_1: wgpu::BindGroup,
     group_2: wgpu::BindGroup,
     group_3: wgpu::BindGroup,
+    // the textures of the material, loaded or not, the bind groups are rebuilt when they change
+    textures: Vec<HandleUntyped>,
 }
 
 impl MaterialResources {
     #[inline]
     fn new(
         material: &PbrMaterial,
+        textures: &Assets<Texture>,
         shadows: &wgpu::TextureView,
         resources: &ShaderResources,
     ) -> Self {
         let device = render_device();
 
+        // textures still loading are bound as the default texture
+        let view = |texture: &Option<Handle<Texture>>| {
+            let texture = textures.get(texture.as_ref()?)?;
+            Some(texture.texture().create_view(&Default::default()))
+        };
+
+        let albedo_texture = view(&material.albedo_texture);
+        let metallic_roughness_texture = view(&material.metallic_roughness_texture);
+        let normal_map = view(&material.normal_map);
+
+        let mut flags = 0;
+
+        if normal_map.is_some() {
+            flags |= NORMAL_MAP_FLAG_BIT;
+        }
+
         let mesh = MeshRaw {
             material: MaterialRaw {
                 albedo: material.albedo.into(),
@@ -502,7 +531,7 @@ impl MaterialResources {
                 shadow_block_samples: material.shadow_blocker_samples,
                 shadow_pcf_samples: material.shadow_pcf_samples,
             },
-            flags: 0,
+            flags,
             joint_count: 0,
         };
 
@@ -522,15 +551,23 @@ impl MaterialResources {
                 },
                 wgpu::BindGroupEntry {
                     binding: 1,
-                    resource: wgpu::BindingResource::TextureView(&resources.default_tex),
+                    resource: wgpu::BindingResource::TextureView(
+                        albedo_texture.as_ref().unwrap_or(&resources.default_tex),
+                    ),
                 },
                 wgpu::BindGroupEntry {
                     binding: 2,
-                    resource: wgpu::BindingResource::TextureView(&resources.default_tex),
+                    resource: wgpu::BindingResource::TextureView(
+                        metallic_roughness_texture
+                            .as_ref()
+                            .unwrap_or(&resources.default_tex),
+                    ),
                 },
                 wgpu::BindGroupEntry {
                     binding: 3,
-                    resource: wgpu::BindingResource::TextureView(&resources.default_tex),
+                    resource: wgpu::BindingResource::TextureView(
+                        normal_map.as_ref().unwrap_or(&resources.default_tex),
+                    ),
                 },
                 wgpu::BindGroupEntry {
                     binding: 4,
@@ -560,34 +597,22 @@ impl MaterialResources {
             ],
         });
 
+        let textures = [
+            &material.albedo_texture,
+            &material.metallic_roughness_texture,
+            &material.normal_map,
+        ]
+        .iter()
+        .filter_map(|texture| Some(texture.as_ref()?.untyped().clone()))
+        .collect();
+
         Self {
-            mesh_buffer,
             group_1,
             group_2,
             group_3,
+            textures,
         }
     }
-
-    #[inline]
-    pub fn update(&mut self, material: &PbrMaterial) {
-        let mesh = MeshRaw {
-            material: MaterialRaw {
-                albedo: material.albedo.into(),
-                emission: material.emission.into(),
-                roughness: material.roughness,
-                metallic: material.metallic,
-                reflectance: material.reflectance,
-                shadow_softness: material.shadow_softness,
-                shadow_softness_falloff: material.shadow_softness_falloff,
-                shadow_block_samples: material.shadow_blocker_samples,
-                shadow_pcf_samples: material.shadow_pcf_samples,
-            },
-            flags: 0,
-            joint_count: 0,
-        };
-
-        render_queue().write_buffer(&self.mesh_buffer, 0, bytes_of(&mesh));
-    }
 }
 
 #[derive(Default)]
@@ -596,7 +621,11 @@ pub struct PbrNode {
     uniforms: Option<wgpu::Buffer>,
     uniforms_group: Option<wgpu::BindGroup>,
     current_env: Option<Handle<Environment>>,
-    materials: HashMap<Handle<PbrMaterial>, MaterialResources>,
+    env_events: AssetEventReader<Environment>,
+    // keyed by the untyped handle so the cache doesn't keep materials alive
+    materials: HashMap<HandleUntyped, MaterialResources>,
+    material_events: AssetEventReader<PbrMaterial>,
+    texture_events: AssetEventReader<Texture>,
     instances: HashMap<InstanceId, Buffer>,
     view_buffers: HashMap<usize, (wgpu::Buffer, wgpu::BindGroup)>,
 }
@@ -634,6 +663,8 @@ impl RenderNode for PbrNode {
 
         let view = target.texture().create_view(&Default::default());
 
+        // the handles are weak and hashed by their id, which never changes
+        #[allow(clippy::mutable_key_type)]
         let mut instances: HashMap<_, Vec<[[f32; 4]; 4]>> = HashMap::new();
 
         for (transform, material, mesh) in world
@@ -641,8 +672,8 @@ impl RenderNode for PbrNode {
             .unwrap()
         {
             let id = InstanceId {
-                material: material.clone(),
-                mesh: mesh.clone(),
+                material: material.clone_weak(),
+                mesh: mesh.clone_weak(),
             };
 
             instances
@@ -691,23 +722,67 @@ impl RenderNode for PbrNode {
         let mut meshes = world.write_resource::<Assets<Mesh>>().unwrap();
         let materials = world.read_resource::<Assets<PbrMaterial>>().unwrap();
 
-        for (id, material) in &mut self.materials {
-            material.update(materials.get(id).unwrap());
+        let textures = world.read_resource::<Assets<Texture>>().unwrap();
+
+        // modified materials may refer to other textures, so they're created again when drawn
+        for event in materials.read_events(&mut self.material_events) {
+            match event {
+                AssetEvent::Created(_) => {}
+                AssetEvent::Modified(handle) | AssetEvent::Removed(handle) => {
+                    self.materials.remove(handle.untyped());
+                }
+            }
         }
 
-        for (id, instances) in &instances {
-            if !self.materials.contains_key(&id.material) {
-                let material = materials.get(&id.material).unwrap();
+        // as are the materials whose textures finished loading, changed or were removed
+        for event in textures.read_events(&mut self.texture_events) {
+            let texture = event.handle().untyped();
 
+            self.materials
+                .retain(|_, material| !material.textures.contains(texture));
+        }
+
+        // materials and meshes still loading or evicted aren't drawn, nor are meshes missing
+        // an attribute the shader reads
+        instances.retain(|id, instances| {
+            let material = match materials.get(&id.material) {
+                Some(material) => material,
+                None => return false,
+            };
+
+            let mesh = match meshes.get_mut_untracked(&id.mesh) {
+                Some(mesh) => mesh,
+                None => return false,
+            };
+
+            for name in MESH_ATTRIBUTES {
+                match mesh.buffer(name) {
+                    Some(buffer) => {
+                        buffer.raw();
+                    }
+                    None => return false,
+                }
+            }
+
+            mesh.index_buffer().raw();
+
+            if let Some(material) = self.materials.get(id.material.untyped()) {
+                // cached bind groups use the textures without getting them, so they're
+                // marked as used here to keep them from being evicted
+                for texture in &material.textures {
+                    textures.get(&Handle::<Texture>::weak(texture.clone()));
+                }
+            } else {
                 let view = self
                     .shadows
                     .as_ref()
                     .unwrap()
                     .create_view(&Default::default());
 
-                let material = MaterialResources::new(material, &view, &resources);
+                let material = MaterialResources::new(material, &textures, &view, &resources);
 
-                self.materials.insert(id.material.clone(), material);
+                self.materials
+                    .insert(id.material.untyped().clone(), material);
             }
 
             let instance_buffer = self
@@ -718,16 +793,11 @@ impl RenderNode for PbrNode {
 
             instance_buffer.raw();
 
-            let mesh = meshes.get_mut(&id.mesh).unwrap();
-
-            mesh.index_buffer().raw();
+            true
+        });
 
-            mesh.buffer(Mesh::POSITION).unwrap().raw();
-            mesh.buffer(Mesh::NORMAL).unwrap().raw();
-            mesh.buffer(Mesh::UV).unwrap().raw();
-            mesh.buffer(Mesh::TANGENT).unwrap().raw();
-            mesh.buffer(Mesh::COLOR).unwrap().raw();
-        }
+        // instance buffers hold handles, so only keep the ones drawn this frame
+        self.instances.retain(|id, _| instances.contains_key(id));
 
         let camera = input.get::<Camera>(Self::CAMERA).unwrap();
 
@@ -811,11 +881,15 @@ impl RenderNode for PbrNode {
             render_pass.set_bind_group(0, bind_group, &[]);
 
             for (id, instances) in &instances {
-                let mesh_group = &self.materials[&id.material];
+                let (mesh, material) = match (
+                    meshes.get(&id.mesh),
+                    self.materials.get(id.material.untyped()),
+                ) {
+                    (Some(mesh), Some(material)) => (mesh, material),
+                    _ => continue,
+                };
 
-                render_pass.set_bind_group(1, &mesh_group.group_1, &[]);
-
-                let mesh = meshes.get(&id.mesh).unwrap();
+                render_pass.set_bind_group(1, &material.group_1, &[]);
 
                 render_pass
                     .set_vertex_buffer(0, mesh.get_raw_buffer(Mesh::POSITION).unwrap().slice(..));
@@ -856,12 +930,25 @@ impl RenderNode for PbrNode {
         }
 
         let env = world.read_resource::<Handle<Environment>>();
+        let envs = world.read_resource::<Assets<Environment>>().unwrap();
+
+        // reloaded environments are bound again
+        for event in envs.read_events(&mut self.env_events) {
+            if self.current_env.as_ref() == Some(event.handle()) {
+                self.uniforms_group = None;
+            }
+        }
+
+        // environments still loading are bound as the default cube
+        let env = env
+            .as_deref()
+            .and_then(|handle| Some((handle, envs.get(handle)?)));
+        let env_handle = env.map(|(handle, _)| handle);
 
-        if self.uniforms_group.is_none() || env.as_deref() != self.current_env.as_ref() {
-            let group = if let Some(env) = env {
-                let envs = world.read_resource::<Assets<Environment>>().unwrap();
-                let env = envs.get(&env).unwrap();
+        if self.uniforms_group.is_none() || env_handle != self.current_env.as_ref() {
+            self.current_env = env_handle.cloned();
 
+            let group = if let Some((_, env)) = env {
                 render_device().create_bind_group(&wgpu::BindGroupDescriptor {
                     label: None,
                     layout: &resources.group_0,
@@ -939,8 +1026,13 @@ impl RenderNode for PbrNode {
         render_pass.set_pipeline(&resources.pipelines[&target.target()]);
 
         for (id, instances) in instances {
-            let mesh = meshes.get(&id.mesh).unwrap();
-            let material = self.materials.get(&id.material).unwrap();
+            let (mesh, material) = match (
+                meshes.get(&id.mesh),
+                self.materials.get(id.material.untyped()),
+            ) {
+                (Some(mesh), Some(material)) => (mesh, material),
+                _ => continue,
+            };
 
             render_pass.set_bind_group(0, self.uniforms_group.as_ref().unwrap(), &[]);
             render_pass.set_bind_group(1, &material.group_1, &[]);
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use bytemuck::bytes_of;
 use glam::Mat4;
-use ike_assets::{Assets, Handle};
+use ike_assets::{AssetEventReader, Assets, Handle};
 use ike_render::*;
 
 #[repr(C)]
@@ -15,6 +15,7 @@ struct Uniforms {
 #[derive(Default)]
 pub struct SkyNode {
     current_env: Option<Handle<Environment>>,
+    env_events: AssetEventReader<Environment>,
     uniform_buffer: Option<wgpu::Buffer>,
     bind_group_layout: Option<wgpu::BindGroupLayout>,
     bind_group: Option<wgpu::BindGroup>,
@@ -204,14 +205,24 @@ impl RenderNode for SkyNode {
             depth_stencil_attachment: None,
         });
 
-        if let Some(env) = world.read_resource::<Handle<Environment>>() {
-            if self.current_env.as_ref() != Some(&env) {
-                self.current_env = Some(env.clone());
+        if let Some(handle) = world.read_resource::<Handle<Environment>>() {
+            let envs = world.read_resource::<Assets<Environment>>().unwrap();
 
-                let envs = world.read_resource::<Assets<Environment>>().unwrap();
+            // reloaded environments are bound again
+            for event in envs.read_events(&mut self.env_events) {
+                if self.current_env.as_ref() == Some(event.handle()) {
+                    self.current_env = None;
+                }
+            }
 
-                let env = envs.get(&env).unwrap();
+            // the sky isn't drawn while the environment is loading
+            let env = match envs.get(&handle) {
+                Some(env) => env,
+                None => return Ok(()),
+            };
 
+            if self.current_env.as_ref() != Some(&handle) {
+                self.current_env = Some(handle.clone());
                 self.set_texture(&env.env_texture);
             }
 
@@ -39,5 +39,11 @@ impl Plugin for PhysicsPlugin {
         app.add_system_to_stage(physics_update.system(), physics_stage::PHYSICS);
 
         app.add_system_to_stage(get_rigid_bodies.system(), physics_stage::POST_PHYSICS);
+        app.add_system_to_stage(
+            physics_diagnostics
+                .system()
+                .run_if(resource_exists::<Diagnostics>()),
+            physics_stage::POST_PHYSICS,
+        );
     }
 }
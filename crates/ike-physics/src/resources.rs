@@ -14,6 +14,8 @@ pub struct PhysicsResource {
     pub broad_phase: BroadPhase,
     pub narrow_phase: NarrowPhase,
     pub ccd_solver: CCDSolver,
+    /// Seconds spent in the last step.
+    pub step_time: f32,
 }
 
 impl Default for PhysicsResource {
@@ -26,6 +28,7 @@ impl Default for PhysicsResource {
             broad_phase: BroadPhase::new(),
             narrow_phase: NarrowPhase::new(),
             ccd_solver: CCDSolver::new(),
+            step_time: 0.0,
         }
     }
 }
@@ -1,3 +1,5 @@
+use std::time::Instant;
+
 use glam::{Quat, Vec3};
 use ike_core::*;
 use ike_transform::{GlobalTransform, Transform};
@@ -122,6 +124,8 @@ pub fn physics_update(
 
     physics_resource.integration_parameters.dt = time.delta_time();
 
+    let start = Instant::now();
+
     physics_resource.pipeline.step(
         &to_vec3(gravity.0),
         &physics_resource.integration_parameters,
@@ -135,6 +139,18 @@ pub fn physics_update(
         &(),
         &(),
     );
+
+    physics_resource.step_time = start.elapsed().as_secs_f32();
+}
+
+pub fn physics_diagnostics(
+    physics_resource: Res<PhysicsResource>,
+    mut diagnostics: ResMut<Diagnostics>,
+) {
+    diagnostics.measure(
+        diagnostic::PHYSICS_STEP_TIME,
+        physics_resource.step_time as f64 * 1000.0,
+    );
 }
 
 pub fn get_rigid_bodies(
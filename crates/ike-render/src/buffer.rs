@@ -1,10 +1,10 @@
-use crate::{render_device, render_queue};
+use crate::{render_device, render_queue, Tracked};
 
 use ike_wgpu as wgpu;
 
 pub struct Buffer {
     len: u64,
-    raw_buffer: Option<wgpu::Buffer>,
+    raw_buffer: Option<Tracked<wgpu::Buffer>>,
     usage: wgpu::BufferUsages,
 }
 
@@ -25,7 +25,7 @@ impl Clone for Buffer {
 
             render_queue().submit_once(encoder.finish());
 
-            buffer
+            Tracked::new(buffer, self.len)
         });
 
         Self {
@@ -57,7 +57,7 @@ impl Buffer {
                 usage: self.usage,
             });
 
-            self.raw_buffer = Some(buffer);
+            self.raw_buffer = Some(Tracked::new(buffer, data.len() as u64));
             self.len = data.len() as u64;
         } else {
             let raw_buffer = self.raw_buffer.as_ref().unwrap();
@@ -72,11 +72,17 @@ impl Buffer {
             self.write(&[]);
         }
 
-        self.raw_buffer.as_ref().unwrap()
+        self.raw_buffer.as_deref().unwrap()
+    }
+
+    /// Bytes of gpu memory used by the buffer, 0 until it's written.
+    #[inline]
+    pub fn gpu_size(&self) -> u64 {
+        self.raw_buffer.as_ref().map_or(0, Tracked::size)
     }
 
     #[inline]
     pub fn get_raw(&self) -> Option<&wgpu::Buffer> {
-        self.raw_buffer.as_ref()
+        self.raw_buffer.as_deref()
     }
 }
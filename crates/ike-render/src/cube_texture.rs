@@ -1,7 +1,8 @@
 use bytemuck::bytes_of;
+use ike_assets::{AssetLoader, AssetSize, LoadContext};
 use once_cell::sync::OnceCell;
 
-use crate::{render_device, render_queue, HdrTexture};
+use crate::{render_device, render_queue, texture_size, HdrTexture, Tracked};
 
 pub struct Environment {
     pub env_texture: CubeTexture,
@@ -18,6 +19,19 @@ impl Default for Environment {
     }
 }
 
+impl AssetSize for Environment {
+    /// Environments only live on the gpu.
+    #[inline]
+    fn cpu_size(&self) -> usize {
+        0
+    }
+
+    #[inline]
+    fn gpu_size(&self) -> usize {
+        (self.env_texture.gpu_size() + self.irradiance_texture.gpu_size()) as usize
+    }
+}
+
 impl Environment {
     pub fn load(&mut self, hdr_texture: &HdrTexture) {
         self.env_texture.load_hdr_texture(hdr_texture);
@@ -25,9 +39,31 @@ impl Environment {
     }
 }
 
+/// Loads radiance hdr images as environments, the render context must be set.
+pub struct EnvironmentLoader;
+
+impl AssetLoader for EnvironmentLoader {
+    type Asset = Environment;
+
+    #[inline]
+    fn extensions(&self) -> &[&str] {
+        &["hdr"]
+    }
+
+    #[inline]
+    fn load(&self, bytes: &[u8], _context: &mut LoadContext) -> anyhow::Result<Self::Asset> {
+        let hdr_texture = HdrTexture::from_hdr_bytes(bytes)?;
+
+        let mut env = Environment::default();
+        env.load(&hdr_texture);
+
+        Ok(env)
+    }
+}
+
 pub struct CubeTexture {
     size: u32,
-    texture: OnceCell<ike_wgpu::Texture>,
+    texture: OnceCell<Tracked<ike_wgpu::Texture>>,
 }
 
 impl Default for CubeTexture {
@@ -59,20 +95,7 @@ impl CubeTexture {
 
         let device = render_device();
 
-        let cube_texture = device.create_texture(&ike_wgpu::TextureDescriptor {
-            label: None,
-            size: ike_wgpu::Extent3d {
-                width: self.size,
-                height: self.size,
-                depth_or_array_layers: 6,
-            },
-            format: ike_wgpu::TextureFormat::Rgba32Float,
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: ike_wgpu::TextureDimension::D2,
-            usage: ike_wgpu::TextureUsages::STORAGE_BINDING
-                | ike_wgpu::TextureUsages::TEXTURE_BINDING,
-        });
+        let cube_texture = self.create_texture();
 
         let eq_layout = device.create_bind_group_layout(&ike_wgpu::BindGroupLayoutDescriptor {
             label: None,
@@ -197,20 +220,7 @@ impl CubeTexture {
 
         let device = render_device();
 
-        let cube_texture = device.create_texture(&ike_wgpu::TextureDescriptor {
-            label: None,
-            size: ike_wgpu::Extent3d {
-                width: self.size,
-                height: self.size,
-                depth_or_array_layers: 6,
-            },
-            format: ike_wgpu::TextureFormat::Rgba32Float,
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: ike_wgpu::TextureDimension::D2,
-            usage: ike_wgpu::TextureUsages::STORAGE_BINDING
-                | ike_wgpu::TextureUsages::TEXTURE_BINDING,
-        });
+        let cube_texture = self.create_texture();
 
         let eq_layout = device.create_bind_group_layout(&ike_wgpu::BindGroupLayoutDescriptor {
             label: None,
@@ -329,24 +339,35 @@ impl CubeTexture {
         self.texture = OnceCell::from(cube_texture);
     }
 
+    #[inline]
+    fn create_texture(&self) -> Tracked<ike_wgpu::Texture> {
+        let desc = ike_wgpu::TextureDescriptor {
+            label: None,
+            size: ike_wgpu::Extent3d {
+                width: self.size,
+                height: self.size,
+                depth_or_array_layers: 6,
+            },
+            format: ike_wgpu::TextureFormat::Rgba32Float,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: ike_wgpu::TextureDimension::D2,
+            usage: ike_wgpu::TextureUsages::STORAGE_BINDING
+                | ike_wgpu::TextureUsages::TEXTURE_BINDING,
+        };
+
+        Tracked::new(render_device().create_texture(&desc), texture_size(&desc))
+    }
+
     #[inline]
     pub fn inner(&self) -> &ike_wgpu::Texture {
-        self.texture.get_or_init(|| {
-            render_device().create_texture(&ike_wgpu::TextureDescriptor {
-                label: None,
-                size: ike_wgpu::Extent3d {
-                    width: self.size,
-                    height: self.size,
-                    depth_or_array_layers: 6,
-                },
-                format: ike_wgpu::TextureFormat::Rgba32Float,
-                mip_level_count: 1,
-                sample_count: 1,
-                dimension: ike_wgpu::TextureDimension::D2,
-                usage: ike_wgpu::TextureUsages::STORAGE_BINDING
-                    | ike_wgpu::TextureUsages::TEXTURE_BINDING,
-            })
-        })
+        self.texture.get_or_init(|| self.create_texture())
+    }
+
+    /// Bytes of gpu memory used by the texture, 0 until it's created.
+    #[inline]
+    pub fn gpu_size(&self) -> u64 {
+        self.texture.get().map_or(0, Tracked::size)
     }
 
     #[inline]
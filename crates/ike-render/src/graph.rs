@@ -1,4 +1,7 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    time::Instant,
+};
 
 use ike_core::World;
 
@@ -65,6 +68,8 @@ struct NodeContainer {
     input: Vec<EdgeSlotInfo>,
     output: NodeEdge,
     node: Box<dyn RenderNode>,
+    // seconds spent in the last run
+    time: f32,
 }
 
 impl NodeContainer {
@@ -74,6 +79,7 @@ impl NodeContainer {
             input: render_node.input(),
             output: NodeEdge::from_info(render_node.output()),
             node: Box::new(render_node),
+            time: 0.0,
         }
     }
 }
@@ -240,6 +246,14 @@ impl RenderGraph {
         }
     }
 
+    /// Returns the cpu time in seconds each node spent in the last [`run`](Self::run).
+    #[inline]
+    pub fn node_times(&self) -> impl Iterator<Item = (&str, f32)> {
+        self.nodes
+            .iter()
+            .map(|(name, node)| (name.as_str(), node.time))
+    }
+
     #[inline]
     pub fn calculate_stages(&mut self) {
         self.stages.clear();
@@ -277,9 +291,13 @@ impl RenderGraph {
                     }
                 }
 
+                let start = Instant::now();
+
                 node.node
                     .run(&mut encoder, world, &input, &mut node.output)?;
 
+                node.time = start.elapsed().as_secs_f32();
+
                 node.output.slots_set()?;
 
                 self.nodes.insert(name.clone(), node);
@@ -4,6 +4,7 @@ mod color;
 mod cube_texture;
 mod edge;
 mod graph;
+mod memory;
 mod mesh;
 mod node;
 mod render_ctx;
@@ -19,6 +20,7 @@ pub use color::*;
 pub use cube_texture::*;
 pub use edge::*;
 pub use graph::*;
+pub use memory::*;
 pub use mesh::*;
 pub use node::*;
 pub use render_ctx::*;
@@ -59,9 +61,20 @@ impl Plugin for RenderPlugin {
 
         app.world_mut().insert_resource(render_graph);
         app.world_mut().insert_resource(MainCamera(None));
-        app.add_asset::<Mesh>();
-        app.add_asset::<Texture>();
-        app.add_asset::<Environment>();
+        app.add_sized_asset::<Mesh>();
+        app.add_sized_asset::<Texture>();
+        app.add_sized_asset::<HdrTexture>();
+        app.add_sized_asset::<Environment>();
+        app.add_asset_loader(TextureLoader);
+        app.add_asset_loader(HdrTextureLoader);
+        app.add_asset_loader(EnvironmentLoader);
+        app.add_asset_loader(ProcessedTextureLoader);
+        app.add_asset_importer(TextureImporter);
+        app.add_asset_loader(MeshLoader);
+        app.add_asset_loader(ProcessedMeshLoader);
+        app.add_asset_importer(MeshImporter);
         app.add_exclusive_system_to_stage(RenderSystem, stage::RENDER);
+        app.add_system_to_stage(texture_data_system::<Rgba8Unorm>.system(), stage::END);
+        app.add_system_to_stage(texture_data_system::<Rgba32Float>.system(), stage::END);
     }
 }
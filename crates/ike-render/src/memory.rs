@@ -0,0 +1,124 @@
+use std::{
+    ops::Deref,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+static GPU_MEMORY: AtomicU64 = AtomicU64::new(0);
+
+/// Bytes of gpu memory held by the buffers and textures created by ike-render, like those of
+/// [`Texture`](crate::Texture), [`CubeTexture`](crate::CubeTexture) and [`Buffer`](crate::Buffer).
+#[inline]
+pub fn gpu_memory() -> u64 {
+    GPU_MEMORY.load(Ordering::Relaxed)
+}
+
+/// The bytes of a texture created with `desc`, including its mip levels.
+#[inline]
+pub fn texture_size<L>(desc: &ike_wgpu::TextureDescriptor<L>) -> u64 {
+    let info = desc.format.describe();
+    let (block_width, block_height) = info.block_dimensions;
+
+    let texels: u64 = (0..desc.mip_level_count)
+        .filter_map(|level| desc.mip_level_size(level))
+        .map(|size| {
+            let width = (size.width as u64).div_ceil(block_width as u64);
+            let height = (size.height as u64).div_ceil(block_height as u64);
+            width * height * size.depth_or_array_layers as u64
+        })
+        .sum();
+
+    texels * info.block_size as u64 * desc.sample_count as u64
+}
+
+/// A gpu resource counted by [`gpu_memory`] until it's dropped.
+pub struct Tracked<T> {
+    resource: T,
+    size: u64,
+}
+
+impl<T> Tracked<T> {
+    #[inline]
+    pub fn new(resource: T, size: u64) -> Self {
+        GPU_MEMORY.fetch_add(size, Ordering::Relaxed);
+
+        Self { resource, size }
+    }
+
+    #[inline]
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+}
+
+impl<T> Deref for Tracked<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.resource
+    }
+}
+
+impl<T> Drop for Tracked<T> {
+    #[inline]
+    fn drop(&mut self) {
+        GPU_MEMORY.fetch_sub(self.size, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn descriptor(
+        format: ike_wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+        mip_level_count: u32,
+    ) -> ike_wgpu::TextureDescriptor<Option<&'static str>> {
+        ike_wgpu::TextureDescriptor {
+            label: None,
+            size: ike_wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count,
+            sample_count: 1,
+            dimension: ike_wgpu::TextureDimension::D2,
+            format,
+            usage: ike_wgpu::TextureUsages::TEXTURE_BINDING,
+        }
+    }
+
+    #[test]
+    fn texture_sizes() {
+        let rgba8 = ike_wgpu::TextureFormat::Rgba8Unorm;
+
+        assert_eq!(texture_size(&descriptor(rgba8, 4, 4, 1)), 4 * 4 * 4);
+        assert_eq!(texture_size(&descriptor(rgba8, 4, 4, 3)), (16 + 4 + 1) * 4);
+        assert_eq!(texture_size(&descriptor(rgba8, 5, 3, 2)), (15 + 2) * 4);
+
+        let rgba32 = ike_wgpu::TextureFormat::Rgba32Float;
+        assert_eq!(texture_size(&descriptor(rgba32, 2, 2, 1)), 2 * 2 * 16);
+
+        // compressed blocks are counted whole, 4x4 texels of 8 bytes for bc1
+        let bc1 = ike_wgpu::TextureFormat::Bc1RgbaUnorm;
+        assert_eq!(texture_size(&descriptor(bc1, 8, 8, 1)), 2 * 2 * 8);
+        assert_eq!(texture_size(&descriptor(bc1, 6, 5, 1)), 2 * 2 * 8);
+    }
+
+    #[test]
+    fn tracked() {
+        // the count is shared by every test, which may create resources at the same time
+        let before = gpu_memory();
+        let tracked = Tracked::new((), 1 << 40);
+
+        assert_eq!(tracked.size(), 1 << 40);
+        assert!(gpu_memory() >= before + (1 << 40));
+
+        drop(tracked);
+
+        assert!(gpu_memory() < before + (1 << 40));
+    }
+}
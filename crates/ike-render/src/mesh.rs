@@ -2,6 +2,8 @@ use std::collections::HashMap;
 
 use bytemuck::{cast_slice, cast_vec};
 use glam::{Vec2, Vec3, Vec4};
+use ike_assets::{AssetImporter, AssetLoader, AssetSize, LoadContext};
+use serde::Deserialize;
 
 use crate::{Buffer, Color};
 
@@ -23,6 +25,44 @@ impl VertexData {
             VertexData::Float32x4(data) => cast_slice(data),
         }
     }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        match self {
+            VertexData::Float32(data) => data.len(),
+            VertexData::Float32x2(data) => data.len(),
+            VertexData::Float32x3(data) => data.len(),
+            VertexData::Float32x4(data) => data.len(),
+        }
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The number of floats per vertex.
+    #[inline]
+    pub fn components(&self) -> usize {
+        match self {
+            VertexData::Float32(_) => 1,
+            VertexData::Float32x2(_) => 2,
+            VertexData::Float32x3(_) => 3,
+            VertexData::Float32x4(_) => 4,
+        }
+    }
+
+    // reads `components` floats per vertex from little endian bytes
+    #[inline]
+    fn from_bytes(components: usize, bytes: &[u8]) -> Option<Self> {
+        Some(match components {
+            1 => VertexData::Float32(bytemuck::pod_collect_to_vec(bytes)),
+            2 => VertexData::Float32x2(bytemuck::pod_collect_to_vec(bytes)),
+            3 => VertexData::Float32x3(bytemuck::pod_collect_to_vec(bytes)),
+            4 => VertexData::Float32x4(bytemuck::pod_collect_to_vec(bytes)),
+            _ => return None,
+        })
+    }
 }
 
 #[derive(Clone)]
@@ -143,6 +183,449 @@ impl Mesh {
 
         attr.buffer.get_raw()
     }
+
+    /// Computes [`TANGENT`](Self::TANGENT)s from the positions, normals and uvs of the
+    /// triangles, with the handedness of the bitangent in `w`, does nothing without them.
+    #[inline]
+    pub fn generate_tangents(&mut self) {
+        let (positions, normals, uvs) = match (
+            self.get::<[f32; 3]>(Self::POSITION),
+            self.get::<[f32; 3]>(Self::NORMAL),
+            self.get::<[f32; 2]>(Self::UV),
+        ) {
+            (Some(positions), Some(normals), Some(uvs))
+                if positions.len() == normals.len() && positions.len() == uvs.len() =>
+            {
+                (positions, normals, uvs)
+            }
+            _ => return,
+        };
+
+        let mut tangents = vec![Vec3::ZERO; positions.len()];
+        let mut bitangents = vec![Vec3::ZERO; positions.len()];
+
+        for triangle in self.indices.chunks_exact(3) {
+            let [a, b, c] = [triangle[0], triangle[1], triangle[2]].map(|index| index as usize);
+
+            if a >= positions.len() || b >= positions.len() || c >= positions.len() {
+                continue;
+            }
+
+            let edge_1 = Vec3::from(positions[b]) - Vec3::from(positions[a]);
+            let edge_2 = Vec3::from(positions[c]) - Vec3::from(positions[a]);
+            let delta_uv_1 = Vec2::from(uvs[b]) - Vec2::from(uvs[a]);
+            let delta_uv_2 = Vec2::from(uvs[c]) - Vec2::from(uvs[a]);
+
+            let determinant = delta_uv_1.perp_dot(delta_uv_2);
+
+            // degenerate uvs don't say anything about the tangent
+            if determinant.abs() <= f32::EPSILON {
+                continue;
+            }
+
+            let tangent = (edge_1 * delta_uv_2.y - edge_2 * delta_uv_1.y) / determinant;
+            let bitangent = (edge_2 * delta_uv_1.x - edge_1 * delta_uv_2.x) / determinant;
+
+            for index in [a, b, c] {
+                tangents[index] += tangent;
+                bitangents[index] += bitangent;
+            }
+        }
+
+        let tangents = normals
+            .iter()
+            .zip(tangents)
+            .zip(bitangents)
+            .map(|((&normal, tangent), bitangent)| {
+                let normal = Vec3::from(normal).normalize_or_zero();
+
+                let mut tangent = (tangent - normal * normal.dot(tangent)).normalize_or_zero();
+
+                if tangent == Vec3::ZERO {
+                    tangent = if normal == Vec3::ZERO {
+                        Vec3::X
+                    } else {
+                        normal.any_orthonormal_vector()
+                    };
+                }
+
+                let handedness = if normal.cross(tangent).dot(bitangent) < 0.0 {
+                    -1.0
+                } else {
+                    1.0
+                };
+
+                tangent.extend(handedness).to_array()
+            })
+            .collect::<Vec<_>>();
+
+        self.insert(Self::TANGENT, tangents);
+    }
+
+    /// Parses a wavefront obj, faces are triangulated as fans, vertices without a normal get
+    /// the area weighted normal of their faces and every vertex is white.
+    #[inline]
+    pub fn from_obj_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        let source = std::str::from_utf8(bytes)?;
+
+        let mut obj_positions = Vec::new();
+        let mut obj_uvs = Vec::new();
+        let mut obj_normals = Vec::new();
+
+        let mut vertices = HashMap::new();
+        let mut positions = Vec::new();
+        let mut uvs = Vec::new();
+        let mut normals = Vec::new();
+        let mut indices = Vec::new();
+
+        for (number, line) in source.lines().enumerate() {
+            let error = |message: &str| anyhow::anyhow!("line {}: {}", number + 1, message);
+
+            let mut words = line.split_whitespace();
+            let keyword = words.next();
+
+            let mut float = |required: bool| match words.next() {
+                Some(word) => word.parse::<f32>().map_err(|_| error("invalid number")),
+                None if required => Err(error("missing number")),
+                None => Ok(0.0),
+            };
+
+            match keyword {
+                Some("v") => {
+                    let position = Vec3::new(float(true)?, float(true)?, float(true)?);
+                    obj_positions.push(position);
+                }
+                Some("vt") => {
+                    // the v coordinate is optional
+                    obj_uvs.push(Vec2::new(float(true)?, float(false)?));
+                }
+                Some("vn") => {
+                    let normal = Vec3::new(float(true)?, float(true)?, float(true)?);
+                    obj_normals.push(normal);
+                }
+                Some("f") => {
+                    let mut face = Vec::new();
+
+                    for word in words {
+                        let mut parts = word.split('/');
+
+                        // obj indices start at 1, negative ones count back from the last element
+                        let mut index = |len: usize| -> anyhow::Result<Option<usize>> {
+                            let part = match parts.next() {
+                                Some(part) if !part.is_empty() => part,
+                                _ => return Ok(None),
+                            };
+
+                            let index =
+                                part.parse::<isize>().map_err(|_| error("invalid index"))?;
+
+                            let index = if index < 0 {
+                                len as isize + index
+                            } else {
+                                index - 1
+                            };
+
+                            if index < 0 || index as usize >= len {
+                                return Err(error("index out of range"));
+                            }
+
+                            Ok(Some(index as usize))
+                        };
+
+                        let position = index(obj_positions.len())?
+                            .ok_or_else(|| error("face vertex without a position"))?;
+                        let uv = index(obj_uvs.len())?;
+                        let normal = index(obj_normals.len())?;
+
+                        let vertex = *vertices.entry((position, uv, normal)).or_insert_with(|| {
+                            positions.push(obj_positions[position]);
+                            uvs.push(uv.map_or(Vec2::ZERO, |uv| obj_uvs[uv]));
+                            normals.push(normal.map(|normal| obj_normals[normal]));
+
+                            positions.len() as u32 - 1
+                        });
+
+                        face.push(vertex);
+                    }
+
+                    if face.len() < 3 {
+                        return Err(error("face with less than 3 vertices"));
+                    }
+
+                    for i in 1..face.len() - 1 {
+                        indices.extend_from_slice(&[face[0], face[i], face[i + 1]]);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut face_normals = vec![Vec3::ZERO; positions.len()];
+
+        if normals.iter().any(Option::is_none) {
+            for triangle in indices.chunks_exact(3) {
+                let [a, b, c] = [triangle[0], triangle[1], triangle[2]].map(|index| index as usize);
+
+                // the length of the cross product is twice the area of the triangle
+                let normal = (positions[b] - positions[a]).cross(positions[c] - positions[a]);
+
+                for index in [a, b, c] {
+                    face_normals[index] += normal;
+                }
+            }
+        }
+
+        let normals = normals
+            .into_iter()
+            .zip(face_normals)
+            .map(|(normal, face_normal)| normal.unwrap_or_else(|| face_normal.normalize_or_zero()))
+            .collect::<Vec<_>>();
+
+        let mut mesh = Self::new();
+        mesh.insert(Self::COLOR, vec![Color::WHITE; positions.len()]);
+        mesh.insert(Self::POSITION, positions);
+        mesh.insert(Self::NORMAL, normals);
+        mesh.insert(Self::UV, uvs);
+        *mesh.indices_mut() = indices;
+
+        Ok(mesh)
+    }
+
+    /// Encodes the vertex attributes and indices in the processed form read by [`ProcessedMeshLoader`].
+    #[inline]
+    pub fn to_processed_bytes(&self) -> Vec<u8> {
+        let mut attributes = self.vertices.iter().collect::<Vec<_>>();
+        attributes.sort_by_key(|&(name, _)| name);
+
+        let mut bytes = Vec::new();
+
+        bytes.extend_from_slice(&PROCESSED_MESH_MAGIC);
+        bytes.extend_from_slice(&PROCESSED_MESH_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(attributes.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&(self.indices.len() as u32).to_le_bytes());
+
+        for (name, attribute) in attributes {
+            bytes.extend_from_slice(&(name.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(name.as_bytes());
+            bytes.push(attribute.data.components() as u8);
+            bytes.extend_from_slice(&(attribute.data.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(attribute.data.data());
+        }
+
+        bytes.extend_from_slice(cast_slice(&self.indices));
+
+        bytes
+    }
+
+    #[inline]
+    pub fn from_processed_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        if bytes.len() < PROCESSED_MESH_HEADER_SIZE || bytes[..4] != PROCESSED_MESH_MAGIC {
+            anyhow::bail!("not a processed mesh");
+        }
+
+        let mut reader = ProcessedMeshReader { bytes, offset: 4 };
+
+        let version = reader.read_u32()?;
+
+        if version != PROCESSED_MESH_VERSION {
+            anyhow::bail!("unsupported processed mesh version {}", version);
+        }
+
+        let attribute_count = reader.read_u32()?;
+        let index_count = reader.read_u32()? as usize;
+
+        let mut mesh = Self::new();
+
+        for _ in 0..attribute_count {
+            let name_len = reader.read_u32()? as usize;
+            let name = std::str::from_utf8(reader.read(name_len)?)?.to_string();
+            let components = reader.read(1)?[0] as usize;
+            let len = reader.read_u32()? as usize;
+
+            let size = len
+                .checked_mul(components * std::mem::size_of::<f32>())
+                .ok_or_else(|| anyhow::anyhow!("processed mesh is truncated"))?;
+
+            let data = VertexData::from_bytes(components, reader.read(size)?).ok_or_else(|| {
+                anyhow::anyhow!("{} components in processed mesh attribute", components)
+            })?;
+
+            mesh.vertices.insert(name, VertexAttribute::new(data));
+        }
+
+        let size = index_count
+            .checked_mul(std::mem::size_of::<u32>())
+            .ok_or_else(|| anyhow::anyhow!("processed mesh is truncated"))?;
+        mesh.indices = bytemuck::pod_collect_to_vec(reader.read(size)?);
+
+        if reader.offset != bytes.len() {
+            anyhow::bail!("processed mesh data doesn't match its size");
+        }
+
+        Ok(mesh)
+    }
+}
+
+// reads the processed mesh format front to back
+struct ProcessedMeshReader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> ProcessedMeshReader<'a> {
+    #[inline]
+    fn read(&mut self, len: usize) -> anyhow::Result<&'a [u8]> {
+        let end = self
+            .offset
+            .checked_add(len)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or_else(|| anyhow::anyhow!("processed mesh is truncated"))?;
+
+        let slice = &self.bytes[self.offset..end];
+        self.offset = end;
+
+        Ok(slice)
+    }
+
+    #[inline]
+    fn read_u32(&mut self) -> anyhow::Result<u32> {
+        let mut value = [0; 4];
+        value.copy_from_slice(self.read(4)?);
+
+        Ok(u32::from_le_bytes(value))
+    }
+}
+
+/// Processed meshes start with the magic followed by the version, attribute count and index
+/// count, then every attribute as its name length, name, component count, vertex count and
+/// floats, then the indices, all little endian.
+pub const PROCESSED_MESH_MAGIC: [u8; 4] = *b"IKMS";
+pub const PROCESSED_MESH_VERSION: u32 = 1;
+
+const PROCESSED_MESH_HEADER_SIZE: usize = 4 + 4 + 4 + 4;
+
+impl AssetSize for Mesh {
+    #[inline]
+    fn cpu_size(&self) -> usize {
+        let vertices: usize = self
+            .vertices
+            .values()
+            .map(|attribute| attribute.data.data().len())
+            .sum();
+
+        vertices + self.indices.len() * std::mem::size_of::<u32>()
+    }
+
+    #[inline]
+    fn gpu_size(&self) -> usize {
+        let vertices: u64 = self
+            .vertices
+            .values()
+            .map(|attribute| attribute.buffer.gpu_size())
+            .sum();
+
+        (vertices + self.index_buffer.gpu_size()) as usize
+    }
+}
+
+pub struct MeshLoader;
+
+impl AssetLoader for MeshLoader {
+    type Asset = Mesh;
+
+    #[inline]
+    fn extensions(&self) -> &[&str] {
+        &["obj"]
+    }
+
+    #[inline]
+    fn load(&self, bytes: &[u8], _context: &mut LoadContext) -> anyhow::Result<Self::Asset> {
+        let mut mesh = Mesh::from_obj_bytes(bytes)?;
+        flip_uvs(&mut mesh);
+        mesh.generate_tangents();
+
+        Ok(mesh)
+    }
+}
+
+// obj uvs start at the bottom of the texture, ours at the top
+#[inline]
+fn flip_uvs(mesh: &mut Mesh) {
+    if let Some(uvs) = mesh.get_mut::<[f32; 2]>(Mesh::UV) {
+        for uv in uvs {
+            uv[1] = 1.0 - uv[1];
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct MeshImportSettings {
+    pub flip_uvs: bool,
+}
+
+impl Default for MeshImportSettings {
+    #[inline]
+    fn default() -> Self {
+        Self { flip_uvs: true }
+    }
+}
+
+/// Imports obj files into processed meshes, which load without parsing and with their
+/// tangents generated.
+///
+/// ```toml
+/// # rock.obj.meta
+/// flip_uvs = false
+/// ```
+pub struct MeshImporter;
+
+impl AssetImporter for MeshImporter {
+    type Settings = MeshImportSettings;
+
+    #[inline]
+    fn extensions(&self) -> &[&str] {
+        &["obj"]
+    }
+
+    #[inline]
+    fn processed_extension(&self) -> &str {
+        "imesh"
+    }
+
+    #[inline]
+    fn version(&self) -> u32 {
+        PROCESSED_MESH_VERSION
+    }
+
+    #[inline]
+    fn import(&self, bytes: &[u8], settings: &Self::Settings) -> anyhow::Result<Vec<u8>> {
+        let mut mesh = Mesh::from_obj_bytes(bytes)?;
+
+        if settings.flip_uvs {
+            flip_uvs(&mut mesh);
+        }
+
+        mesh.generate_tangents();
+
+        Ok(mesh.to_processed_bytes())
+    }
+}
+
+pub struct ProcessedMeshLoader;
+
+impl AssetLoader for ProcessedMeshLoader {
+    type Asset = Mesh;
+
+    #[inline]
+    fn extensions(&self) -> &[&str] {
+        &["imesh"]
+    }
+
+    #[inline]
+    fn load(&self, bytes: &[u8], _context: &mut LoadContext) -> anyhow::Result<Self::Asset> {
+        Mesh::from_processed_bytes(bytes)
+    }
 }
 
 pub trait IntoVertexData: Sized {
@@ -273,3 +756,126 @@ impl AsVertexData for [f32; 4] {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QUAD: &str = "
+        # a unit quad facing +z
+        v 0 0 0
+        v 1 0 0
+        v 1 1 0
+        v 0 1 0
+        vt 0 0
+        vt 1 0
+        vt 1 1
+        vt 0 1
+        f 1/1 2/2 3/3 -1/-1
+    ";
+
+    #[test]
+    fn generate_tangents() {
+        let mut mesh = Mesh::new();
+        mesh.insert(
+            Mesh::POSITION,
+            vec![Vec3::ZERO, Vec3::X, Vec3::Y, Vec3::ONE],
+        );
+        mesh.insert(Mesh::NORMAL, vec![Vec3::Z; 4]);
+        mesh.indices_mut().extend_from_slice(&[0, 1, 2, 2, 1, 3]);
+
+        // nothing to generate from without uvs
+        mesh.generate_tangents();
+        assert!(mesh.get::<[f32; 4]>(Mesh::TANGENT).is_none());
+
+        mesh.insert(Mesh::UV, vec![Vec2::ZERO, Vec2::X, Vec2::Y, Vec2::ONE]);
+        mesh.generate_tangents();
+
+        let tangents = mesh.get::<[f32; 4]>(Mesh::TANGENT).unwrap();
+        assert_eq!(tangents, &vec![[1.0, 0.0, 0.0, 1.0]; 4]);
+
+        // mirrored uvs flip the tangent and its handedness
+        for uv in mesh.get_mut::<[f32; 2]>(Mesh::UV).unwrap() {
+            uv[0] = 1.0 - uv[0];
+        }
+
+        mesh.generate_tangents();
+
+        let tangents = mesh.get::<[f32; 4]>(Mesh::TANGENT).unwrap();
+        assert_eq!(tangents, &vec![[-1.0, 0.0, 0.0, -1.0]; 4]);
+
+        // degenerate uvs still give a tangent orthogonal to the normal
+        mesh.insert(Mesh::UV, vec![Vec2::ZERO; 4]);
+        mesh.generate_tangents();
+
+        for tangent in mesh.get::<[f32; 4]>(Mesh::TANGENT).unwrap() {
+            let tangent = Vec4::from(*tangent).truncate();
+            assert!((tangent.length() - 1.0).abs() < 1e-5);
+            assert!(tangent.dot(Vec3::Z).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn obj() {
+        let mesh = Mesh::from_obj_bytes(QUAD.as_bytes()).unwrap();
+
+        assert_eq!(mesh.indices(), &vec![0, 1, 2, 0, 2, 3]);
+        assert_eq!(mesh.get::<[f32; 3]>(Mesh::POSITION).unwrap().len(), 4);
+        assert_eq!(mesh.get::<[f32; 2]>(Mesh::UV).unwrap()[2], [1.0, 1.0]);
+        assert_eq!(
+            mesh.get::<[f32; 3]>(Mesh::NORMAL).unwrap(),
+            &vec![[0.0, 0.0, 1.0]; 4]
+        );
+        assert_eq!(
+            mesh.get::<[f32; 4]>(Mesh::COLOR).unwrap(),
+            &vec![[1.0; 4]; 4]
+        );
+
+        // shared vertices are only added once, given normals are kept
+        let mesh = Mesh::from_obj_bytes(
+            b"v 0 0 0\nv 1 0 0\nv 0 1 0\nv 1 1 0\nvn 0 0 -1\nf 1//1 2//1 3//1\nf 3//1 2//1 4//1",
+        )
+        .unwrap();
+
+        assert_eq!(mesh.indices(), &vec![0, 1, 2, 2, 1, 3]);
+        assert_eq!(
+            mesh.get::<[f32; 3]>(Mesh::NORMAL).unwrap(),
+            &vec![[0.0, 0.0, -1.0]; 4]
+        );
+
+        assert!(Mesh::from_obj_bytes(b"v 0 0 0\nv 1 0 0\nf 1 2").is_err());
+        assert!(Mesh::from_obj_bytes(b"v 0 0 0\nv 1 0 0\nf 1 2 3").is_err());
+        assert!(Mesh::from_obj_bytes(b"v 0 0 0\nv 1 0 0\nf 1 2 0").is_err());
+        assert!(Mesh::from_obj_bytes(b"v 0 x 0").is_err());
+        assert!(Mesh::from_obj_bytes(b"vn 0 1").is_err());
+    }
+
+    #[test]
+    fn processed_bytes() {
+        let bytes = MeshImporter
+            .import(QUAD.as_bytes(), &MeshImportSettings::default())
+            .unwrap();
+        let mesh = Mesh::from_processed_bytes(&bytes).unwrap();
+
+        assert_eq!(mesh.indices(), &vec![0, 1, 2, 0, 2, 3]);
+        assert_eq!(mesh.get::<[f32; 3]>(Mesh::POSITION).unwrap().len(), 4);
+        assert_eq!(mesh.get::<[f32; 4]>(Mesh::COLOR).unwrap().len(), 4);
+
+        // uvs are flipped to start at the top, so the bitangent points down
+        assert_eq!(mesh.get::<[f32; 2]>(Mesh::UV).unwrap()[0], [0.0, 1.0]);
+        assert_eq!(
+            mesh.get::<[f32; 4]>(Mesh::TANGENT).unwrap(),
+            &vec![[1.0, 0.0, 0.0, -1.0]; 4]
+        );
+
+        assert_eq!(mesh.to_processed_bytes(), bytes);
+
+        assert!(Mesh::from_processed_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Mesh::from_processed_bytes(&[bytes.as_slice(), &[0]].concat()).is_err());
+        assert!(Mesh::from_processed_bytes(b"IKMS").is_err());
+
+        let mut bytes = bytes;
+        bytes[4..8].copy_from_slice(&2u32.to_le_bytes());
+        assert!(Mesh::from_processed_bytes(&bytes).is_err());
+    }
+}
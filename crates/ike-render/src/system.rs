@@ -1,10 +1,11 @@
 use glam::{Mat4, UVec2, Vec3};
+use ike_assets::{Assets, HandleUntyped};
 use ike_core::*;
 use ike_transform::Transform;
 
 use crate::{
-    Camera, MainCamera, PerspectiveProjection, RenderGraph, RenderSurface, RenderTexture,
-    ViewInputNode,
+    gpu_memory, Camera, MainCamera, PerspectiveProjection, RenderGraph, RenderSurface,
+    RenderTexture, Texture, TextureFormat, ViewInputNode,
 };
 
 pub struct RenderSystem;
@@ -80,6 +81,33 @@ impl ExclusiveSystem for RenderSystem {
 
         render_texture.present();
 
+        let draw_stats = ike_wgpu::take_draw_stats();
+
+        if let Some(mut diagnostics) = world.write_resource::<Diagnostics>() {
+            for (name, time) in render_graph.node_times() {
+                let name = format!("{}{}", diagnostic::RENDER_NODE_TIME, name);
+                diagnostics.measure(name, time as f64 * 1000.0);
+            }
+
+            diagnostics.measure(diagnostic::DRAW_CALLS, draw_stats.draw_calls as f64);
+            diagnostics.measure(diagnostic::TRIANGLES, draw_stats.triangles as f64);
+            diagnostics.measure(diagnostic::GPU_BYTES, gpu_memory() as f64);
+        }
+
         world.insert_resource(render_graph);
     }
 }
+
+/// Frees the cpu data of textures loaded from files once they're on the gpu, unless they
+/// [keep their data](Texture::set_keep_data), [`AssetServer::reload`](ike_assets::AssetServer::reload)
+/// gets it back.
+pub fn texture_data_system<F: TextureFormat>(mut textures: ResMut<Assets<Texture<F>>>)
+where
+    Texture<F>: Send + Sync,
+{
+    for (handle, texture) in textures.iter_mut_untracked() {
+        if let HandleUntyped::Path(_) = handle.untyped() {
+            texture.free_data();
+        }
+    }
+}
@@ -1,11 +1,18 @@
-use std::{fmt::Debug, io::BufReader, path::Path};
+use std::{
+    fmt::Debug,
+    io::{BufRead, BufReader},
+    path::Path,
+    sync::atomic::{AtomicU64, Ordering},
+};
 
 use bytemuck::cast_slice;
 use glam::UVec2;
-use image::{hdr::HdrDecoder, io::Reader};
+use ike_assets::{AssetImporter, AssetLoader, AssetSize, LoadContext};
+use image::{hdr::HdrDecoder, io::Reader, DynamicImage};
 use once_cell::sync::OnceCell;
+use serde::Deserialize;
 
-use crate::{render_device, render_queue, Color, Color8};
+use crate::{render_device, render_queue, texture_size, Color, Color8, Tracked};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct TextureVersion(u64);
@@ -17,6 +24,15 @@ impl Default for TextureVersion {
     }
 }
 
+static NEXT_VERSION: AtomicU64 = AtomicU64::new(1);
+
+// versions are unique across all textures, so a texture replacing another, like when an asset
+// is reloaded, is always newer than any version taken from the one it replaced
+#[inline]
+fn next_version() -> u64 {
+    NEXT_VERSION.fetch_add(1, Ordering::Relaxed)
+}
+
 pub trait TextureFormat: Clone + Default {
     type Data: bytemuck::Pod + bytemuck::Zeroable;
 
@@ -58,7 +74,8 @@ impl TextureFormat for Rgba32Float {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ColorSpace {
     Linear,
     Gamma,
@@ -71,16 +88,47 @@ impl ColorSpace {
             Self::Gamma => ike_wgpu::TextureFormat::Rgba8UnormSrgb,
         }
     }
+
+    #[inline]
+    fn to_u8(self) -> u8 {
+        match self {
+            Self::Linear => 0,
+            Self::Gamma => 1,
+        }
+    }
+
+    #[inline]
+    fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::Linear),
+            1 => Some(Self::Gamma),
+            _ => None,
+        }
+    }
+}
+
+// the number of pixels in each mip level, the first being the full size
+#[inline]
+fn mip_level_sizes(width: u32, height: u32, mip_level_count: u32) -> impl Iterator<Item = usize> {
+    (0..mip_level_count).map(move |level| {
+        let width = (width >> level).max(1) as usize;
+        let height = (height >> level).max(1) as usize;
+        width * height
+    })
 }
 
 pub struct Texture<F: TextureFormat = Rgba8Unorm> {
     version: u64,
     width: u32,
     height: u32,
+    mip_level_count: u32,
     format: F,
+    keep_data: bool,
+    // set once the data is freed, the pixels then only exist on the gpu
+    freed: bool,
     buffer: OnceCell<ike_wgpu::Buffer>,
     data: OnceCell<Vec<F::Data>>,
-    texture: OnceCell<ike_wgpu::Texture>,
+    texture: OnceCell<Tracked<ike_wgpu::Texture>>,
 }
 
 pub type HdrTexture = Texture<Rgba32Float>;
@@ -89,10 +137,13 @@ impl<F: TextureFormat> Default for Texture<F> {
     #[inline]
     fn default() -> Self {
         Self {
-            version: 1,
+            version: next_version(),
             width: 1,
             height: 1,
+            mip_level_count: 1,
             format: F::default(),
+            keep_data: false,
+            freed: false,
             buffer: Default::default(),
             data: Default::default(),
             texture: Default::default(),
@@ -101,16 +152,52 @@ impl<F: TextureFormat> Default for Texture<F> {
 }
 
 impl<F: TextureFormat> Clone for Texture<F> {
+    /// Textures whose data was freed are copied on the gpu.
     #[inline]
     fn clone(&self) -> Self {
+        let texture = match self.texture.get() {
+            Some(source) if self.data.get().is_none() => {
+                let desc = self.descriptor();
+                let texture = render_device().create_texture(&desc);
+
+                let mut encoder = render_device().create_command_encoder(&Default::default());
+
+                for mip_level in 0..self.mip_level_count {
+                    encoder.copy_texture_to_texture(
+                        ike_wgpu::ImageCopyTexture {
+                            texture: source,
+                            mip_level,
+                            origin: ike_wgpu::Origin3d::ZERO,
+                            aspect: ike_wgpu::TextureAspect::All,
+                        },
+                        ike_wgpu::ImageCopyTexture {
+                            texture: &texture,
+                            mip_level,
+                            origin: ike_wgpu::Origin3d::ZERO,
+                            aspect: ike_wgpu::TextureAspect::All,
+                        },
+                        desc.mip_level_size(mip_level).unwrap(),
+                    );
+                }
+
+                render_queue().submit_once(encoder.finish());
+
+                OnceCell::from(Tracked::new(texture, texture_size(&desc)))
+            }
+            _ => OnceCell::new(),
+        };
+
         Self {
-            version: 1,
+            version: next_version(),
             width: self.width,
             height: self.height,
+            mip_level_count: self.mip_level_count,
             format: self.format.clone(),
+            keep_data: self.keep_data,
+            freed: self.freed,
             buffer: OnceCell::new(),
             data: self.data.clone(),
-            texture: OnceCell::new(),
+            texture,
         }
     }
 }
@@ -122,6 +209,7 @@ impl<F: TextureFormat> std::fmt::Debug for Texture<F> {
             .field("version", &self.version)
             .field("width", &self.width)
             .field("height", &self.height)
+            .field("mip_level_count", &self.mip_level_count)
             .finish()
     }
 }
@@ -130,9 +218,18 @@ impl HdrTexture {
     #[inline]
     pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
         let file = std::fs::File::open(path.as_ref())?;
-        let buf_reader = BufReader::new(file);
+        Self::from_reader(BufReader::new(file))
+    }
+
+    /// Decodes a radiance hdr image.
+    #[inline]
+    pub fn from_hdr_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        Self::from_reader(bytes)
+    }
 
-        let image = HdrDecoder::new(buf_reader)?;
+    #[inline]
+    fn from_reader(reader: impl BufRead) -> anyhow::Result<Self> {
+        let image = HdrDecoder::new(reader)?;
 
         let meta = image.metadata();
 
@@ -152,6 +249,17 @@ impl Texture {
     pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
         let image = Reader::open(path.as_ref())?.decode()?;
 
+        Ok(Self::from_image(image))
+    }
+
+    /// Decodes an encoded image like a png or jpg.
+    #[inline]
+    pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        Ok(Self::from_image(image::load_from_memory(bytes)?))
+    }
+
+    #[inline]
+    fn from_image(image: DynamicImage) -> Self {
         let rgba = image.to_rgba8();
 
         let data: Vec<Color8> = rgba
@@ -159,7 +267,7 @@ impl Texture {
             .map(|pixel| Color8::rgba(pixel[0], pixel[1], pixel[2], pixel[3]))
             .collect();
 
-        Ok(Self::from_data(data, rgba.width(), rgba.height()))
+        Self::from_data(data, rgba.width(), rgba.height())
     }
 
     #[inline]
@@ -167,14 +275,149 @@ impl Texture {
         self.format.color_space
     }
 
+    /// Fails if the data was [freed](Self::free_data), since the texture is created again.
     #[inline]
-    pub fn set_color_space(&mut self, color_space: ColorSpace) {
-        self.version += 1;
+    pub fn set_color_space(&mut self, color_space: ColorSpace) -> anyhow::Result<()> {
+        self.ensure_data()?;
+
+        self.version = next_version();
         self.format.color_space = color_space;
         self.texture.take();
+
+        Ok(())
+    }
+
+    /// Replaces the mip levels of the texture with ones down to 1x1, each averaging 2x2
+    /// pixels of the level above, does nothing without data.
+    #[inline]
+    pub fn generate_mips(&mut self) {
+        let data = match self.data.get_mut() {
+            Some(data) => data,
+            None => return,
+        };
+
+        data.truncate(self.width as usize * self.height as usize);
+
+        let mut width = self.width;
+        let mut height = self.height;
+        let mut start = 0;
+        let mut mip_level_count = 1;
+
+        while width > 1 || height > 1 {
+            let next_width = (width / 2).max(1);
+            let next_height = (height / 2).max(1);
+
+            for y in 0..next_height {
+                for x in 0..next_width {
+                    let x0 = (x * 2).min(width - 1);
+                    let x1 = (x * 2 + 1).min(width - 1);
+                    let y0 = (y * 2).min(height - 1);
+                    let y1 = (y * 2 + 1).min(height - 1);
+
+                    let mut sum = [0u32; 4];
+
+                    for (x, y) in [(x0, y0), (x1, y0), (x0, y1), (x1, y1)] {
+                        let pixel = data[start + (y * width + x) as usize];
+
+                        sum[0] += pixel.r as u32;
+                        sum[1] += pixel.g as u32;
+                        sum[2] += pixel.b as u32;
+                        sum[3] += pixel.a as u32;
+                    }
+
+                    let [r, g, b, a] = sum.map(|channel| ((channel + 2) / 4) as u8);
+                    data.push(Color8::rgba(r, g, b, a));
+                }
+            }
+
+            start += (width * height) as usize;
+            width = next_width;
+            height = next_height;
+            mip_level_count += 1;
+        }
+
+        self.version = next_version();
+        self.mip_level_count = mip_level_count;
+        self.texture.take();
+    }
+
+    /// Encodes the texture, with its mip levels, in the processed form read by [`ProcessedTextureLoader`].
+    #[inline]
+    pub fn to_processed_bytes(&self) -> Vec<u8> {
+        let data = self.bytes().unwrap_or_default();
+        let mut bytes = Vec::with_capacity(PROCESSED_TEXTURE_HEADER_SIZE + data.len());
+
+        bytes.extend_from_slice(&PROCESSED_TEXTURE_MAGIC);
+        bytes.extend_from_slice(&PROCESSED_TEXTURE_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&self.width.to_le_bytes());
+        bytes.extend_from_slice(&self.height.to_le_bytes());
+        bytes.extend_from_slice(&self.mip_level_count.to_le_bytes());
+        bytes.push(self.color_space().to_u8());
+        bytes.extend_from_slice(data);
+
+        bytes
+    }
+
+    #[inline]
+    pub fn from_processed_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        if bytes.len() < PROCESSED_TEXTURE_HEADER_SIZE || bytes[..4] != PROCESSED_TEXTURE_MAGIC {
+            anyhow::bail!("not a processed texture");
+        }
+
+        let read_u32 = |offset: usize| {
+            let mut value = [0; 4];
+            value.copy_from_slice(&bytes[offset..offset + 4]);
+            u32::from_le_bytes(value)
+        };
+
+        let version = read_u32(4);
+
+        if version != PROCESSED_TEXTURE_VERSION {
+            anyhow::bail!("unsupported processed texture version {}", version);
+        }
+
+        let width = read_u32(8);
+        let height = read_u32(12);
+        let mip_level_count = read_u32(16);
+
+        let color_space = ColorSpace::from_u8(bytes[20])
+            .ok_or_else(|| anyhow::anyhow!("unknown color space {}", bytes[20]))?;
+
+        // a mip chain goes down to 1x1, so it has at most log2(max(width, height)) + 1 levels
+        let max_mip_level_count = u32::BITS - width.max(height).leading_zeros();
+
+        if mip_level_count == 0 || mip_level_count > max_mip_level_count {
+            anyhow::bail!(
+                "{} mip levels for a {}x{} processed texture",
+                mip_level_count,
+                width,
+                height
+            );
+        }
+
+        let data = &bytes[PROCESSED_TEXTURE_HEADER_SIZE..];
+        let len: usize = mip_level_sizes(width, height, mip_level_count).sum();
+
+        if data.len() != len * std::mem::size_of::<Color8>() {
+            anyhow::bail!("processed texture data doesn't match its size");
+        }
+
+        let mut texture = Self::from_data(bytemuck::pod_collect_to_vec(data), width, height);
+        texture.mip_level_count = mip_level_count;
+        texture.format.color_space = color_space;
+
+        Ok(texture)
     }
 }
 
+/// Processed textures start with the magic followed by the version, width, height, mip level
+/// count and [`ColorSpace`] of the texture, then the pixels of every mip level, all little
+/// endian.
+pub const PROCESSED_TEXTURE_MAGIC: [u8; 4] = *b"IKTX";
+pub const PROCESSED_TEXTURE_VERSION: u32 = 1;
+
+const PROCESSED_TEXTURE_HEADER_SIZE: usize = 4 + 4 + 4 + 4 + 4 + 1;
+
 impl<F: TextureFormat> Texture<F> {
     #[inline]
     pub fn from_size(size: UVec2) -> Self {
@@ -220,17 +463,24 @@ impl<F: TextureFormat> Texture<F> {
         UVec2::new(self.width, self.height)
     }
 
+    /// Clears the texture, fails if the data was [freed](Self::free_data).
     #[inline]
-    pub fn resize(&mut self, width: u32, height: u32) {
+    pub fn resize(&mut self, width: u32, height: u32) -> anyhow::Result<()> {
+        self.ensure_data()?;
+
         if self.width != width || self.height != height {
-            self.version += 1;
+            self.version = next_version();
             self.width = width;
             self.height = height;
 
+            self.mip_level_count = 1;
+
             self.texture.take();
             self.data.take();
             self.buffer.take();
         }
+
+        Ok(())
     }
 
     #[inline]
@@ -239,31 +489,51 @@ impl<F: TextureFormat> Texture<F> {
     }
 
     #[inline]
-    pub fn data_mut(&mut self) -> &mut Vec<F::Data> {
-        self.version += 1;
+    pub fn mip_level_count(&self) -> u32 {
+        self.mip_level_count
+    }
+
+    /// The pixels of the full size texture, mip levels are dropped since they'd be outdated.
+    ///
+    /// Fails if the data was [freed](Self::free_data).
+    #[inline]
+    pub fn data_mut(&mut self) -> anyhow::Result<&mut Vec<F::Data>> {
+        self.ensure_data()?;
+
+        self.version = next_version();
 
         if self.data.get().is_some() {
-            self.data.get_mut().unwrap()
+            self.mip_level_count = 1;
+
+            let data = self.data.get_mut().unwrap();
+            data.truncate(self.width as usize * self.height as usize);
+            Ok(data)
         } else {
             let _ = self.data.set(vec![
                 bytemuck::Zeroable::zeroed();
                 self.width as usize * self.height as usize
             ]);
-            self.data.get_mut().unwrap()
+            Ok(self.data.get_mut().unwrap())
         }
     }
 
+    /// The bytes of every mip level, starting with the full size one.
     #[inline]
     pub fn bytes(&self) -> Option<&[u8]> {
         self.data.get().map(|data| cast_slice(data))
     }
 
+    /// Writes the full size texture as an image.
     #[inline]
     pub fn write(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let data = self
+            .data
+            .get()
+            .ok_or_else(|| anyhow::Error::msg("no data in texture"))?;
+
         image::save_buffer(
             path,
-            self.bytes()
-                .ok_or_else(|| anyhow::Error::msg("no data in texture"))?,
+            cast_slice(&data[..self.width as usize * self.height as usize]),
             self.width,
             self.height,
             image::ColorType::Rgba8,
@@ -272,56 +542,309 @@ impl<F: TextureFormat> Texture<F> {
         Ok(())
     }
 
+    #[inline]
+    fn descriptor(&self) -> ike_wgpu::TextureDescriptor<Option<&'static str>> {
+        ike_wgpu::TextureDescriptor {
+            label: Some("texture"),
+            size: ike_wgpu::Extent3d {
+                width: self.width,
+                height: self.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: self.mip_level_count,
+            sample_count: 1,
+            dimension: ike_wgpu::TextureDimension::D2,
+            format: self.format(),
+            usage: ike_wgpu::TextureUsages::COPY_DST
+                | ike_wgpu::TextureUsages::COPY_SRC
+                | ike_wgpu::TextureUsages::TEXTURE_BINDING
+                | ike_wgpu::TextureUsages::RENDER_ATTACHMENT,
+        }
+    }
+
     #[inline]
     pub fn texture(&self) -> &ike_wgpu::Texture {
-        if let Some(texture) = self.texture.get() {
-            texture
-        } else {
-            let texture = if let Some(data) = self.bytes() {
-                let texture = render_device().create_texture_with_data(
-                    render_queue(),
-                    &ike_wgpu::TextureDescriptor {
-                        label: Some("texture"),
-                        size: ike_wgpu::Extent3d {
-                            width: self.width,
-                            height: self.height,
-                            depth_or_array_layers: 1,
-                        },
-                        mip_level_count: 1,
-                        sample_count: 1,
-                        dimension: ike_wgpu::TextureDimension::D2,
-                        format: self.format(),
-                        usage: ike_wgpu::TextureUsages::COPY_DST
-                            | ike_wgpu::TextureUsages::TEXTURE_BINDING
-                            | ike_wgpu::TextureUsages::RENDER_ATTACHMENT,
-                    },
-                    data,
-                );
-
-                texture
-            } else {
-                let texture = render_device().create_texture(&ike_wgpu::TextureDescriptor {
-                    label: Some("texture"),
-                    size: ike_wgpu::Extent3d {
-                        width: self.width,
-                        height: self.height,
-                        depth_or_array_layers: 1,
-                    },
-                    mip_level_count: 1,
-                    sample_count: 1,
-                    dimension: ike_wgpu::TextureDimension::D2,
-                    format: self.format(),
-                    usage: ike_wgpu::TextureUsages::COPY_DST
-                        | ike_wgpu::TextureUsages::TEXTURE_BINDING
-                        | ike_wgpu::TextureUsages::RENDER_ATTACHMENT,
-                });
-
-                texture
+        self.texture.get_or_init(|| {
+            let desc = self.descriptor();
+
+            let texture = match self.bytes() {
+                Some(data) => render_device().create_texture_with_data(render_queue(), &desc, data),
+                None => render_device().create_texture(&desc),
             };
 
-            self.texture.set(texture).unwrap();
+            Tracked::new(texture, texture_size(&desc))
+        })
+    }
+
+    /// Bytes of gpu memory used by the texture, 0 until it's created by [`texture`](Self::texture).
+    #[inline]
+    pub fn gpu_size(&self) -> u64 {
+        self.texture.get().map_or(0, Tracked::size)
+    }
+
+    #[inline]
+    pub fn keep_data(&self) -> bool {
+        self.keep_data
+    }
+
+    /// Keeps the data of the texture from being freed by [`free_data`](Self::free_data), for
+    /// textures read on the cpu.
+    #[inline]
+    pub fn set_keep_data(&mut self, keep_data: bool) {
+        self.keep_data = keep_data;
+    }
+
+    /// Drops the cpu copy of the data once the texture is on the gpu unless [`keep_data`](Self::keep_data)
+    /// is set, returns whether it was dropped.
+    ///
+    /// The texture can't be changed on the cpu after, until it's loaded again.
+    #[inline]
+    pub fn free_data(&mut self) -> bool {
+        if self.keep_data || self.texture.get().is_none() {
+            return false;
+        }
+
+        let freed = self.data.take().is_some();
+        self.freed |= freed;
 
-            self.texture.get().unwrap()
+        freed
+    }
+
+    /// Whether the data was dropped by [`free_data`](Self::free_data).
+    #[inline]
+    pub fn is_freed(&self) -> bool {
+        self.freed
+    }
+
+    #[inline]
+    fn ensure_data(&self) -> anyhow::Result<()> {
+        if self.freed {
+            anyhow::bail!("the data of the texture was freed, it has to be loaded again");
         }
+
+        Ok(())
+    }
+}
+
+impl<F: TextureFormat> AssetSize for Texture<F> {
+    #[inline]
+    fn cpu_size(&self) -> usize {
+        self.bytes().map_or(0, <[u8]>::len)
+    }
+
+    #[inline]
+    fn gpu_size(&self) -> usize {
+        Texture::gpu_size(self) as usize
+    }
+}
+
+pub struct TextureLoader;
+
+impl AssetLoader for TextureLoader {
+    type Asset = Texture;
+
+    #[inline]
+    fn extensions(&self) -> &[&str] {
+        &["png", "jpg", "jpeg", "bmp", "tga"]
+    }
+
+    #[inline]
+    fn load(&self, bytes: &[u8], _context: &mut LoadContext) -> anyhow::Result<Self::Asset> {
+        Texture::from_bytes(bytes)
+    }
+}
+
+pub struct HdrTextureLoader;
+
+impl AssetLoader for HdrTextureLoader {
+    type Asset = HdrTexture;
+
+    #[inline]
+    fn extensions(&self) -> &[&str] {
+        &["hdr"]
+    }
+
+    #[inline]
+    fn load(&self, bytes: &[u8], _context: &mut LoadContext) -> anyhow::Result<Self::Asset> {
+        HdrTexture::from_hdr_bytes(bytes)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct TextureImportSettings {
+    pub generate_mips: bool,
+    pub color_space: ColorSpace,
+}
+
+impl Default for TextureImportSettings {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            generate_mips: true,
+            color_space: ColorSpace::Gamma,
+        }
+    }
+}
+
+/// Imports encoded images into processed textures, which load without decoding and with
+/// their mip levels.
+///
+/// ```toml
+/// # brick_normal.png.meta
+/// color_space = "linear"
+/// ```
+pub struct TextureImporter;
+
+impl AssetImporter for TextureImporter {
+    type Settings = TextureImportSettings;
+
+    #[inline]
+    fn extensions(&self) -> &[&str] {
+        &["png", "jpg", "jpeg", "bmp", "tga"]
+    }
+
+    #[inline]
+    fn processed_extension(&self) -> &str {
+        "itex"
+    }
+
+    #[inline]
+    fn version(&self) -> u32 {
+        PROCESSED_TEXTURE_VERSION
+    }
+
+    #[inline]
+    fn import(&self, bytes: &[u8], settings: &Self::Settings) -> anyhow::Result<Vec<u8>> {
+        let mut texture = Texture::from_bytes(bytes)?;
+        texture.set_color_space(settings.color_space)?;
+
+        if settings.generate_mips {
+            texture.generate_mips();
+        }
+
+        Ok(texture.to_processed_bytes())
+    }
+}
+
+pub struct ProcessedTextureLoader;
+
+impl AssetLoader for ProcessedTextureLoader {
+    type Asset = Texture;
+
+    #[inline]
+    fn extensions(&self) -> &[&str] {
+        &["itex"]
+    }
+
+    #[inline]
+    fn load(&self, bytes: &[u8], _context: &mut LoadContext) -> anyhow::Result<Self::Asset> {
+        Texture::from_processed_bytes(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_mips() {
+        let data = vec![
+            Color8::rgba(0, 0, 0, 255),
+            Color8::rgba(4, 8, 0, 255),
+            Color8::rgba(100, 0, 0, 255),
+            Color8::rgba(8, 4, 0, 255),
+            Color8::rgba(0, 0, 0, 255),
+            Color8::rgba(100, 0, 0, 255),
+        ];
+        let mut texture = Texture::from_data(data, 3, 2);
+
+        texture.generate_mips();
+
+        assert_eq!(texture.mip_level_count(), 2);
+
+        let data = texture.data.get().unwrap();
+        assert_eq!(data.len(), 3 * 2 + 1);
+        assert_eq!(data[6], Color8::rgba(3, 3, 0, 255));
+
+        let mut texture = Texture::from_data(vec![Color8::rgba(1, 2, 3, 4); 8 * 4], 8, 4);
+
+        texture.generate_mips();
+
+        assert_eq!(texture.mip_level_count(), 4);
+        assert_eq!(texture.data.get().unwrap().len(), 32 + 8 + 2 + 1);
+        assert!(texture
+            .data
+            .get()
+            .unwrap()
+            .iter()
+            .all(|&pixel| pixel == Color8::rgba(1, 2, 3, 4)));
+
+        // regenerating replaces the previous mip levels
+        texture.generate_mips();
+
+        assert_eq!(texture.mip_level_count(), 4);
+        assert_eq!(texture.data.get().unwrap().len(), 32 + 8 + 2 + 1);
+    }
+
+    #[test]
+    fn processed_bytes() {
+        let data = (0..16).map(|i| Color8::rgba(i, i, i, 255)).collect();
+        let mut texture = Texture::from_data(data, 4, 4);
+        texture.set_color_space(ColorSpace::Linear).unwrap();
+        texture.generate_mips();
+
+        let bytes = texture.to_processed_bytes();
+        let processed = Texture::from_processed_bytes(&bytes).unwrap();
+
+        assert_eq!(processed.size(), texture.size());
+        assert_eq!(processed.mip_level_count(), 3);
+        assert_eq!(processed.color_space(), ColorSpace::Linear);
+        assert_eq!(processed.bytes(), texture.bytes());
+
+        assert!(Texture::from_processed_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Texture::from_processed_bytes(b"IKTX").is_err());
+
+        let with_mip_level_count = |mip_level_count: u32| {
+            let mut bytes = bytes.clone();
+            bytes[16..20].copy_from_slice(&mip_level_count.to_le_bytes());
+            Texture::from_processed_bytes(&bytes)
+        };
+
+        assert!(with_mip_level_count(0).is_err());
+        assert!(with_mip_level_count(4).is_err());
+        assert!(with_mip_level_count(32).is_err());
+        assert!(with_mip_level_count(u32::MAX).is_err());
+    }
+
+    #[test]
+    fn free_data() {
+        let mut texture = Texture::from_data(vec![Color8::rgba(1, 2, 3, 4); 4], 2, 2);
+
+        // the data is only freed once the texture is on the gpu
+        assert!(!texture.free_data());
+        assert!(!texture.is_freed());
+        assert_eq!(texture.data_mut().unwrap().len(), 4);
+
+        // like free_data does after the texture is created
+        texture.data.take();
+        texture.freed = true;
+
+        assert!(texture.set_color_space(ColorSpace::Linear).is_err());
+        assert!(texture.resize(4, 4).is_err());
+        assert!(texture.data_mut().is_err());
+
+        assert_eq!(texture.color_space(), ColorSpace::Gamma);
+        assert_eq!(texture.size(), UVec2::new(2, 2));
+        assert!(texture.clone().is_freed());
+
+        // textures without data that were never freed are filled with zeroes
+        let mut texture = Texture::<Rgba8Unorm>::from_size(UVec2::new(2, 1));
+
+        assert!(texture.resize(3, 1).is_ok());
+        assert_eq!(
+            texture.data_mut().unwrap(),
+            &vec![Color8::rgba(0, 0, 0, 0); 3]
+        );
     }
 }
@@ -0,0 +1,67 @@
+mod registry;
+mod script;
+mod system;
+mod world;
+
+pub use registry::*;
+pub use script::*;
+pub use system::*;
+pub use world::*;
+
+pub use rhai;
+
+use ike_assets::{AssetAppBuilderExt, AssetPath, AssetServer};
+use ike_core::*;
+use ike_transform::{GlobalTransform, Transform};
+
+pub struct ScriptPlugin;
+
+impl Plugin for ScriptPlugin {
+    #[inline]
+    fn build(self, app: &mut AppBuilder) {
+        app.add_asset::<Script>();
+        app.add_asset_loader(ScriptLoader);
+        app.init_resource::<ScriptRegistry>();
+        app.init_resource::<ScriptSystems>();
+        app.init_resource::<ScriptEvents>();
+        app.register_script_component::<Transform>();
+        app.register_script_component::<GlobalTransform>();
+        app.add_exclusive_system_to_stage(ScriptSystem, stage::UPDATE);
+    }
+}
+
+pub trait ScriptAppBuilderExt {
+    fn register_script_component<T: ScriptComponent>(&mut self) -> &mut Self;
+
+    /// Loads the script at `path`, relative to the asset root, and runs its `update` function
+    /// every frame.
+    fn add_script_system(&mut self, path: impl Into<AssetPath>) -> &mut Self;
+}
+
+impl ScriptAppBuilderExt for AppBuilder {
+    #[inline]
+    fn register_script_component<T: ScriptComponent>(&mut self) -> &mut Self {
+        self.world()
+            .write_resource::<ScriptRegistry>()
+            .expect("ScriptPlugin not added")
+            .register_component::<T>();
+
+        self
+    }
+
+    #[inline]
+    fn add_script_system(&mut self, path: impl Into<AssetPath>) -> &mut Self {
+        let handle = self
+            .world()
+            .read_resource::<AssetServer>()
+            .expect("ScriptPlugin not added")
+            .load(path);
+
+        self.world()
+            .write_resource::<ScriptSystems>()
+            .expect("ScriptPlugin not added")
+            .add(handle);
+
+        self
+    }
+}
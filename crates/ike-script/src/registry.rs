@@ -0,0 +1,262 @@
+use std::collections::HashMap;
+
+use glam::{Quat, Vec3};
+use ike_core::{AnyComponent, ComponentId, Entity, World};
+use ike_transform::{GlobalTransform, Transform};
+use rhai::{Dynamic, Engine};
+
+use crate::ScriptWorld;
+
+pub trait ScriptComponent: AnyComponent + Clone {
+    const NAME: &'static str;
+
+    fn register(engine: &mut Engine);
+}
+
+#[derive(Clone, Copy)]
+pub struct ScriptComponentFns {
+    pub entities: fn(&World) -> Vec<Entity>,
+    pub contains: fn(&World, &Entity) -> bool,
+    pub get: fn(&World, &Entity) -> Option<Dynamic>,
+    pub set: fn(&World, &Entity, Dynamic) -> bool,
+    pub insert: fn(&World, Entity, Dynamic) -> bool,
+}
+
+impl ScriptComponentFns {
+    #[inline]
+    pub fn of<T: ScriptComponent>() -> Self {
+        fn entities<T: ScriptComponent>(world: &World) -> Vec<Entity> {
+            world
+                .storage(ComponentId::of::<T>())
+                .map(|storage| storage.entities.to_vec())
+                .unwrap_or_default()
+        }
+
+        fn contains<T: ScriptComponent>(world: &World, entity: &Entity) -> bool {
+            world.contains_component::<T>(entity)
+        }
+
+        fn get<T: ScriptComponent>(world: &World, entity: &Entity) -> Option<Dynamic> {
+            let component = world.get_component::<T>(entity)?;
+
+            Some(Dynamic::from(component.clone()))
+        }
+
+        fn set<T: ScriptComponent>(world: &World, entity: &Entity, value: Dynamic) -> bool {
+            match (world.get_component_mut::<T>(entity), value.try_cast::<T>()) {
+                (Some(mut component), Some(value)) => {
+                    *component = value;
+                    true
+                }
+                _ => false,
+            }
+        }
+
+        fn insert<T: ScriptComponent>(world: &World, entity: Entity, value: Dynamic) -> bool {
+            match value.try_cast::<T>() {
+                Some(value) => {
+                    world.queue_insert(entity, value);
+                    true
+                }
+                None => false,
+            }
+        }
+
+        Self {
+            entities: entities::<T>,
+            contains: contains::<T>,
+            get: get::<T>,
+            set: set::<T>,
+            insert: insert::<T>,
+        }
+    }
+}
+
+pub struct ScriptRegistry {
+    engine: Engine,
+    components: HashMap<&'static str, ScriptComponentFns>,
+}
+
+impl Default for ScriptRegistry {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScriptRegistry {
+    #[inline]
+    pub fn new() -> Self {
+        let mut engine = Engine::new();
+
+        register_math(&mut engine);
+        ScriptWorld::register(&mut engine);
+
+        Self {
+            engine,
+            components: HashMap::new(),
+        }
+    }
+
+    #[inline]
+    pub fn engine(&self) -> &Engine {
+        &self.engine
+    }
+
+    #[inline]
+    pub fn engine_mut(&mut self) -> &mut Engine {
+        &mut self.engine
+    }
+
+    #[inline]
+    pub fn register_component<T: ScriptComponent>(&mut self) {
+        T::register(&mut self.engine);
+
+        self.components
+            .insert(T::NAME, ScriptComponentFns::of::<T>());
+    }
+
+    #[inline]
+    pub fn get_component(&self, name: &str) -> Option<&ScriptComponentFns> {
+        self.components.get(name)
+    }
+}
+
+fn register_math(engine: &mut Engine) {
+    engine
+        .register_type_with_name::<Vec3>("Vec3")
+        .register_fn("vec3", Vec3::new)
+        .register_get_set("x", |v: &mut Vec3| v.x, |v: &mut Vec3, x: f32| v.x = x)
+        .register_get_set("y", |v: &mut Vec3| v.y, |v: &mut Vec3, y: f32| v.y = y)
+        .register_get_set("z", |v: &mut Vec3| v.z, |v: &mut Vec3, z: f32| v.z = z)
+        .register_fn("+", |a: Vec3, b: Vec3| a + b)
+        .register_fn("-", |a: Vec3, b: Vec3| a - b)
+        .register_fn("*", |a: Vec3, b: f32| a * b)
+        .register_fn("*", |a: Vec3, b: Vec3| a * b)
+        .register_fn("length", |v: &mut Vec3| v.length())
+        .register_fn("normalize", |v: &mut Vec3| v.normalize_or_zero())
+        .register_fn("dot", |a: Vec3, b: Vec3| a.dot(b))
+        .register_fn("cross", |a: Vec3, b: Vec3| a.cross(b))
+        .register_fn("to_string", |v: &mut Vec3| v.to_string());
+
+    engine
+        .register_type_with_name::<Quat>("Quat")
+        .register_fn("quat_from_rotation_x", Quat::from_rotation_x)
+        .register_fn("quat_from_rotation_y", Quat::from_rotation_y)
+        .register_fn("quat_from_rotation_z", Quat::from_rotation_z)
+        .register_fn("*", |a: Quat, b: Quat| a * b)
+        .register_fn("*", |a: Quat, b: Vec3| a * b)
+        .register_fn("inverse", |q: &mut Quat| q.inverse())
+        .register_fn("to_string", |q: &mut Quat| q.to_string());
+}
+
+impl ScriptComponent for Transform {
+    const NAME: &'static str = "Transform";
+
+    #[inline]
+    fn register(engine: &mut Engine) {
+        engine
+            .register_type_with_name::<Transform>(Self::NAME)
+            .register_fn("transform", || Transform::IDENTITY)
+            .register_fn("transform_from_xyz", Transform::from_xyz)
+            .register_get_set(
+                "translation",
+                |t: &mut Transform| t.translation,
+                |t: &mut Transform, v: Vec3| t.translation = v,
+            )
+            .register_get_set(
+                "rotation",
+                |t: &mut Transform| t.rotation,
+                |t: &mut Transform, q: Quat| t.rotation = q,
+            )
+            .register_get_set(
+                "scale",
+                |t: &mut Transform| t.scale,
+                |t: &mut Transform, v: Vec3| t.scale = v,
+            )
+            .register_fn("local_x", |t: &mut Transform| t.local_x())
+            .register_fn("local_y", |t: &mut Transform| t.local_y())
+            .register_fn("local_z", |t: &mut Transform| t.local_z())
+            .register_fn("look_at", |t: &mut Transform, target: Vec3, up: Vec3| {
+                t.look_at(target, up)
+            });
+    }
+}
+
+impl ScriptComponent for GlobalTransform {
+    const NAME: &'static str = "GlobalTransform";
+
+    #[inline]
+    fn register(engine: &mut Engine) {
+        engine
+            .register_type_with_name::<GlobalTransform>(Self::NAME)
+            .register_get("translation", |t: &mut GlobalTransform| t.translation)
+            .register_get("rotation", |t: &mut GlobalTransform| t.rotation)
+            .register_get("scale", |t: &mut GlobalTransform| t.scale)
+            .register_fn("local_x", |t: &mut GlobalTransform| t.local_x())
+            .register_fn("local_y", |t: &mut GlobalTransform| t.local_y())
+            .register_fn("local_z", |t: &mut GlobalTransform| t.local_z());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn component_fns() {
+        let mut registry = ScriptRegistry::new();
+        registry.register_component::<Transform>();
+
+        assert!(registry.get_component("GlobalTransform").is_none());
+        let fns = *registry.get_component("Transform").unwrap();
+
+        let mut world = World::new();
+        let a = world.create_entity();
+        let b = world.create_entity();
+        world.insert(a, Transform::from_xyz(1.0, 2.0, 3.0));
+
+        assert_eq!((fns.entities)(&world), vec![a]);
+        assert!((fns.contains)(&world, &a));
+        assert!(!(fns.contains)(&world, &b));
+
+        let transform = (fns.get)(&world, &a).unwrap().cast::<Transform>();
+        assert_eq!(transform.translation, Vec3::new(1.0, 2.0, 3.0));
+        assert!((fns.get)(&world, &b).is_none());
+
+        let moved = Dynamic::from(Transform::from_xyz(4.0, 5.0, 6.0));
+        assert!((fns.set)(&world, &a, moved.clone()));
+        assert!(!(fns.set)(&world, &b, moved.clone()));
+        assert!(!(fns.set)(&world, &a, Dynamic::from(1.0f32)));
+        assert_eq!(
+            world.get_component::<Transform>(&a).unwrap().translation,
+            Vec3::new(4.0, 5.0, 6.0)
+        );
+
+        assert!((fns.insert)(&world, b, moved));
+        assert!(!(fns.insert)(&world, b, Dynamic::UNIT));
+        world.dequeue();
+
+        assert_eq!(
+            world.get_component::<Transform>(&b).unwrap().translation,
+            Vec3::new(4.0, 5.0, 6.0)
+        );
+    }
+
+    #[test]
+    fn math() {
+        let registry = ScriptRegistry::new();
+
+        let v: Vec3 = registry
+            .engine()
+            .eval("let v = vec3(1.0, 2.0, 3.0) * 2.0; v.x = 0.0; v")
+            .unwrap();
+        assert_eq!(v, Vec3::new(0.0, 4.0, 6.0));
+
+        let length: f32 = registry
+            .engine()
+            .eval("vec3(3.0, 4.0, 0.0).length()")
+            .unwrap();
+        assert_eq!(length, 5.0);
+    }
+}
@@ -0,0 +1,83 @@
+use std::path::{Path, PathBuf};
+
+use ike_assets::{AssetLoader, LoadContext};
+use rhai::{Engine, AST};
+
+pub struct Script {
+    path: Option<PathBuf>,
+    source: String,
+    ast: Option<AST>,
+    error: Option<String>,
+}
+
+impl Script {
+    #[inline]
+    pub fn from_source(source: impl Into<String>) -> Self {
+        Self {
+            path: None,
+            source: source.into(),
+            ast: None,
+            error: None,
+        }
+    }
+
+    #[inline]
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    #[inline]
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    #[inline]
+    pub fn set_source(&mut self, source: impl Into<String>) {
+        self.source = source.into();
+        self.ast = None;
+        self.error = None;
+    }
+
+    #[inline]
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    #[inline]
+    pub fn set_error(&mut self, error: Option<String>) {
+        self.error = error;
+    }
+
+    #[inline]
+    pub fn compile(&mut self, engine: &Engine) -> Option<&AST> {
+        if self.ast.is_none() && self.error.is_none() {
+            match engine.compile(&self.source) {
+                Ok(ast) => self.ast = Some(ast),
+                Err(err) => self.error = Some(err.to_string()),
+            }
+        }
+
+        self.ast.as_ref()
+    }
+}
+
+/// Loads `.rhai` files as [`Script`]s, which are reloaded by the [`AssetServer`](ike_assets::AssetServer)
+/// when watching for changes.
+pub struct ScriptLoader;
+
+impl AssetLoader for ScriptLoader {
+    type Asset = Script;
+
+    #[inline]
+    fn extensions(&self) -> &[&str] {
+        &["rhai"]
+    }
+
+    #[inline]
+    fn load(&self, bytes: &[u8], context: &mut LoadContext) -> anyhow::Result<Self::Asset> {
+        let mut script = Script::from_source(std::str::from_utf8(bytes)?);
+        script.path = Some(context.path().to_path_buf());
+
+        Ok(script)
+    }
+}
@@ -0,0 +1,272 @@
+use ike_assets::{AssetServer, Assets, Handle, LoadState};
+use ike_core::{ExclusiveSystem, World};
+use rhai::{Dynamic, Scope};
+
+use crate::{Script, ScriptRegistry, ScriptWorld};
+
+#[derive(Default)]
+pub struct ScriptSystems {
+    scripts: Vec<Handle<Script>>,
+}
+
+impl ScriptSystems {
+    #[inline]
+    pub fn add(&mut self, script: Handle<Script>) {
+        self.scripts.push(script);
+    }
+
+    #[inline]
+    pub fn remove(&mut self, script: &Handle<Script>) {
+        self.scripts.retain(|handle| handle != script);
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &Handle<Script>> {
+        self.scripts.iter()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ScriptEvent {
+    pub name: String,
+    pub data: Dynamic,
+}
+
+/// Events sent by scripts during the current frame.
+#[derive(Default)]
+pub struct ScriptEvents {
+    events: Vec<ScriptEvent>,
+}
+
+impl ScriptEvents {
+    #[inline]
+    pub fn send(&mut self, event: ScriptEvent) {
+        self.events.push(event);
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &ScriptEvent> {
+        self.events.iter()
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+}
+
+// logs `error` unless it's the error the script already failed with
+#[inline]
+fn report_error(script: &mut Script, error: Option<String>) {
+    if let Some(ref error) = error {
+        if script.error() != Some(error.as_str()) {
+            eprintln!("script '{}' failed: {}", script_name(script), error);
+        }
+    }
+
+    script.set_error(error);
+}
+
+#[inline]
+fn script_name(script: &Script) -> String {
+    match script.path() {
+        Some(path) => path.display().to_string(),
+        None => String::from("<source>"),
+    }
+}
+
+// scripts that failed to load are logged and removed
+#[inline]
+fn remove_failed(world: &World, handle: &Handle<Script>) {
+    let asset_server = match world.read_resource::<AssetServer>() {
+        Some(asset_server) => asset_server,
+        None => return,
+    };
+
+    if let LoadState::Failed(error) = asset_server.load_state(handle) {
+        eprintln!("{:#}", error);
+
+        world
+            .write_resource::<ScriptSystems>()
+            .unwrap()
+            .remove(handle);
+    }
+}
+
+pub struct ScriptSystem;
+
+impl ExclusiveSystem for ScriptSystem {
+    fn run(&mut self, world: &mut World) {
+        world.write_resource::<ScriptEvents>().unwrap().clear();
+
+        let handles: Vec<_> = world
+            .read_resource::<ScriptSystems>()
+            .unwrap()
+            .iter()
+            .cloned()
+            .collect();
+
+        for handle in handles {
+            let registry = world.read_resource::<ScriptRegistry>().unwrap();
+
+            let ast = {
+                let mut scripts = world.write_resource::<Assets<Script>>().unwrap();
+
+                let script = match scripts.get_mut_untracked(&handle) {
+                    Some(script) => script,
+                    None => {
+                        drop(scripts);
+                        remove_failed(world, &handle);
+                        continue;
+                    }
+                };
+
+                let had_error = script.error().is_some();
+                let ast = script.compile(registry.engine()).cloned();
+
+                if !had_error && script.error().is_some() {
+                    eprintln!(
+                        "script '{}' failed to compile: {}",
+                        script_name(script),
+                        script.error().unwrap()
+                    );
+                }
+
+                match ast {
+                    Some(ast) => ast,
+                    None => continue,
+                }
+            };
+
+            let script_world = unsafe { ScriptWorld::new(world) };
+
+            let result = registry.engine().call_fn::<Dynamic>(
+                &mut Scope::new(),
+                &ast,
+                "update",
+                (script_world,),
+            );
+
+            drop(registry);
+
+            let mut scripts = world.write_resource::<Assets<Script>>().unwrap();
+
+            if let Some(script) = scripts.get_mut_untracked(&handle) {
+                report_error(script, result.err().map(|err| err.to_string()));
+            }
+
+            drop(scripts);
+
+            world.dequeue();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{mpsc, Arc},
+        thread,
+        time::Duration,
+    };
+
+    use ike_assets::{AssetPlugin, MemoryIo};
+    use ike_core::{App, AppRunner};
+
+    use super::*;
+    use crate::{ScriptAppBuilderExt, ScriptPlugin};
+
+    struct Runner(mpsc::Sender<App>);
+
+    impl AppRunner for Runner {
+        fn run(&mut self, app: App) {
+            self.0.send(app).unwrap();
+        }
+    }
+
+    fn app(files: Arc<MemoryIo>) -> App {
+        let (sender, receiver) = mpsc::channel();
+
+        let mut app = App::new();
+        app.set_runner(Runner(sender));
+        app.add_plugin(AssetPlugin {
+            watch_for_changes: true,
+            ..Default::default()
+        });
+
+        let mut asset_server = app.world_mut().write_resource::<AssetServer>().unwrap();
+        asset_server.watch_for_changes(Duration::ZERO);
+        asset_server.mount("scripts", files);
+        drop(asset_server);
+
+        app.add_plugin(ScriptPlugin);
+        app.add_script_system("scripts/events.rhai");
+        app.add_script_system("scripts/missing.rhai");
+        app.run();
+
+        receiver.recv().unwrap()
+    }
+
+    fn frames(app: &mut App, count: usize) {
+        for _ in 0..count {
+            app.execute();
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    fn events(app: &App) -> Vec<String> {
+        let events = app.world().read_resource::<ScriptEvents>().unwrap();
+        events.iter().map(|event| event.name.clone()).collect()
+    }
+
+    fn error(app: &App) -> Option<String> {
+        let systems = app.world().read_resource::<ScriptSystems>().unwrap();
+        let scripts = app.world().read_resource::<Assets<Script>>().unwrap();
+
+        let handle = systems.iter().next().unwrap();
+        scripts.get(handle)?.error().map(String::from)
+    }
+
+    #[test]
+    fn reload() {
+        let files = Arc::new(MemoryIo::new());
+        files.insert(
+            "events.rhai",
+            &b"fn update(world) { world.send(\"first\"); }"[..],
+        );
+
+        let mut app = app(files.clone());
+        frames(&mut app, 10);
+
+        assert_eq!(events(&app), ["first"]);
+
+        // missing scripts are removed after failing to load
+        let systems = app.world().read_resource::<ScriptSystems>().unwrap();
+        assert_eq!(systems.iter().count(), 1);
+        drop(systems);
+
+        thread::sleep(Duration::from_millis(10));
+        files.insert(
+            "events.rhai",
+            &b"fn update(world) { world.send(\"second\"); }"[..],
+        );
+        frames(&mut app, 10);
+
+        assert_eq!(events(&app), ["second"]);
+        assert_eq!(error(&app), None);
+
+        thread::sleep(Duration::from_millis(10));
+        files.insert("events.rhai", &b"fn update(world) { missing(); }"[..]);
+        frames(&mut app, 10);
+
+        assert!(events(&app).is_empty());
+        assert!(error(&app).unwrap().contains("missing"));
+
+        thread::sleep(Duration::from_millis(10));
+        files.insert("events.rhai", &b"fn update(world) {"[..]);
+        frames(&mut app, 10);
+
+        assert!(events(&app).is_empty());
+        assert!(error(&app).is_some());
+    }
+}
@@ -0,0 +1,225 @@
+use ike_core::{Entity, ReadGuard, Time, World};
+use rhai::{Array, Dynamic, Engine};
+
+use crate::{ScriptComponentFns, ScriptEvent, ScriptEvents, ScriptRegistry};
+
+#[derive(Clone)]
+pub struct ScriptWorld {
+    world: *const World,
+}
+
+// SAFETY: a ScriptWorld is only created by the ScriptSystem and only lives for the duration
+// of a single script call, during which the world is kept alive and not mutably borrowed.
+unsafe impl Send for ScriptWorld {}
+unsafe impl Sync for ScriptWorld {}
+
+impl ScriptWorld {
+    /// # Safety
+    /// - `world` must outlive every use of the returned value.
+    #[inline]
+    pub unsafe fn new(world: &World) -> Self {
+        Self { world }
+    }
+
+    #[inline]
+    pub fn world(&self) -> &World {
+        unsafe { &*self.world }
+    }
+
+    #[inline]
+    fn registry(&self) -> ReadGuard<'_, ScriptRegistry> {
+        self.world()
+            .read_resource::<ScriptRegistry>()
+            .expect("ScriptRegistry not found")
+    }
+
+    #[inline]
+    fn component(&self, name: &str) -> Option<ScriptComponentFns> {
+        self.registry().get_component(name).copied()
+    }
+
+    #[inline]
+    pub fn query(&self, names: &[&str]) -> Array {
+        let components: Option<Vec<_>> = names.iter().map(|name| self.component(name)).collect();
+
+        let components = match components {
+            Some(components) => components,
+            None => return Array::new(),
+        };
+
+        let (first, rest) = match components.split_first() {
+            Some(split) => split,
+            None => return Array::new(),
+        };
+
+        (first.entities)(self.world())
+            .into_iter()
+            .filter(|entity| rest.iter().all(|fns| (fns.contains)(self.world(), entity)))
+            .map(Dynamic::from)
+            .collect()
+    }
+
+    #[inline]
+    pub fn has(&self, entity: &Entity, name: &str) -> bool {
+        self.component(name)
+            .is_some_and(|fns| (fns.contains)(self.world(), entity))
+    }
+
+    #[inline]
+    pub fn get(&self, entity: &Entity, name: &str) -> Dynamic {
+        self.component(name)
+            .and_then(|fns| (fns.get)(self.world(), entity))
+            .unwrap_or(Dynamic::UNIT)
+    }
+
+    #[inline]
+    pub fn set(&self, entity: &Entity, name: &str, value: Dynamic) -> bool {
+        self.component(name)
+            .is_some_and(|fns| (fns.set)(self.world(), entity, value))
+    }
+
+    #[inline]
+    pub fn insert(&self, entity: Entity, name: &str, value: Dynamic) -> bool {
+        self.component(name)
+            .is_some_and(|fns| (fns.insert)(self.world(), entity, value))
+    }
+
+    #[inline]
+    pub fn spawn_node(&self, name: &str) -> Entity {
+        self.world().spawn_node(name).entity()
+    }
+
+    #[inline]
+    pub fn send(&self, name: &str, data: Dynamic) {
+        if let Some(mut events) = self.world().write_resource::<ScriptEvents>() {
+            events.send(ScriptEvent {
+                name: name.into(),
+                data,
+            });
+        }
+    }
+
+    #[inline]
+    pub(crate) fn register(engine: &mut Engine) {
+        engine
+            .register_type_with_name::<Entity>("Entity")
+            .register_fn("==", |a: Entity, b: Entity| a == b)
+            .register_fn("!=", |a: Entity, b: Entity| a != b)
+            .register_fn("to_string", |e: &mut Entity| format!("{:?}", e));
+
+        engine
+            .register_type_with_name::<ScriptWorld>("World")
+            .register_fn("query", |w: &mut ScriptWorld, name: &str| w.query(&[name]))
+            .register_fn("query", |w: &mut ScriptWorld, names: Array| {
+                let names: Vec<String> = names.into_iter().map(|name| name.to_string()).collect();
+                let names: Vec<&str> = names.iter().map(String::as_str).collect();
+
+                w.query(&names)
+            })
+            .register_fn("has", |w: &mut ScriptWorld, e: Entity, name: &str| {
+                w.has(&e, name)
+            })
+            .register_fn("get", |w: &mut ScriptWorld, e: Entity, name: &str| {
+                w.get(&e, name)
+            })
+            .register_fn(
+                "set",
+                |w: &mut ScriptWorld, e: Entity, name: &str, value: Dynamic| w.set(&e, name, value),
+            )
+            .register_fn(
+                "insert",
+                |w: &mut ScriptWorld, e: Entity, name: &str, value: Dynamic| {
+                    w.insert(e, name, value)
+                },
+            )
+            .register_fn("spawn_node", |w: &mut ScriptWorld, name: &str| {
+                w.spawn_node(name)
+            })
+            .register_fn("find_node", |w: &mut ScriptWorld, name: &str| {
+                w.world()
+                    .find_node(name)
+                    .map_or(Dynamic::UNIT, |node| Dynamic::from(node.entity()))
+            })
+            .register_fn("send", |w: &mut ScriptWorld, name: &str| {
+                w.send(name, Dynamic::UNIT)
+            })
+            .register_fn("send", |w: &mut ScriptWorld, name: &str, data: Dynamic| {
+                w.send(name, data)
+            })
+            .register_get("delta_time", |w: &mut ScriptWorld| {
+                w.world()
+                    .read_resource::<Time>()
+                    .map_or(0.0, |time| time.delta_time())
+            })
+            .register_get("time", |w: &mut ScriptWorld| {
+                w.world()
+                    .read_resource::<Time>()
+                    .map_or(0.0, |time| time.time_since_startup())
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec3;
+    use ike_transform::Transform;
+    use rhai::Scope;
+
+    use super::*;
+
+    const SCRIPT: &str = r#"
+        fn update(world) {
+            for entity in world.query(["Transform", "Missing"]) {
+                world.send("unreachable");
+            }
+
+            for entity in world.query("Transform") {
+                let transform = world.get(entity, "Transform");
+                transform.translation = transform.translation + vec3(1.0, 0.0, 0.0);
+                world.set(entity, "Transform", transform);
+            }
+
+            let player = world.find_node("player");
+            world.send("has", world.has(player, "Transform"));
+            world.send("missing", world.has(player, "Missing"));
+            world.send("set", world.set(player, "Missing", 1));
+        }
+    "#;
+
+    #[test]
+    fn script_world() {
+        let mut world = World::new();
+
+        let mut registry = ScriptRegistry::new();
+        registry.register_component::<Transform>();
+        world.insert_resource(registry);
+        world.insert_resource(ScriptEvents::default());
+
+        let player = world.spawn_node("player").entity();
+        world.dequeue();
+        world.insert(player, Transform::IDENTITY);
+
+        let registry = world.read_resource::<ScriptRegistry>().unwrap();
+        let ast = registry.engine().compile(SCRIPT).unwrap();
+
+        let script_world = unsafe { ScriptWorld::new(&world) };
+        registry
+            .engine()
+            .call_fn::<()>(&mut Scope::new(), &ast, "update", (script_world,))
+            .unwrap();
+
+        drop(registry);
+
+        let transform = world.get_component::<Transform>(&player).unwrap();
+        assert_eq!(transform.translation, Vec3::X);
+        drop(transform);
+
+        let events = world.read_resource::<ScriptEvents>().unwrap();
+        let events: Vec<_> = events
+            .iter()
+            .map(|event| (event.name.as_str(), event.data.as_bool().unwrap()))
+            .collect();
+
+        assert_eq!(events, [("has", true), ("missing", false), ("set", false)]);
+    }
+}
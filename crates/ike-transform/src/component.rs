@@ -1,5 +1,5 @@
 use glam::{Mat3, Mat4, Quat, Vec3};
-use ike_core::Entity;
+use ike_core::{Entity, EntityMap, MapEntities};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Transform {
@@ -208,5 +208,27 @@ impl From<Transform> for GlobalTransform {
 #[derive(Clone, Debug)]
 pub struct Parent(pub Entity);
 
+impl MapEntities for Parent {
+    #[inline]
+    fn map_entities(&mut self, map: &EntityMap) -> bool {
+        match map.map(self.0) {
+            Some(parent) => {
+                self.0 = parent;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Children(pub Vec<Entity>);
+
+impl MapEntities for Children {
+    // children left behind are dropped from the list
+    #[inline]
+    fn map_entities(&mut self, map: &EntityMap) -> bool {
+        self.0 = self.0.iter().filter_map(|child| map.map(*child)).collect();
+        true
+    }
+}
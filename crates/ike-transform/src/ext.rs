@@ -1,9 +1,29 @@
-use ike_core::Node;
+use ike_core::{Entity, Node, World};
 
 use crate::Parent;
 
+fn find_path_from(world: &World, parent: Option<Entity>, path: &str) -> Option<Entity> {
+    let mut parents = vec![parent];
+
+    for segment in path.split('/').filter(|segment| !segment.is_empty()) {
+        parents = world
+            .find_nodes(segment)
+            .filter(|node| {
+                let parent = node.get_component::<Parent>().map(|parent| parent.0);
+
+                parents.contains(&parent)
+            })
+            .map(|node| Some(node.entity()))
+            .collect();
+    }
+
+    parents.first().copied().flatten()
+}
+
 pub trait TransformNodeExt {
     fn get_parent(&self) -> Option<Node>;
+
+    fn find_path(&self, path: &str) -> Option<Node<'_>>;
 }
 
 impl<'a> TransformNodeExt for Node<'a> {
@@ -13,4 +33,72 @@ impl<'a> TransformNodeExt for Node<'a> {
 
         self.world().get_node(parent.0)
     }
+
+    #[inline]
+    fn find_path(&self, path: &str) -> Option<Node<'_>> {
+        let entity = find_path_from(self.world(), Some(self.entity()), path)?;
+
+        self.world().get_node(entity)
+    }
+}
+
+pub trait TransformWorldExt {
+    fn find_path(&self, path: &str) -> Option<Node<'_>>;
+}
+
+impl TransformWorldExt for World {
+    #[inline]
+    fn find_path(&self, path: &str) -> Option<Node<'_>> {
+        let entity = find_path_from(self, None, path)?;
+
+        self.get_node(entity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn child(world: &mut World, parent: Entity, name: &str) -> Entity {
+        let entity = world.spawn_node(name).entity();
+        world.dequeue();
+        world.insert(entity, Parent(parent));
+        entity
+    }
+
+    #[test]
+    fn find_path() {
+        let mut world = World::new();
+
+        let root = world.spawn_node("root").entity();
+        world.dequeue();
+
+        let arm = child(&mut world, root, "arm");
+        let leg = child(&mut world, root, "leg");
+        let arm_hand = child(&mut world, arm, "hand");
+        let leg_hand = child(&mut world, leg, "hand");
+        let first = child(&mut world, arm_hand, "finger");
+        let second = child(&mut world, arm_hand, "finger");
+
+        assert_eq!(world.find_path("root").unwrap().entity(), root);
+        assert_eq!(world.find_path("root/arm/hand").unwrap().entity(), arm_hand);
+        assert_eq!(world.find_path("root/leg/hand").unwrap().entity(), leg_hand);
+        assert_eq!(world.find_path("/root//leg/").unwrap().entity(), leg);
+
+        // unparented nodes are the only roots
+        assert!(world.find_path("arm").is_none());
+        assert!(world.find_path("root/hand").is_none());
+        assert!(world.find_path("root/arm/foot").is_none());
+        assert!(world.find_path("root/missing/hand").is_none());
+
+        // the first of same name siblings
+        let finger = world.find_path("root/arm/hand/finger").unwrap().entity();
+        assert_eq!(finger, first);
+        assert_ne!(finger, second);
+
+        let arm = world.get_node(arm).unwrap();
+        assert_eq!(arm.find_path("hand/finger").unwrap().entity(), first);
+        assert!(arm.find_path("finger").is_none());
+        assert_eq!(arm.get_parent().unwrap().entity(), root);
+    }
 }
@@ -1,9 +1,11 @@
 mod component;
 mod ext;
+mod spatial;
 mod system;
 
 pub use component::*;
 pub use ext::*;
+pub use spatial::*;
 pub use system::*;
 
 use ike_core::*;
@@ -13,6 +15,8 @@ pub struct TransformPlugin;
 impl Plugin for TransformPlugin {
     #[inline]
     fn build(self, app: &mut ike_core::AppBuilder) {
+        app.world_mut().register_entity_mapper::<Parent>();
+        app.world_mut().register_entity_mapper::<Children>();
         app.add_exclusive_system_to_stage(TransformSystem, stage::POST_UPDATE);
     }
 }
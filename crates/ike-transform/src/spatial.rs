@@ -0,0 +1,512 @@
+use std::collections::HashMap;
+
+use glam::{IVec3, Vec3};
+use ike_core::*;
+
+use crate::GlobalTransform;
+
+/// Uniform grid over the translation of every [`GlobalTransform`].
+///
+/// Kept up to date by [`SpatialIndexSystem`] from `Changed<GlobalTransform>`.
+pub struct SpatialIndex {
+    cell_size: f32,
+    cells: HashMap<IVec3, Vec<Entity>>,
+    entries: HashMap<Entity, (IVec3, Vec3)>,
+    min_cell: IVec3,
+    max_cell: IVec3,
+}
+
+impl Default for SpatialIndex {
+    #[inline]
+    fn default() -> Self {
+        Self::new(10.0)
+    }
+}
+
+impl SpatialIndex {
+    #[inline]
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+            entries: HashMap::new(),
+            min_cell: IVec3::ZERO,
+            max_cell: IVec3::ZERO,
+        }
+    }
+
+    #[inline]
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    #[inline]
+    pub fn contains(&self, entity: &Entity) -> bool {
+        self.entries.contains_key(entity)
+    }
+
+    #[inline]
+    pub fn position(&self, entity: &Entity) -> Option<Vec3> {
+        self.entries.get(entity).map(|(_, position)| *position)
+    }
+
+    #[inline]
+    fn cell(&self, position: Vec3) -> IVec3 {
+        (position / self.cell_size).floor().as_ivec3()
+    }
+
+    #[inline]
+    pub fn insert(&mut self, entity: Entity, position: Vec3) {
+        let cell = self.cell(position);
+
+        match self.entries.insert(entity, (cell, position)) {
+            Some((old_cell, _)) if old_cell == cell => return,
+            Some((old_cell, _)) => self.remove_from_cell(&entity, old_cell),
+            None => {}
+        }
+
+        if self.cells.is_empty() {
+            self.min_cell = cell;
+            self.max_cell = cell;
+        } else {
+            self.min_cell = self.min_cell.min(cell);
+            self.max_cell = self.max_cell.max(cell);
+        }
+
+        self.cells.entry(cell).or_default().push(entity);
+    }
+
+    #[inline]
+    pub fn remove(&mut self, entity: &Entity) -> Option<Vec3> {
+        let (cell, position) = self.entries.remove(entity)?;
+        self.remove_from_cell(entity, cell);
+
+        Some(position)
+    }
+
+    #[inline]
+    fn remove_from_cell(&mut self, entity: &Entity, cell: IVec3) {
+        if let Some(entities) = self.cells.get_mut(&cell) {
+            entities.retain(|e| e != entity);
+
+            if entities.is_empty() {
+                self.cells.remove(&cell);
+
+                // only emptying a cell on the boundary can shrink the bounds
+                if cell.cmpeq(self.min_cell).any() || cell.cmpeq(self.max_cell).any() {
+                    self.update_bounds();
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn update_bounds(&mut self) {
+        let mut cells = self.cells.keys();
+
+        let first = match cells.next() {
+            Some(cell) => *cell,
+            None => {
+                self.min_cell = IVec3::ZERO;
+                self.max_cell = IVec3::ZERO;
+                return;
+            }
+        };
+
+        let (min_cell, max_cell) = cells.fold((first, first), |(min, max), cell| {
+            (min.min(*cell), max.max(*cell))
+        });
+
+        self.min_cell = min_cell;
+        self.max_cell = max_cell;
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.entries.clear();
+        self.update_bounds();
+    }
+
+    // calls `f` with every cell at chebyshev distance `ring` from `center`
+    #[inline]
+    fn for_each_ring_cell(center: IVec3, ring: i32, mut f: impl FnMut(IVec3)) {
+        for x in -ring..=ring {
+            for y in -ring..=ring {
+                if x.abs() == ring || y.abs() == ring {
+                    for z in -ring..=ring {
+                        f(center + IVec3::new(x, y, z));
+                    }
+                } else if ring > 0 {
+                    f(center + IVec3::new(x, y, -ring));
+                    f(center + IVec3::new(x, y, ring));
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn cell_entries(&self, cell: IVec3) -> impl Iterator<Item = (Entity, Vec3)> + '_ {
+        self.cells
+            .get(&cell)
+            .into_iter()
+            .flatten()
+            .map(move |entity| (*entity, self.entries[entity].1))
+    }
+
+    /// Returns every entity inside the box from `min` to `max` that passes `filter`.
+    #[inline]
+    pub fn within_aabb_filtered(
+        &self,
+        min: Vec3,
+        max: Vec3,
+        mut filter: impl FnMut(&Entity) -> bool,
+    ) -> Vec<Entity> {
+        let min_cell = self.cell(min).max(self.min_cell);
+        let max_cell = self.cell(max).min(self.max_cell);
+
+        let mut entities = Vec::new();
+
+        for x in min_cell.x..=max_cell.x {
+            for y in min_cell.y..=max_cell.y {
+                for z in min_cell.z..=max_cell.z {
+                    for (entity, position) in self.cell_entries(IVec3::new(x, y, z)) {
+                        let inside = position.cmpge(min).all() && position.cmple(max).all();
+
+                        if inside && filter(&entity) {
+                            entities.push(entity);
+                        }
+                    }
+                }
+            }
+        }
+
+        entities
+    }
+
+    #[inline]
+    pub fn within_aabb(&self, min: Vec3, max: Vec3) -> Vec<Entity> {
+        self.within_aabb_filtered(min, max, |_| true)
+    }
+
+    /// Returns every entity within `radius` of `point` that passes `filter`.
+    #[inline]
+    pub fn within_radius_filtered(
+        &self,
+        point: Vec3,
+        radius: f32,
+        mut filter: impl FnMut(&Entity) -> bool,
+    ) -> Vec<Entity> {
+        let extent = Vec3::splat(radius);
+
+        self.within_aabb_filtered(point - extent, point + extent, |entity| {
+            self.entries[entity].1.distance_squared(point) <= radius * radius && filter(entity)
+        })
+    }
+
+    #[inline]
+    pub fn within_radius(&self, point: Vec3, radius: f32) -> Vec<Entity> {
+        self.within_radius_filtered(point, radius, |_| true)
+    }
+
+    /// Returns the `k` entities nearest to `point` that pass `filter`, closest first.
+    #[inline]
+    pub fn nearest_filtered(
+        &self,
+        point: Vec3,
+        k: usize,
+        mut filter: impl FnMut(&Entity) -> bool,
+    ) -> Vec<Entity> {
+        if k == 0 || self.is_empty() {
+            return Vec::new();
+        }
+
+        let center = self.cell(point);
+
+        // no cell further than this ring holds any entities
+        let max_ring = (center - self.min_cell)
+            .abs()
+            .max((self.max_cell - center).abs())
+            .max_element();
+
+        let mut found: Vec<(f32, Entity)> = Vec::new();
+
+        for ring in 0..=max_ring {
+            // once rings have more cells than are occupied, the occupied ones are checked instead
+            if 6 * (2 * ring as usize + 1).pow(2) > self.cells.len() {
+                for (cell, entities) in &self.cells {
+                    if (*cell - center).abs().max_element() < ring {
+                        continue;
+                    }
+
+                    for entity in entities {
+                        if filter(entity) {
+                            let position = self.entries[entity].1;
+                            found.push((position.distance_squared(point), *entity));
+                        }
+                    }
+                }
+
+                break;
+            }
+
+            Self::for_each_ring_cell(center, ring, |cell| {
+                for (entity, position) in self.cell_entries(cell) {
+                    if filter(&entity) {
+                        found.push((position.distance_squared(point), entity));
+                    }
+                }
+            });
+
+            // every entity outside the searched rings is at least this far away
+            let searched = ring as f32 * self.cell_size;
+
+            if found.len() >= k {
+                found.select_nth_unstable_by(k - 1, |a, b| a.0.total_cmp(&b.0));
+                found.truncate(k);
+
+                if found[k - 1].0 <= searched * searched {
+                    break;
+                }
+            }
+        }
+
+        found.sort_by(|a, b| a.0.total_cmp(&b.0));
+        found
+            .into_iter()
+            .take(k)
+            .map(|(_, entity)| entity)
+            .collect()
+    }
+
+    #[inline]
+    pub fn nearest(&self, point: Vec3, k: usize) -> Vec<Entity> {
+        self.nearest_filtered(point, k, |_| true)
+    }
+}
+
+/// Returns a filter for [`SpatialIndex`] queries accepting entities with a `T` component.
+#[inline]
+pub fn with_component<T: AnyComponent>(world: &World) -> impl Fn(&Entity) -> bool + '_ {
+    move |entity| world.contains_component::<T>(entity)
+}
+
+pub struct SpatialIndexSystem;
+
+impl ExclusiveSystem for SpatialIndexSystem {
+    #[inline]
+    fn run(&mut self, world: &mut World) {
+        let mut index = match world.write_resource::<SpatialIndex>() {
+            Some(index) => index,
+            None => return,
+        };
+
+        for (entity, global_transform) in world
+            .query::<(Entity, &GlobalTransform), Changed<GlobalTransform>>()
+            .unwrap()
+        {
+            index.insert(entity, global_transform.translation);
+        }
+
+        let storage = world.storage(ComponentId::of::<GlobalTransform>());
+        let entities = storage.as_ref().map_or(&[][..], |storage| storage.entities);
+
+        // global transforms inserted without being seen as changed
+        for entity in entities {
+            if !index.contains(entity) {
+                let global_transform = world.get_component::<GlobalTransform>(entity).unwrap();
+                index.insert(*entity, global_transform.translation);
+            }
+        }
+
+        // every entity with a global transform is indexed, so any extra entries were removed
+        if index.len() > entities.len() {
+            let removed: Vec<Entity> = index
+                .entries
+                .keys()
+                .filter(|entity| !world.contains_component::<GlobalTransform>(entity))
+                .copied()
+                .collect();
+
+            for entity in removed {
+                index.remove(&entity);
+            }
+        }
+    }
+}
+
+/// Maintains a [`SpatialIndex`] resource, should be added after [`TransformPlugin`](crate::TransformPlugin).
+#[derive(Default)]
+pub struct SpatialIndexPlugin {
+    pub cell_size: Option<f32>,
+}
+
+impl Plugin for SpatialIndexPlugin {
+    #[inline]
+    fn build(self, app: &mut AppBuilder) {
+        match self.cell_size {
+            Some(cell_size) => app.insert_resource(SpatialIndex::new(cell_size)),
+            None => app.init_resource::<SpatialIndex>(),
+        };
+
+        app.add_exclusive_system_to_stage(SpatialIndexSystem, stage::POST_UPDATE);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Transform;
+
+    fn random_index(world: &mut World) -> (SpatialIndex, Vec<(Entity, Vec3)>) {
+        let mut rng = Rng::new(7);
+        let mut index = SpatialIndex::new(2.0);
+        let mut entries = Vec::new();
+
+        for _ in 0..500 {
+            let entity = world.create_entity();
+            let position = Vec3::new(
+                rng.range_f32(-40.0, 40.0),
+                rng.range_f32(-40.0, 40.0),
+                rng.range_f32(-40.0, 40.0),
+            );
+
+            index.insert(entity, position);
+            entries.push((entity, position));
+        }
+
+        (index, entries)
+    }
+
+    fn sorted(mut entities: Vec<Entity>) -> Vec<Entity> {
+        entities.sort();
+        entities
+    }
+
+    #[test]
+    fn aabb_and_radius() {
+        let mut world = World::new();
+        let (index, entries) = random_index(&mut world);
+
+        let min = Vec3::new(-10.0, -5.0, 0.0);
+        let max = Vec3::new(12.0, 20.0, 7.5);
+
+        let expected: Vec<_> = entries
+            .iter()
+            .filter(|(_, p)| p.cmpge(min).all() && p.cmple(max).all())
+            .map(|(entity, _)| *entity)
+            .collect();
+        assert_eq!(sorted(index.within_aabb(min, max)), sorted(expected));
+
+        let point = Vec3::new(3.0, -1.0, 4.0);
+
+        let expected: Vec<_> = entries
+            .iter()
+            .filter(|(_, p)| p.distance(point) <= 15.0)
+            .map(|(entity, _)| *entity)
+            .collect();
+        assert!(!expected.is_empty());
+        assert_eq!(sorted(index.within_radius(point, 15.0)), sorted(expected));
+
+        // outside the indexed bounds
+        assert!(index.within_radius(Vec3::splat(1000.0), 10.0).is_empty());
+    }
+
+    #[test]
+    fn nearest() {
+        let mut world = World::new();
+        let (index, entries) = random_index(&mut world);
+
+        for point in [Vec3::ZERO, Vec3::new(35.0, -20.0, 5.0), Vec3::splat(300.0)] {
+            let mut expected = entries.clone();
+            expected.sort_by(|a, b| a.1.distance(point).total_cmp(&b.1.distance(point)));
+
+            let expected: Vec<_> = expected.iter().take(10).map(|(e, _)| *e).collect();
+            assert_eq!(index.nearest(point, 10), expected);
+        }
+
+        assert!(index.nearest(Vec3::ZERO, 0).is_empty());
+        assert_eq!(index.nearest(Vec3::ZERO, 1000).len(), 500);
+    }
+
+    #[test]
+    fn filtered() {
+        let mut world = World::new();
+        let (index, entries) = random_index(&mut world);
+
+        let marked: Vec<_> = entries.iter().step_by(7).map(|(e, _)| *e).collect();
+
+        for entity in &marked {
+            world.insert(*entity, true);
+        }
+
+        let nearest = index.nearest_filtered(Vec3::ZERO, 5, with_component::<bool>(&world));
+        assert_eq!(nearest.len(), 5);
+        assert!(nearest.iter().all(|entity| marked.contains(entity)));
+
+        let mut expected: Vec<_> = entries.iter().filter(|(e, _)| marked.contains(e)).collect();
+        expected.sort_by(|a, b| a.1.length().total_cmp(&b.1.length()));
+        assert_eq!(nearest[0], expected[0].0);
+
+        let within =
+            index.within_radius_filtered(Vec3::ZERO, 30.0, |entity| marked.contains(entity));
+        assert!(within.iter().all(|entity| marked.contains(entity)));
+        assert!(within.len() < index.within_radius(Vec3::ZERO, 30.0).len());
+    }
+
+    #[test]
+    fn bounds_shrink() {
+        let mut world = World::new();
+        let a = world.create_entity();
+        let b = world.create_entity();
+
+        let mut index = SpatialIndex::new(1.0);
+        index.insert(a, Vec3::ZERO);
+        index.insert(b, Vec3::splat(1000.5));
+        assert_eq!(index.max_cell, IVec3::splat(1000));
+
+        index.remove(&b);
+        assert_eq!(index.max_cell, IVec3::ZERO);
+
+        index.insert(a, Vec3::splat(-3.5));
+        assert_eq!(index.min_cell, IVec3::splat(-4));
+        assert_eq!(index.max_cell, IVec3::splat(-4));
+
+        index.clear();
+        assert_eq!(index.min_cell, IVec3::ZERO);
+    }
+
+    #[test]
+    fn system() {
+        let mut world = World::new();
+        world.insert_resource(SpatialIndex::new(1.0));
+
+        let a = world.create_entity();
+        let b = world.create_entity();
+        world.insert(a, GlobalTransform::from(Transform::from_xyz(1.0, 0.0, 0.0)));
+
+        SpatialIndexSystem.run(&mut world);
+        world.clear_trackers();
+
+        // a removal and an insertion in the same frame, which the system runs after
+        world.remove::<GlobalTransform>(&a);
+        world.insert(b, GlobalTransform::from(Transform::from_xyz(2.0, 0.0, 0.0)));
+        world.clear_trackers();
+
+        SpatialIndexSystem.run(&mut world);
+
+        let index = world.read_resource::<SpatialIndex>().unwrap();
+        assert!(!index.contains(&a));
+        assert_eq!(index.position(&b), Some(Vec3::new(2.0, 0.0, 0.0)));
+    }
+}
@@ -27,8 +27,8 @@ pub use pipeline::{
 };
 pub use queue::Queue;
 pub use render_pass::{
-    LoadOp, Operations, RenderPass, RenderPassColorAttachment, RenderPassDepthStencilAttachment,
-    RenderPassDescriptor,
+    take_draw_stats, DrawStats, LoadOp, Operations, RenderPass, RenderPassColorAttachment,
+    RenderPassDepthStencilAttachment, RenderPassDescriptor,
 };
 pub use sampler::{Sampler, SamplerDescriptor};
 pub use shader::{ShaderModule, ShaderModuleDescriptor, ShaderSource};
@@ -1,4 +1,34 @@
-use std::ops::Range;
+use std::{
+    ops::Range,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+static DRAW_CALLS: AtomicU64 = AtomicU64::new(0);
+static TRIANGLES: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DrawStats {
+    pub draw_calls: u64,
+    pub triangles: u64,
+}
+
+/// Returns the draws recorded by every [`RenderPass`] since the last call,
+/// triangles are counted as if every draw used a triangle list.
+#[inline]
+pub fn take_draw_stats() -> DrawStats {
+    DrawStats {
+        draw_calls: DRAW_CALLS.swap(0, Ordering::Relaxed),
+        triangles: TRIANGLES.swap(0, Ordering::Relaxed),
+    }
+}
+
+#[inline]
+fn record_draw(vertices: &Range<u32>, instances: &Range<u32>) {
+    let triangles = vertices.len() as u64 / 3 * instances.len() as u64;
+
+    DRAW_CALLS.fetch_add(1, Ordering::Relaxed);
+    TRIANGLES.fetch_add(triangles, Ordering::Relaxed);
+}
 
 #[derive(Clone)]
 pub enum LoadOp<V> {
@@ -140,11 +170,13 @@ impl<'a> RenderPass<'a> {
 
     #[inline]
     pub fn draw(&mut self, vertices: Range<u32>, instances: Range<u32>) {
+        record_draw(&vertices, &instances);
         self.0.draw(vertices, instances);
     }
 
     #[inline]
     pub fn draw_indexed(&mut self, indices: Range<u32>, base_vertex: i32, instances: Range<u32>) {
+        record_draw(&indices, &instances);
         self.0.draw_indexed(indices, base_vertex, instances);
     }
 }
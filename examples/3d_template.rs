@@ -95,16 +95,11 @@ struct Material(Handle<PbrMaterial>, Handle<Mesh>);
 fn setup(
     commands: Commands,
     mut main_camera: ResMut<MainCamera>,
-    mut envs: ResMut<Assets<Environment>>,
+    asset_server: Res<AssetServer>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<PbrMaterial>>,
 ) {
-    let hdr = HdrTexture::load("assets/env.hdr").unwrap();
-
-    let mut env = Environment::default();
-    env.load(&hdr);
-
-    let env = envs.add(env);
+    let env = asset_server.load::<Environment>("env.hdr");
     commands.insert_resource(env);
 
     let node = commands.spawn_node("Camera");
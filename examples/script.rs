@@ -0,0 +1,42 @@
+use ike::prelude::*;
+use ike_script::*;
+
+fn setup(
+    commands: Commands,
+    mut main_camera: ResMut<MainCamera>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<PbrMaterial>>,
+) {
+    let camera = commands.spawn_node("camera");
+
+    let mut transform = Transform::from_xyz(2.0, 2.0, 2.0);
+    transform.look_at(Vec3::ZERO, Vec3::Y);
+
+    camera.insert(PerspectiveProjection::default());
+    camera.insert(transform);
+
+    main_camera.0 = Some(camera.entity());
+
+    let cube = commands.spawn_node("cube");
+
+    cube.insert(Transform::IDENTITY);
+    cube.insert(meshes.add(Mesh::cube(Vec3::ONE / 2.0)));
+    cube.insert(materials.add(PbrMaterial::default()));
+}
+
+fn main() {
+    App::new()
+        .set_runner(WinitRunner)
+        .add_plugin(AssetPlugin {
+            watch_for_changes: true,
+            ..Default::default()
+        })
+        .add_plugin(RenderPlugin)
+        .add_plugin(DebugLinePlugin)
+        .add_plugin(PbrPlugin)
+        .add_plugin(TransformPlugin)
+        .add_plugin(ScriptPlugin)
+        .add_script_system("scripts/rotate.rhai")
+        .add_startup_system(setup.system())
+        .run();
+}
@@ -10,11 +10,17 @@ pub use ike_winit as winit;
 
 pub mod prelude {
     pub use glam::*;
-    pub use ike_assets::{AssetAppBuilderExt, Assets, Handle, HandleUntyped};
+    pub use ike_assets::{
+        embedded_asset, ArchiveIo, AssetAppBuilderExt, AssetEvent, AssetEventReader, AssetImporter,
+        AssetIo, AssetLoader, AssetPath, AssetPlugin, AssetServer, AssetSize, Assets, DirectoryIo,
+        Handle, HandleUntyped, LoadContext, LoadState, MemoryIo,
+    };
     pub use ike_core::{
-        App, AppBuilder, Commands, Component, ExclusiveSystem, FnSystem, HasId, Id, Node,
-        QueryMut as Query, ReadGuard, Res, ResMut, Resources, Schedule, System, Time, Without,
-        World, WriteGuard, Changed
+        App, AppBuilder, Changed, Commands, Component, CsvDiagnosticsPlugin, Diagnostics,
+        ExclusiveSystem, FnSystem, FrameDiagnosticsPlugin, HasId, Id, LogDiagnosticsPlugin, Name,
+        Node, QueryMut as Query, ReadGuard, Relation, Relations, Res, ResMut, Resources, Rng,
+        Schedule, Stopwatch, System, Time, Timer, TimerMode, TimerPlugin, Without, World,
+        WriteGuard,
     };
     pub use ike_debug_line::{DebugLine, DebugLinePlugin};
     pub use ike_input::{Input, Mouse};
@@ -26,7 +32,8 @@ pub mod prelude {
         PerspectiveProjection, RenderCtx, RenderGraph, RenderNode, RenderPlugin, Shader, Texture,
     };
     pub use ike_transform::{
-        GlobalTransform, Parent, Transform, TransformNodeExt, TransformPlugin,
+        GlobalTransform, Parent, SpatialIndex, SpatialIndexPlugin, Transform, TransformNodeExt,
+        TransformPlugin, TransformWorldExt,
     };
     pub use ike_wgpu as wgpu;
     pub use ike_winit::{Key, MouseButton, Window, WinitRunner};